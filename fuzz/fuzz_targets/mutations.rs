@@ -0,0 +1,56 @@
+//! Applies a byte-encoded sequence of public mutations to a `Graph` and to
+//! the `NaiveGraph` reference model, checking invariants and agreement after
+//! every step.
+#![no_main]
+use labeledgraph::{Graph, NaiveGraph};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut graph = Graph::new();
+    let mut model = NaiveGraph::new();
+
+    for op in data.chunks(3) {
+        let vertices = model.vertices();
+        let pick = |byte: u8| vertices[byte as usize % vertices.len()];
+        let (a, b) = (op.get(1).cloned().unwrap_or(0), op.get(2).cloned().unwrap_or(0));
+        match op[0] % 7 {
+            0 => assert_eq!(graph.insert(a), model.insert(a)),
+            _ if vertices.is_empty() => continue,
+            1 => {
+                let vertex = pick(a);
+                assert_eq!(graph.remove(vertex), model.remove(vertex));
+            }
+            2 => {
+                let (src, dst) = (pick(a), pick(b));
+                assert_eq!(graph.connect(src, dst), model.connect(src, dst));
+            }
+            3 => {
+                let edge = (pick(a), pick(b));
+                assert_eq!(graph.disconnect(edge), model.disconnect(edge));
+            }
+            4 => {
+                let vertex = pick(a);
+                assert_eq!(graph.append_label(vertex, b), model.append_label(vertex, b));
+            }
+            5 => {
+                let vertex = pick(a);
+                assert_eq!(
+                    graph.remove_vertex_label(&b, vertex),
+                    model.remove_vertex_label(&b, vertex)
+                );
+            }
+            _ => {
+                let mut members = vec![pick(a)];
+                if pick(b) != members[0] {
+                    members.push(pick(b));
+                }
+                assert_eq!(
+                    graph.merge_vertices(members.clone()),
+                    model.merge_vertices(members)
+                );
+            }
+        }
+        graph.check_invariants().unwrap();
+        model.compare(&graph).unwrap();
+    }
+});
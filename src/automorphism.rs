@@ -0,0 +1,266 @@
+use crate::{Graph, UnionFind, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Dense copy of a graph used by the individualization-refinement search.
+/// Colors are always renumbered by sorting their signatures so that they are
+/// invariant under automorphisms.
+struct Symmetry {
+    vertices: Vec<VertexIndex>,
+    succs: Vec<Vec<usize>>,
+    preds: Vec<Vec<usize>>,
+    edges: HashSet<(usize, usize)>,
+    initial: Vec<usize>,
+}
+
+fn rank<T: Ord + Clone>(signatures: &[T]) -> Vec<usize> {
+    let mut sorted = signatures.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    signatures
+        .iter()
+        .map(|signature| sorted.binary_search(signature).unwrap())
+        .collect()
+}
+
+fn cell_sizes(colors: &[usize]) -> Vec<usize> {
+    let mut sizes = vec![0; colors.len()];
+    for &color in colors.iter() {
+        sizes[color] += 1;
+    }
+    sizes
+}
+
+fn target_cell(colors: &[usize]) -> Option<Vec<usize>> {
+    let sizes = cell_sizes(colors);
+    let color = sizes.iter().position(|&size| size > 1)?;
+    Some((0..colors.len()).filter(|&i| colors[i] == color).collect())
+}
+
+fn individualize(colors: &[usize], vertex: usize) -> Vec<usize> {
+    let signatures: Vec<(usize, bool)> = colors
+        .iter()
+        .enumerate()
+        .map(|(i, &color)| (color, i != vertex))
+        .collect();
+    rank(&signatures)
+}
+
+impl Symmetry {
+    fn new<V: Eq + Hash + Clone>(graph: &Graph<V>) -> Self {
+        let vertices: Vec<VertexIndex> = graph.nodes.iter().map(|(vertex, _)| vertex).collect();
+        let dense: HashMap<VertexIndex, usize> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let labels: HashMap<&V, usize> = graph
+            .aliases
+            .keys()
+            .enumerate()
+            .map(|(id, label)| (label, id))
+            .collect();
+
+        let mut succs = vec![Vec::new(); vertices.len()];
+        let mut preds = vec![Vec::new(); vertices.len()];
+        let mut edges = HashSet::new();
+        let mut signatures = Vec::with_capacity(vertices.len());
+        for (i, &vertex) in vertices.iter().enumerate() {
+            let node = &graph.nodes[vertex];
+            for dst in node.posset.iter() {
+                succs[i].push(dense[dst]);
+                preds[dense[dst]].push(i);
+                edges.insert((i, dense[dst]));
+            }
+            let mut signature: Vec<usize> =
+                node.aliases.iter().map(|label| labels[label]).collect();
+            signature.sort_unstable();
+            signatures.push(signature);
+        }
+
+        Symmetry {
+            vertices,
+            succs,
+            preds,
+            edges,
+            initial: rank(&signatures),
+        }
+    }
+
+    /// Splits cells by neighbor color counts until the partition is equitable.
+    fn refine(&self, mut colors: Vec<usize>) -> Vec<usize> {
+        let mut count = cell_sizes(&colors).iter().filter(|&&size| size > 0).count();
+        loop {
+            let signatures: Vec<(usize, Vec<usize>, Vec<usize>)> = (0..colors.len())
+                .map(|i| {
+                    let mut succs: Vec<usize> = self.succs[i].iter().map(|&j| colors[j]).collect();
+                    let mut preds: Vec<usize> = self.preds[i].iter().map(|&j| colors[j]).collect();
+                    succs.sort_unstable();
+                    preds.sort_unstable();
+                    (colors[i], succs, preds)
+                })
+                .collect();
+            colors = rank(&signatures);
+            let refined = colors.iter().max().map_or(0, |&max| max + 1);
+            if refined == count {
+                return colors;
+            }
+            count = refined;
+        }
+    }
+
+    /// Permutation taking the vertex colored `c` in `first` to the vertex
+    /// colored `c` in `leaf`, if it is an automorphism.
+    fn leaf_automorphism(&self, first: &[usize], leaf: &[usize]) -> Option<Vec<usize>> {
+        let mut position = vec![0; leaf.len()];
+        for (i, &color) in leaf.iter().enumerate() {
+            position[color] = i;
+        }
+        let perm: Vec<usize> = first.iter().map(|&color| position[color]).collect();
+        let preserved = (0..perm.len()).all(|i| self.initial[i] == self.initial[perm[i]])
+            && self
+                .edges
+                .iter()
+                .all(|&(src, dst)| self.edges.contains(&(perm[src], perm[dst])));
+        if preserved {
+            Some(perm)
+        } else {
+            None
+        }
+    }
+
+    /// Depth-first search below `colors` for a leaf equivalent to the first
+    /// leaf, pruning nodes whose cell sizes differ from the first path.
+    fn find_automorphism(&self, colors: Vec<usize>, path: &[Vec<usize>]) -> Option<Vec<usize>> {
+        if cell_sizes(&colors) != cell_sizes(&path[0]) {
+            return None;
+        }
+        match target_cell(&colors) {
+            None => self.leaf_automorphism(path.last().unwrap(), &colors),
+            Some(cell) => cell.into_iter().find_map(|vertex| {
+                self.find_automorphism(self.refine(individualize(&colors, vertex)), &path[1..])
+            }),
+        }
+    }
+
+    /// Generators of the automorphism group, found level by level from the
+    /// bottom of the first path, together with the orbits they induce.
+    fn search(&self) -> (Vec<Vec<usize>>, UnionFind) {
+        let mut path = vec![self.refine(self.initial.clone())];
+        let mut cells = Vec::new();
+        while let Some(cell) = target_cell(path.last().unwrap()) {
+            let next = self.refine(individualize(path.last().unwrap(), cell[0]));
+            cells.push(cell);
+            path.push(next);
+        }
+
+        let mut orbits = UnionFind::default();
+        for i in 0..self.vertices.len() {
+            orbits.make_set(i);
+        }
+        let mut generators = Vec::new();
+        for (level, cell) in cells.iter().enumerate().rev() {
+            for &vertex in cell[1..].iter() {
+                if orbits.find(vertex) == orbits.find(cell[0]) {
+                    continue;
+                }
+                let start = self.refine(individualize(&path[level], vertex));
+                if let Some(perm) = self.find_automorphism(start, &path[level + 1..]) {
+                    for (i, &image) in perm.iter().enumerate() {
+                        orbits.union(i, image);
+                    }
+                    generators.push(perm);
+                }
+            }
+        }
+
+        (generators, orbits)
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Generators of the group of vertex permutations that preserve edges and
+    /// label sets. Each generator maps every vertex to its image; an empty
+    /// result means the graph is asymmetric.
+    pub fn automorphism_generators(&self) -> Vec<HashMap<VertexIndex, VertexIndex>> {
+        let symmetry = Symmetry::new(self);
+        let (generators, _) = symmetry.search();
+        generators
+            .into_iter()
+            .map(|perm| {
+                perm.iter()
+                    .enumerate()
+                    .map(|(i, &image)| (symmetry.vertices[i], symmetry.vertices[image]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Partition of the vertices into orbits of the automorphism group, i.e.
+    /// classes of interchangeable vertices. Singleton orbits are included.
+    pub fn automorphism_orbits(&self) -> Vec<Vec<VertexIndex>> {
+        let symmetry = Symmetry::new(self);
+        let (_, mut orbits) = symmetry.search();
+        let mut classes: HashMap<usize, Vec<VertexIndex>> = HashMap::new();
+        for (i, &vertex) in symmetry.vertices.iter().enumerate() {
+            classes.entry(orbits.find(i)).or_default().push(vertex);
+        }
+        classes.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn automorphism_generators() {
+        // Three labeled arms hanging off a hub are interchangeable.
+        let mut graph = Graph::new();
+        let hub = graph.insert("hub");
+        let arms: Vec<(VertexIndex, VertexIndex)> = (0..3)
+            .map(|_| {
+                let near = graph.insert("near");
+                let far = graph.insert("far");
+                graph.connect(hub, near);
+                graph.connect(near, far);
+                (near, far)
+            })
+            .collect();
+
+        let generators = graph.automorphism_generators();
+        assert_eq!(generators.len(), 2);
+        for perm in generators.iter() {
+            assert_eq!(perm[&hub], hub);
+            for &(near, far) in arms.iter() {
+                assert!(graph.posset(perm[&near]).unwrap().any(|v| v == perm[&far]));
+            }
+        }
+
+        graph.append_label(arms[0].1, "marked");
+        let generators = graph.automorphism_generators();
+        assert_eq!(generators.len(), 1);
+        assert_eq!(generators[0][&arms[0].0], arms[0].0);
+
+        graph.connect(arms[1].1, arms[2].0);
+        assert!(graph.automorphism_generators().is_empty());
+    }
+
+    #[test]
+    fn automorphism_orbits() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..4).map(|_| graph.insert("v")).collect();
+        for i in 0..4 {
+            graph.connect(vertices[i], vertices[(i + 1) % 4]);
+        }
+        let tail = graph.insert("v");
+        graph.connect(tail, vertices[0]);
+
+        let mut orbits = graph.automorphism_orbits();
+        assert_eq!(orbits.len(), 5);
+
+        graph.disconnect((tail, vertices[0]));
+        orbits = graph.automorphism_orbits();
+        orbits.sort_by_key(|orbit| orbit.len());
+        assert_eq!(orbits.len(), 2);
+        assert_eq!(orbits[0], vec![tail]);
+        assert_eq!(orbits[1].len(), 4);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::VertexIndex;
+
+const WORD_BITS: usize = 64;
+
+#[derive(Default, Clone, Debug)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    #[inline]
+    pub fn new() -> Self {
+        BitSet { words: Vec::new() }
+    }
+
+    #[inline]
+    pub fn grow(&mut self, bits: usize) {
+        let words = bits.div_ceil(WORD_BITS);
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+    }
+
+    #[inline]
+    pub fn insert(&mut self, index: VertexIndex) -> bool {
+        let (word, bit) = (index / WORD_BITS, index % WORD_BITS);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let old = self.words[word];
+        self.words[word] |= 1 << bit;
+        old != self.words[word]
+    }
+
+    #[inline]
+    pub fn remove(&mut self, index: VertexIndex) -> bool {
+        let (word, bit) = (index / WORD_BITS, index % WORD_BITS);
+        match self.words.get_mut(word) {
+            None => false,
+            Some(word) => {
+                let old = *word;
+                *word &= !(1 << bit);
+                old != *word
+            }
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, index: VertexIndex) -> bool {
+        self.words
+            .get(index / WORD_BITS)
+            .is_some_and(|word| word & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    #[inline]
+    pub fn union_with(&mut self, other: &BitSet) {
+        self.grow(other.words.len() * WORD_BITS);
+        for (word, &bits) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= bits;
+        }
+    }
+
+    #[inline]
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (pos, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(pos).cloned().unwrap_or(0);
+        }
+    }
+
+    /// Set bits in increasing order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = VertexIndex> + '_ {
+        self.words.iter().enumerate().flat_map(|(pos, &word)| {
+            (0..WORD_BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| pos * WORD_BITS + bit)
+        })
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::hash::Hash;
+
+const UNVISITED: usize = usize::MAX;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Bridges and articulation points of the underlying undirected simple
+    /// graph, found with Tarjan's low-link search. Bridges are returned as
+    /// `(lower, higher)` vertex pairs.
+    fn cut_structure(&self) -> (Vec<(VertexIndex, VertexIndex)>, Vec<VertexIndex>) {
+        let mut discovered = vec![UNVISITED; self.nodes.capacity()];
+        let mut low = vec![UNVISITED; self.nodes.capacity()];
+        let mut bridges = Vec::new();
+        let mut cuts = Vec::new();
+        let mut time = 0;
+
+        for (root, _) in self.nodes.iter() {
+            if discovered[root] != UNVISITED {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            let mut stack = vec![(root, root, self.undirected_neighbors(root))];
+            while let Some((vertex, parent, neighbors)) = stack.last_mut() {
+                let (vertex, parent) = (*vertex, *parent);
+                match neighbors.next() {
+                    Some(next) if next == parent => (),
+                    Some(next) if discovered[next] != UNVISITED => {
+                        low[vertex] = low[vertex].min(discovered[next]);
+                    }
+                    Some(next) => {
+                        discovered[next] = time;
+                        low[next] = time;
+                        time += 1;
+                        if vertex == root {
+                            root_children += 1;
+                        }
+                        stack.push((next, vertex, self.undirected_neighbors(next)));
+                    }
+                    None => {
+                        stack.pop();
+                        if vertex == root {
+                            continue;
+                        }
+                        low[parent] = low[parent].min(low[vertex]);
+                        if low[vertex] > discovered[parent] {
+                            bridges.push((parent.min(vertex), parent.max(vertex)));
+                        }
+                        if parent != root && low[vertex] >= discovered[parent] {
+                            cuts.push(parent);
+                        }
+                    }
+                }
+            }
+            if root_children > 1 {
+                cuts.push(root);
+            }
+        }
+
+        bridges.sort_unstable();
+        cuts.sort_unstable();
+        cuts.dedup();
+        (bridges, cuts)
+    }
+
+    /// Edges whose removal, together with any edge running the opposite
+    /// way, disconnects their endpoints when directions are ignored. Both
+    /// directions are listed when present. Sorted.
+    pub fn bridges(&self) -> Vec<EdgeIndex> {
+        let _span = trace_span!("bridges", vertices = self.nodes.len());
+        let mut edges = Vec::new();
+        for (one, other) in self.cut_structure().0 {
+            if self.nodes[one].posset.contains(&other) {
+                edges.push((one, other));
+            }
+            if self.nodes[other].posset.contains(&one) {
+                edges.push((other, one));
+            }
+        }
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Vertices whose removal splits their weakly connected component.
+    /// Sorted.
+    pub fn articulation_points(&self) -> Vec<VertexIndex> {
+        let _span = trace_span!("articulation_points", vertices = self.nodes.len());
+        self.cut_structure().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn bridges_and_articulation_points() {
+        // A triangle a-b-c hanging from d, which leads on to e and f.
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        let f = graph.insert("f");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+        graph.connect(c, d);
+        graph.connect(d, c);
+        graph.connect(e, d);
+        graph.connect(d, f);
+        graph.connect(f, f);
+
+        assert_eq!(graph.bridges(), vec![(c, d), (d, c), (d, f), (e, d)]);
+        assert_eq!(graph.articulation_points(), vec![c, d]);
+
+        graph.connect(e, f);
+        assert_eq!(graph.bridges(), vec![(c, d), (d, c)]);
+        assert_eq!(graph.articulation_points(), vec![c, d]);
+        assert!(Graph::<u8>::new().bridges().is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+struct Class {
+    start: usize,
+    end: usize,
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    fn lex_bfs_order(&self, start: Option<VertexIndex>) -> Vec<VertexIndex> {
+        let mut seq: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        if let Some(first) = start.and_then(|start| seq.iter().position(|&v| v == start)) {
+            seq.swap(0, first);
+        }
+        let mut pos: HashMap<VertexIndex, usize> =
+            seq.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let mut class_of: HashMap<VertexIndex, usize> = seq.iter().map(|&v| (v, 0)).collect();
+        let mut classes = vec![Class {
+            start: 0,
+            end: seq.len(),
+        }];
+
+        for i in 0..seq.len() {
+            let vertex = seq[i];
+            classes[class_of[&vertex]].start += 1;
+            let mut split: HashMap<usize, usize> = HashMap::new();
+            for other in self.undirected_neighbors(vertex) {
+                if pos[&other] <= i {
+                    continue;
+                }
+                let old = class_of[&other];
+                let new = *split.entry(old).or_insert_with(|| {
+                    let start = classes[old].start;
+                    classes.push(Class { start, end: start });
+                    classes.len() - 1
+                });
+                let target = classes[old].start;
+                let displaced = seq[target];
+                let from = pos[&other];
+                seq.swap(target, from);
+                pos.insert(displaced, from);
+                pos.insert(other, target);
+                classes[old].start += 1;
+                classes[new].end += 1;
+                class_of.insert(other, new);
+            }
+        }
+
+        seq
+    }
+
+    /// Checks that the reverse of `order` is a perfect elimination ordering.
+    fn is_elimination_ordering(&self, order: &[VertexIndex]) -> bool {
+        let rank: HashMap<VertexIndex, usize> =
+            order.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        order.iter().all(|&vertex| {
+            let earlier: Vec<VertexIndex> = self
+                .undirected_neighbors(vertex)
+                .filter(|other| rank[other] < rank[&vertex])
+                .collect();
+            match earlier.iter().max_by_key(|other| rank[other]) {
+                None => true,
+                Some(&parent) => {
+                    let adjacent: HashSet<VertexIndex> =
+                        self.undirected_neighbors(parent).collect();
+                    earlier
+                        .iter()
+                        .all(|other| *other == parent || adjacent.contains(other))
+                }
+            }
+        })
+    }
+
+    /// Lexicographic breadth-first order of the undirected projection,
+    /// computed by partition refinement in linear time. The order begins at
+    /// `start` and continues through every other component.
+    #[inline]
+    pub fn lex_bfs(&self, start: VertexIndex) -> Option<Vec<VertexIndex>> {
+        if self.nodes.contains(start) {
+            Some(self.lex_bfs_order(Some(start)))
+        } else {
+            None
+        }
+    }
+
+    /// An ordering in which every vertex's later neighbors form a clique, or
+    /// `None` if the undirected projection is not chordal.
+    pub fn perfect_elimination_ordering(&self) -> Option<Vec<VertexIndex>> {
+        let mut order = self.lex_bfs_order(None);
+        if self.is_elimination_ordering(&order) {
+            order.reverse();
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the undirected projection is chordal, i.e. every cycle longer
+    /// than three has a chord.
+    #[inline]
+    pub fn is_chordal(&self) -> bool {
+        self.is_elimination_ordering(&self.lex_bfs_order(None))
+    }
+
+    /// Whether the undirected projection is an interval graph, tested as a
+    /// chordal graph without asteroidal triples (Lekkerkerker and Boland).
+    /// Takes cubic time in the number of vertices.
+    pub fn is_interval_graph(&self) -> bool {
+        if !self.is_chordal() {
+            return false;
+        }
+
+        let vertices: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        let adjacent: HashMap<VertexIndex, HashSet<VertexIndex>> = vertices
+            .iter()
+            .map(|&v| (v, self.undirected_neighbors(v).collect()))
+            .collect();
+        // Component labels of the graph minus the closed neighborhood of each vertex.
+        let avoiding: HashMap<VertexIndex, HashMap<VertexIndex, usize>> = vertices
+            .iter()
+            .map(|&center| {
+                let mut label = HashMap::new();
+                for &root in vertices.iter() {
+                    if root == center
+                        || adjacent[&center].contains(&root)
+                        || label.contains_key(&root)
+                    {
+                        continue;
+                    }
+                    let mut stack = vec![root];
+                    label.insert(root, root);
+                    while let Some(vertex) = stack.pop() {
+                        for &next in adjacent[&vertex].iter() {
+                            if next != center
+                                && !adjacent[&center].contains(&next)
+                                && !label.contains_key(&next)
+                            {
+                                label.insert(next, root);
+                                stack.push(next);
+                            }
+                        }
+                    }
+                }
+                (center, label)
+            })
+            .collect();
+        let joined = |x: VertexIndex, y: VertexIndex, z: VertexIndex| {
+            let label = &avoiding[&z];
+            label.get(&x).is_some() && label.get(&x) == label.get(&y)
+        };
+
+        for (i, &x) in vertices.iter().enumerate() {
+            for (j, &y) in vertices.iter().enumerate().skip(i + 1) {
+                if adjacent[&x].contains(&y) {
+                    continue;
+                }
+                for &z in vertices.iter().skip(j + 1) {
+                    if adjacent[&z].contains(&x) || adjacent[&z].contains(&y) {
+                        continue;
+                    }
+                    if joined(x, y, z) && joined(y, z, x) && joined(x, z, y) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn chordal_and_interval() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(d, a);
+        assert!(!graph.is_chordal());
+        assert!(!graph.is_interval_graph());
+        assert_eq!(graph.perfect_elimination_ordering(), None);
+
+        graph.connect(a, c);
+        assert!(graph.is_chordal());
+        assert!(graph.is_interval_graph());
+
+        // A subdivided claw is chordal but has an asteroidal triple.
+        let mut claw = Graph::new();
+        let center = claw.insert(0);
+        for _ in 0..3 {
+            let middle = claw.insert(1);
+            let tip = claw.insert(2);
+            claw.connect(center, middle);
+            claw.connect(tip, middle);
+        }
+        assert!(claw.is_chordal());
+        assert_eq!(claw.perfect_elimination_ordering().unwrap().len(), 7);
+        assert!(!claw.is_interval_graph());
+    }
+
+    #[test]
+    fn lex_bfs() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(c, a);
+        graph.connect(b, d);
+
+        let order = graph.lex_bfs(d).unwrap();
+        assert_eq!(order[..4], [d, b, a, c]);
+        assert_eq!(order[4], e);
+        assert_eq!(graph.lex_bfs(42), None);
+    }
+}
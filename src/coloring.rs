@@ -0,0 +1,144 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// The two color classes of the graph viewed undirected, or `None` if it
+    /// has an odd cycle; a self-loop counts as one. Each component is colored
+    /// starting from its lowest index, which goes to the first class.
+    pub fn is_bipartite(&self) -> Option<(HashSet<VertexIndex>, HashSet<VertexIndex>)> {
+        let mut side: HashMap<VertexIndex, bool> = HashMap::with_capacity(self.nodes.len());
+        for (root, _) in self.nodes.iter() {
+            if side.contains_key(&root) {
+                continue;
+            }
+            side.insert(root, false);
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            while let Some(vertex) = queue.pop_front() {
+                if self.nodes[vertex].posset.contains(&vertex) {
+                    return None;
+                }
+                let color = side[&vertex];
+                for next in self.undirected_neighbors(vertex) {
+                    match side.get(&next) {
+                        Some(&other) if other == color => return None,
+                        Some(_) => (),
+                        None => {
+                            side.insert(next, !color);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (second, first): (Vec<_>, Vec<_>) = side.into_iter().partition(|&(_, color)| color);
+        Some((
+            first.into_iter().map(|(vertex, _)| vertex).collect(),
+            second.into_iter().map(|(vertex, _)| vertex).collect(),
+        ))
+    }
+
+    /// Colors vertices greedily so that no two neighbors share a color, edges
+    /// taken as undirected conflicts and self-loops ignored. Vertices are
+    /// visited in `order`, skipping unknown and repeated ones and finishing
+    /// with any left out, or by decreasing degree if no order is given.
+    /// Returns the color of every vertex and the number of colors used.
+    pub fn greedy_coloring(
+        &self,
+        order: Option<&[VertexIndex]>,
+    ) -> (HashMap<VertexIndex, usize>, usize) {
+        let mut by_degree: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        by_degree
+            .sort_by_key(|&vertex| std::cmp::Reverse(self.undirected_neighbors(vertex).count()));
+        let order = order
+            .unwrap_or(&[])
+            .iter()
+            .cloned()
+            .filter(|&vertex| self.nodes.contains(vertex))
+            .chain(by_degree);
+
+        let mut colors: HashMap<VertexIndex, usize> = HashMap::with_capacity(self.nodes.len());
+        let mut used = 0;
+        let mut taken = Vec::new();
+        for vertex in order {
+            if colors.contains_key(&vertex) {
+                continue;
+            }
+            taken.clear();
+            taken.resize(used + 1, false);
+            for next in self.undirected_neighbors(vertex) {
+                if let Some(&color) = colors.get(&next) {
+                    taken[color] = true;
+                }
+            }
+            let color = taken.iter().position(|&taken| !taken).unwrap();
+            used = used.max(color + 1);
+            colors.insert(vertex, color);
+        }
+        (colors, used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn bipartite() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(c, b);
+        graph.connect(c, d);
+        graph.connect(d, a);
+
+        let (first, second) = graph.is_bipartite().unwrap();
+        assert_eq!(first, vec![a, c, e].into_iter().collect());
+        assert_eq!(second, vec![b, d].into_iter().collect());
+
+        graph.connect(a, c);
+        assert!(graph.is_bipartite().is_none());
+        graph.disconnect((a, c));
+        graph.connect(e, e);
+        assert!(graph.is_bipartite().is_none());
+        assert_eq!(
+            Graph::<&str>::new().is_bipartite(),
+            Some((HashSet::new(), HashSet::new()))
+        );
+    }
+
+    #[test]
+    fn greedy_coloring() {
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..6).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)].iter() {
+            graph.connect(v[src], v[dst]);
+        }
+        graph.connect(v[5], v[5]);
+
+        let (colors, used) = graph.greedy_coloring(None);
+        assert_eq!(used, 2);
+        assert_eq!(colors.len(), 6);
+        for (src, node) in graph.nodes.iter() {
+            for &dst in node.posset.iter().filter(|&&dst| dst != src) {
+                assert_ne!(colors[&src], colors[&dst]);
+            }
+        }
+
+        let order = [v[0], v[3], 42, v[0], v[1]];
+        let (colors, used) = graph.greedy_coloring(Some(&order));
+        assert_eq!(used, 3);
+        assert_eq!((colors[&v[0]], colors[&v[3]], colors[&v[1]]), (0, 0, 1));
+        assert_eq!(colors[&v[2]], 2);
+        assert_eq!(
+            Graph::<u8>::new().greedy_coloring(None),
+            (HashMap::new(), 0)
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// Version of the `CsrHeader` layout, bumped on any incompatible change.
+pub const CSR_LAYOUT_VERSION: u32 = 1;
+
+/// Fixed header at the start of a `GpuCsr` buffer. The layout is part of the
+/// public ABI: eight native-endian `u32` words, 32 bytes, so it can be bound
+/// directly as a uniform or read by a kernel from word zero.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsrHeader {
+    /// Always `CSR_LAYOUT_VERSION`.
+    pub version: u32,
+    pub vertex_count: u32,
+    pub edge_count: u32,
+    /// Word offset of the `vertex_count + 1` row offsets.
+    pub offsets_start: u32,
+    /// Word offset of the `edge_count` column indices.
+    pub targets_start: u32,
+    pub reserved: [u32; 3],
+}
+
+const HEADER_WORDS: usize = 8;
+
+/// Compressed sparse row snapshot of the successor lists packed into one
+/// contiguous `u32` buffer: the header, then the row offsets, then the
+/// targets. Vertices are renumbered densely in index order and each row is
+/// sorted. The buffer can be uploaded as is, e.g. through
+/// `bytemuck::cast_slice(csr.as_words())`.
+#[derive(Clone, Debug)]
+pub struct GpuCsr {
+    words: Vec<u32>,
+    vertices: Vec<VertexIndex>,
+}
+
+impl GpuCsr {
+    #[inline]
+    pub fn header(&self) -> CsrHeader {
+        let w = &self.words;
+        CsrHeader {
+            version: w[0],
+            vertex_count: w[1],
+            edge_count: w[2],
+            offsets_start: w[3],
+            targets_start: w[4],
+            reserved: [w[5], w[6], w[7]],
+        }
+    }
+
+    /// The whole buffer, header included.
+    #[inline]
+    pub fn as_words(&self) -> &[u32] {
+        &self.words
+    }
+
+    /// Successors of dense vertex `i` are `targets()[offsets()[i]..offsets()[i + 1]]`.
+    #[inline]
+    pub fn offsets(&self) -> &[u32] {
+        let start = HEADER_WORDS;
+        &self.words[start..start + self.vertices.len() + 1]
+    }
+
+    #[inline]
+    pub fn targets(&self) -> &[u32] {
+        &self.words[HEADER_WORDS + self.vertices.len() + 1..]
+    }
+
+    /// Graph index of dense vertex `dense`.
+    #[inline]
+    pub fn vertex(&self, dense: u32) -> Option<VertexIndex> {
+        self.vertices.get(dense as usize).cloned()
+    }
+
+    /// Dense number of graph vertex `vertex`.
+    #[inline]
+    pub fn dense(&self, vertex: VertexIndex) -> Option<u32> {
+        self.vertices
+            .binary_search(&vertex)
+            .ok()
+            .map(|pos| pos as u32)
+    }
+}
+
+/// String table accompanying a `GpuCsr`, so the export can be consumed
+/// without the original graph. Every distinct label is stored once, numbered
+/// in sorted order, and the labels of dense vertex `i` are the string ids
+/// `entries()[offsets()[i]..offsets()[i + 1]]`, sorted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsrLabels {
+    strings: Vec<String>,
+    ids: HashMap<String, u32>,
+    offsets: Vec<u32>,
+    entries: Vec<u32>,
+}
+
+impl CsrLabels {
+    /// Every distinct label, indexed by string id.
+    #[inline]
+    pub fn strings(&self) -> &[String] {
+        &self.strings
+    }
+
+    #[inline]
+    pub fn string(&self, id: u32) -> Option<&str> {
+        self.strings.get(id as usize).map(|string| string.as_str())
+    }
+
+    #[inline]
+    pub fn string_id(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).cloned()
+    }
+
+    #[inline]
+    pub fn offsets(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[u32] {
+        &self.entries
+    }
+
+    /// Labels of dense vertex `dense`.
+    pub fn labels(&self, dense: u32) -> Option<impl Iterator<Item = &str> + '_> {
+        let dense = dense as usize;
+        if dense + 1 >= self.offsets.len() {
+            return None;
+        }
+        let row = self.offsets[dense] as usize..self.offsets[dense + 1] as usize;
+        Some(
+            self.entries[row]
+                .iter()
+                .map(move |&id| self.strings[id as usize].as_str()),
+        )
+    }
+
+    /// Dense vertices carrying `label`, in increasing order.
+    pub fn vertices_labeled(&self, label: &str) -> Vec<u32> {
+        let id = match self.string_id(label) {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        (0..self.offsets.len() - 1)
+            .filter(|&dense| {
+                let row = self.offsets[dense] as usize..self.offsets[dense + 1] as usize;
+                self.entries[row].binary_search(&id).is_ok()
+            })
+            .map(|dense| dense as u32)
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Exports the successor lists as a `GpuCsr`. `None` if the buffer would
+    /// not be addressable with `u32` offsets.
+    pub fn to_gpu_csr(&self) -> Option<GpuCsr> {
+        let _span = trace_span!("to_gpu_csr", vertices = self.nodes.len());
+        let vertices: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        let edges: usize = self.nodes.iter().map(|(_, node)| node.posset.len()).sum();
+        let total = HEADER_WORDS + vertices.len() + 1 + edges;
+        if total > u32::MAX as usize {
+            return None;
+        }
+
+        let mut dense = vec![0u32; self.nodes.capacity()];
+        for (pos, &vertex) in vertices.iter().enumerate() {
+            dense[vertex] = pos as u32;
+        }
+        let mut words = Vec::with_capacity(total);
+        words.extend_from_slice(&[
+            CSR_LAYOUT_VERSION,
+            vertices.len() as u32,
+            edges as u32,
+            HEADER_WORDS as u32,
+            (HEADER_WORDS + vertices.len() + 1) as u32,
+            0,
+            0,
+            0,
+        ]);
+        let mut offset = 0u32;
+        words.push(offset);
+        for &vertex in vertices.iter() {
+            offset += self.nodes[vertex].posset.len() as u32;
+            words.push(offset);
+        }
+        for &vertex in vertices.iter() {
+            let row = words.len();
+            words.extend(self.nodes[vertex].posset.iter().map(|&dst| dense[dst]));
+            words[row..].sort_unstable();
+        }
+
+        Some(GpuCsr { words, vertices })
+    }
+
+    /// Like `to_gpu_csr`, along with the `CsrLabels` string table of the
+    /// labels of each dense vertex.
+    pub fn to_gpu_csr_labeled(&self) -> Option<(GpuCsr, CsrLabels)>
+    where
+        V: Display,
+    {
+        let csr = self.to_gpu_csr()?;
+        let rows: Vec<Vec<String>> = csr
+            .vertices
+            .iter()
+            .map(|&vertex| {
+                self.nodes[vertex]
+                    .aliases
+                    .iter()
+                    .map(|label| label.to_string())
+                    .collect()
+            })
+            .collect();
+        let strings: Vec<String> = rows
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        if strings.len() > u32::MAX as usize {
+            return None;
+        }
+        let ids: HashMap<String, u32> = strings
+            .iter()
+            .enumerate()
+            .map(|(id, string)| (string.clone(), id as u32))
+            .collect();
+
+        let mut offsets = Vec::with_capacity(rows.len() + 1);
+        let mut entries = Vec::new();
+        offsets.push(0);
+        for row in rows.iter() {
+            // Distinct labels may display alike, so dedup within the row.
+            let mut row: Vec<u32> = row.iter().map(|label| ids[label]).collect();
+            row.sort_unstable();
+            row.dedup();
+            entries.extend(row);
+            if entries.len() > u32::MAX as usize {
+                return None;
+            }
+            offsets.push(entries.len() as u32);
+        }
+
+        Some((
+            csr,
+            CsrLabels {
+                strings,
+                ids,
+                offsets,
+                entries,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn gpu_csr() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let gone = graph.insert("gone");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.remove(gone);
+        graph.connect(a, c);
+        graph.connect(a, b);
+        graph.connect(c, a);
+
+        let csr = graph.to_gpu_csr().unwrap();
+        let header = csr.header();
+        assert_eq!(header.version, CSR_LAYOUT_VERSION);
+        assert_eq!((header.vertex_count, header.edge_count), (3, 3));
+        assert_eq!(csr.offsets(), &[0, 2, 2, 3]);
+        assert_eq!(csr.targets(), &[1, 2, 0]);
+        assert_eq!(
+            &csr.as_words()[header.targets_start as usize..],
+            csr.targets()
+        );
+        assert_eq!(csr.vertex(2), Some(c));
+        assert_eq!(csr.dense(b), Some(1));
+        assert_eq!(csr.dense(gone), None);
+        assert_eq!(std::mem::size_of::<CsrHeader>(), 32);
+    }
+
+    #[test]
+    fn labeled_csr() {
+        let mut graph = Graph::new();
+        let a = graph.insert("x");
+        let b = graph.insert("y");
+        let c = graph.insert("x");
+        graph.append_label(b, "a");
+        graph.connect(a, b);
+        graph.remove(c);
+        let c = graph.insert("z");
+
+        let (csr, labels) = graph.to_gpu_csr_labeled().unwrap();
+        assert_eq!(labels.strings(), &["a", "x", "y", "z"]);
+        assert_eq!(csr.vertex(1), Some(b));
+        assert_eq!(
+            labels.labels(1).unwrap().collect::<Vec<_>>(),
+            vec!["a", "y"]
+        );
+        assert_eq!(labels.string_id("x"), Some(1));
+        assert_eq!(labels.string(3), Some("z"));
+        assert_eq!(labels.vertices_labeled("x"), vec![csr.dense(a).unwrap()]);
+        assert_eq!(labels.vertices_labeled("z"), vec![csr.dense(c).unwrap()]);
+        assert_eq!(labels.offsets(), &[0, 1, 3, 4]);
+        assert!(labels.labels(3).is_none());
+        assert!(labels.vertices_labeled("w").is_empty());
+    }
+
+    #[test]
+    fn adjacent_rows_share_label() {
+        let mut graph = Graph::new();
+        let a = graph.insert("x");
+        let b = graph.insert("x");
+        graph.append_label(b, "y");
+
+        let (csr, labels) = graph.to_gpu_csr_labeled().unwrap();
+        let (a, b) = (csr.dense(a).unwrap(), csr.dense(b).unwrap());
+        assert_eq!(
+            labels.labels(b).unwrap().collect::<Vec<_>>(),
+            vec!["x", "y"]
+        );
+        assert_eq!(labels.vertices_labeled("x"), vec![a, b]);
+        assert_eq!(labels.offsets(), &[0, 1, 3]);
+    }
+}
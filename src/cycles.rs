@@ -0,0 +1,314 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+fn pop_remaining(
+    stack: &mut Vec<VertexIndex>,
+    remaining: &HashSet<VertexIndex>,
+) -> Option<VertexIndex> {
+    while let Some(vertex) = stack.pop() {
+        if remaining.contains(&vertex) {
+            return Some(vertex);
+        }
+    }
+    None
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Fundamental cycle basis with respect to a breadth-first spanning
+    /// forest of the undirected projection, with every directed edge counted
+    /// as a separate undirected edge. Each cycle lists its edges in walk
+    /// order starting with the non-tree edge, which is walked from source to
+    /// destination; an edge appearing reversed relative to the walk is
+    /// traversed against its orientation.
+    pub fn cycle_basis(&self) -> Vec<Vec<EdgeIndex>> {
+        let mut parent: HashMap<VertexIndex, Option<(VertexIndex, EdgeIndex)>> = HashMap::new();
+        let mut depth: HashMap<VertexIndex, usize> = HashMap::new();
+        let mut tree: HashSet<EdgeIndex> = HashSet::new();
+
+        for (root, _) in self.nodes.iter() {
+            if parent.contains_key(&root) {
+                continue;
+            }
+            parent.insert(root, None);
+            depth.insert(root, 0);
+            let mut queue = vec![root];
+            let mut head = 0;
+            while head < queue.len() {
+                let vertex = queue[head];
+                head += 1;
+                let node = &self.nodes[vertex];
+                let outgoing = node.posset.iter().map(|&dst| (dst, (vertex, dst)));
+                let incoming = node.preset.iter().map(|&src| (src, (src, vertex)));
+                for (next, edge) in outgoing.chain(incoming) {
+                    if let Entry::Vacant(entry) = parent.entry(next) {
+                        entry.insert(Some((vertex, edge)));
+                        depth.insert(next, depth[&vertex] + 1);
+                        tree.insert(edge);
+                        queue.push(next);
+                    }
+                }
+            }
+        }
+
+        let mut basis = Vec::new();
+        for (src, node) in self.nodes.iter() {
+            for &dst in node.posset.iter() {
+                if tree.contains(&(src, dst)) {
+                    continue;
+                }
+                // Climb from both endpoints to their lowest common ancestor.
+                let mut down = vec![(src, dst)];
+                let mut up = Vec::new();
+                let (mut a, mut b) = (dst, src);
+                while a != b {
+                    if depth[&a] >= depth[&b] {
+                        let (next, edge) = parent[&a].unwrap();
+                        down.push(edge);
+                        a = next;
+                    } else {
+                        let (next, edge) = parent[&b].unwrap();
+                        up.push(edge);
+                        b = next;
+                    }
+                }
+                down.extend(up.into_iter().rev());
+                basis.push(down);
+            }
+        }
+
+        basis
+    }
+
+    /// A small set of edges whose removal leaves the graph acyclic, found
+    /// with the greedy heuristic of Eades, Lin and Smyth: vertices are peeled
+    /// off as sinks, sources or by largest outdegree surplus into a linear
+    /// order, and every edge pointing backwards in it, self-loops included,
+    /// is returned, sorted. Reversing the edges instead of removing them also
+    /// leaves the graph acyclic. Vertices wait in buckets keyed by surplus,
+    /// so the whole pass runs in linear time.
+    pub fn feedback_arc_set(&self) -> Vec<EdgeIndex> {
+        let degree = |vertex: VertexIndex, set: &HashSet<VertexIndex>| {
+            set.iter().filter(|&&other| other != vertex).count()
+        };
+        let mut indegree: HashMap<VertexIndex, usize> = HashMap::with_capacity(self.nodes.len());
+        let mut outdegree: HashMap<VertexIndex, usize> = HashMap::with_capacity(self.nodes.len());
+        let mut sinks = Vec::new();
+        let mut sources = Vec::new();
+        // Bucket `out + offset - in` holds the vertices with that surplus;
+        // entries left behind by degree changes are skipped when popped.
+        let offset = self.nodes.len();
+        let bucket = |outdegree: usize, indegree: usize| outdegree + offset - indegree;
+        let mut buckets: Vec<Vec<VertexIndex>> = vec![Vec::new(); 2 * offset + 1];
+        let mut top = 0;
+        for (vertex, node) in self.nodes.iter() {
+            indegree.insert(vertex, degree(vertex, &node.preset));
+            outdegree.insert(vertex, degree(vertex, &node.posset));
+            if outdegree[&vertex] == 0 {
+                sinks.push(vertex);
+            } else if indegree[&vertex] == 0 {
+                sources.push(vertex);
+            }
+            let surplus = bucket(outdegree[&vertex], indegree[&vertex]);
+            buckets[surplus].push(vertex);
+            top = top.max(surplus);
+        }
+
+        let mut remaining: HashSet<VertexIndex> = indegree.keys().cloned().collect();
+        let mut head = Vec::with_capacity(remaining.len());
+        let mut tail = Vec::new();
+        while !remaining.is_empty() {
+            let vertex = if let Some(sink) = pop_remaining(&mut sinks, &remaining) {
+                tail.push(sink);
+                sink
+            } else {
+                let vertex = match pop_remaining(&mut sources, &remaining) {
+                    Some(source) => source,
+                    None => loop {
+                        match buckets[top].pop() {
+                            Some(vertex)
+                                if remaining.contains(&vertex)
+                                    && bucket(outdegree[&vertex], indegree[&vertex]) == top =>
+                            {
+                                break vertex
+                            }
+                            Some(_) => {}
+                            None => top -= 1,
+                        }
+                    },
+                };
+                head.push(vertex);
+                vertex
+            };
+
+            remaining.remove(&vertex);
+            let node = &self.nodes[vertex];
+            for &next in node.posset.iter().filter(|next| remaining.contains(next)) {
+                let count = indegree.get_mut(&next).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    sources.push(next);
+                }
+                let surplus = bucket(outdegree[&next], indegree[&next]);
+                buckets[surplus].push(next);
+                top = top.max(surplus);
+            }
+            for &prev in node.preset.iter().filter(|prev| remaining.contains(prev)) {
+                let count = outdegree.get_mut(&prev).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    sinks.push(prev);
+                }
+                buckets[bucket(outdegree[&prev], indegree[&prev])].push(prev);
+            }
+        }
+
+        let position: HashMap<VertexIndex, usize> = head
+            .into_iter()
+            .chain(tail.into_iter().rev())
+            .enumerate()
+            .map(|(pos, vertex)| (vertex, pos))
+            .collect();
+        let mut arcs: Vec<EdgeIndex> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .filter(|&(src, dst)| position[&src] >= position[&dst])
+            .collect();
+        arcs.sort_unstable();
+        arcs
+    }
+
+    /// Orients every edge of the undirected projection from the earlier to
+    /// the later endpoint in `order`, leaving the graph acyclic so DAG-only
+    /// algorithms apply. Vertices missing from `order`, or all of them if it
+    /// is `None`, follow in index order. Edges keep their protection and kind
+    /// when reversed, opposite edges collapse into one and self-loops are
+    /// removed unless protected. Returns how many edges were reversed or
+    /// removed.
+    pub fn orient_acyclically(&mut self, order: Option<&[VertexIndex]>) -> usize {
+        let mut position: HashMap<VertexIndex, usize> = HashMap::with_capacity(self.nodes.len());
+        let given = order.unwrap_or(&[]).iter().cloned();
+        for vertex in given.chain(self.nodes.iter().map(|(vertex, _)| vertex)) {
+            if self.nodes.contains(vertex) && !position.contains_key(&vertex) {
+                position.insert(vertex, position.len());
+            }
+        }
+
+        let backwards: Vec<EdgeIndex> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .filter(|(src, dst)| position[src] >= position[dst])
+            .collect();
+        let mut changed = 0;
+        for (src, dst) in backwards {
+            if src != dst {
+                self.reverse((src, dst));
+                changed += 1;
+            } else if !self.protected.contains(&(src, dst)) {
+                self.unlink((src, dst));
+                changed += 1;
+            }
+        }
+        self.debug_check_invariants();
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn cycle_basis() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+        graph.connect(c, d);
+        graph.connect(d, c);
+        graph.connect(e, e);
+
+        let basis = graph.cycle_basis();
+        assert_eq!(basis.len(), 6 - 5 + 2);
+        assert!(basis.contains(&vec![(e, e)]));
+        for cycle in basis.iter() {
+            // Consecutive edges share an endpoint and the walk closes up.
+            let mut at = cycle[0].1;
+            for &(src, dst) in cycle[1..].iter() {
+                at = if at == src {
+                    dst
+                } else {
+                    assert_eq!(at, dst);
+                    src
+                };
+            }
+            assert_eq!(at, cycle[0].0);
+        }
+    }
+
+    #[test]
+    fn feedback_arc_set() {
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..6).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 2),
+            (4, 5),
+            (5, 5),
+        ]
+        .iter()
+        {
+            graph.connect(v[src], v[dst]);
+        }
+
+        let arcs = graph.feedback_arc_set();
+        assert_eq!(arcs.len(), 3);
+        assert!(arcs.contains(&(v[5], v[5])));
+        let mut flipped = graph.clone();
+        for &edge in arcs.iter() {
+            if edge.0 != edge.1 {
+                flipped.flip_edge(edge);
+            }
+        }
+        flipped.disconnect((v[5], v[5]));
+        assert!(flipped.topological_order().is_some());
+        for &edge in arcs.iter() {
+            graph.disconnect(edge);
+        }
+        assert!(graph.topological_order().is_some());
+        assert!(graph.feedback_arc_set().is_empty());
+    }
+
+    #[test]
+    fn orient_acyclically() {
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 2), (3, 3)].iter() {
+            graph.connect(v[src], v[dst]);
+        }
+        graph.protect_edge((v[2], v[0]));
+
+        assert_eq!(graph.orient_acyclically(None), 3);
+        assert!(graph.topological_order().is_some());
+        assert_eq!(graph.is_protected((v[0], v[2])), Some(true));
+        assert_eq!(graph.posset(v[2]).unwrap().sorted(), vec![v[3]]);
+
+        let order = [v[3], v[1]];
+        assert_eq!(graph.orient_acyclically(Some(&order)), 2);
+        assert_eq!(graph.posset(v[3]).unwrap().sorted(), vec![v[2]]);
+        assert_eq!(graph.posset(v[1]).unwrap().sorted(), vec![v[0], v[2]]);
+        assert_eq!(graph.orient_acyclically(Some(&order)), 0);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{BitSet, Graph, VertexIndex};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Diffusion process run by `simulate_spread` along edge directions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpreadModel {
+    /// Independent cascade: each newly active vertex gets a single chance
+    /// to activate each successor with probability `p`.
+    IndependentCascade { p: f64 },
+    /// Discrete-time SIR: every round each infected vertex infects each
+    /// susceptible successor with probability `infection`, then recovers
+    /// with probability `recovery`. Recovered vertices stay immune.
+    Sir { infection: f64, recovery: f64 },
+}
+
+impl SpreadModel {
+    /// Per-contact infection and per-round recovery probabilities, or `None`
+    /// unless both lie in [0, 1] and recovery is positive, without which an
+    /// outbreak would never end.
+    fn probabilities(&self) -> Option<(f64, f64)> {
+        let (infection, recovery) = match *self {
+            SpreadModel::IndependentCascade { p } => (p, 1.0),
+            SpreadModel::Sir {
+                infection,
+                recovery,
+            } => (infection, recovery),
+        };
+        if (0.0..=1.0).contains(&infection) && recovery > 0.0 && recovery <= 1.0 {
+            Some((infection, recovery))
+        } else {
+            None
+        }
+    }
+}
+
+/// Outcome of one simulated outbreak.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpreadRun {
+    /// Every vertex ever activated, seeds first, in activation order.
+    pub reached: Vec<VertexIndex>,
+    /// Rounds until no vertex was left active.
+    pub rounds: usize,
+}
+
+/// Aggregate of several independent runs from the same seeds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpreadSummary {
+    pub runs: usize,
+    pub mean_reached: f64,
+    pub max_reached: usize,
+    /// Fraction of runs in which each vertex was activated; vertices never
+    /// reached are omitted.
+    pub probability: HashMap<VertexIndex, f64>,
+}
+
+/// Marginal gain of adding a vertex to the seed set, computed when the set
+/// had `round` members. Ordered by gain, then preferring lower indices.
+struct Gain {
+    gain: f64,
+    vertex: VertexIndex,
+    round: usize,
+}
+
+impl Ord for Gain {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.gain
+            .total_cmp(&other.gain)
+            .then_with(|| other.vertex.cmp(&self.vertex))
+    }
+}
+
+impl PartialOrd for Gain {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Gain {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Gain {}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Runs one outbreak from `seeds`. Successors are tried in index order,
+    /// so a seeded `rng` gives reproducible runs. `None` if a seed is
+    /// missing or the model's probabilities are invalid: outside [0, 1], NaN,
+    /// or a zero SIR recovery.
+    pub fn simulate_spread<R: Rng + ?Sized>(
+        &self,
+        seeds: &[VertexIndex],
+        model: SpreadModel,
+        rng: &mut R,
+    ) -> Option<SpreadRun> {
+        if !seeds.iter().all(|&seed| self.nodes.contains(seed)) {
+            return None;
+        }
+        let (infection, recovery) = model.probabilities()?;
+
+        let mut touched = BitSet::default();
+        touched.grow(self.nodes.capacity());
+        let mut reached = Vec::new();
+        for &seed in seeds.iter() {
+            if touched.insert(seed) {
+                reached.push(seed);
+            }
+        }
+        let mut active = reached.clone();
+        let mut rounds = 0;
+        while !active.is_empty() {
+            rounds += 1;
+            let mut next = Vec::new();
+            for &vertex in active.iter() {
+                let mut succs: Vec<VertexIndex> =
+                    self.nodes[vertex].posset.iter().cloned().collect();
+                succs.sort_unstable();
+                for dst in succs {
+                    if !touched.contains(dst) && rng.gen_bool(infection) {
+                        touched.insert(dst);
+                        reached.push(dst);
+                        next.push(dst);
+                    }
+                }
+            }
+            active.retain(|_| !rng.gen_bool(recovery));
+            active.extend(next);
+        }
+
+        Some(SpreadRun { reached, rounds })
+    }
+
+    /// Monte Carlo estimate over `runs` independent outbreaks.
+    pub fn monte_carlo_spread<R: Rng + ?Sized>(
+        &self,
+        seeds: &[VertexIndex],
+        model: SpreadModel,
+        runs: usize,
+        rng: &mut R,
+    ) -> Option<SpreadSummary> {
+        let _span = trace_span!("monte_carlo_spread", runs);
+        let mut counts: HashMap<VertexIndex, usize> = HashMap::new();
+        let mut total = 0;
+        let mut max_reached = 0;
+        for _ in 0..runs {
+            let run = self.simulate_spread(seeds, model, rng)?;
+            total += run.reached.len();
+            max_reached = max_reached.max(run.reached.len());
+            for vertex in run.reached {
+                *counts.entry(vertex).or_insert(0) += 1;
+            }
+        }
+
+        let runs_f = runs.max(1) as f64;
+        Some(SpreadSummary {
+            runs,
+            mean_reached: total as f64 / runs_f,
+            max_reached,
+            probability: counts
+                .into_iter()
+                .map(|(vertex, count)| (vertex, count as f64 / runs_f))
+                .collect(),
+        })
+    }
+
+    /// Greedily picks up to `k` seeds maximizing the expected spread under
+    /// `model`, estimated with `runs` simulations per evaluation. Uses CELF
+    /// lazy evaluation: marginal gains only shrink as seeds are added, so a
+    /// stale gain still on top of the queue only needs recomputing once.
+    /// Returns the seeds in the order chosen and their estimated spread, or
+    /// `None` if the model's probabilities are invalid.
+    pub fn maximize_influence<R: Rng + ?Sized>(
+        &self,
+        k: usize,
+        model: SpreadModel,
+        runs: usize,
+        rng: &mut R,
+    ) -> Option<(Vec<VertexIndex>, f64)> {
+        model.probabilities()?;
+        let _span = trace_span!("maximize_influence", k, runs);
+        let mut seeds: Vec<VertexIndex> = Vec::with_capacity(k);
+        let mut spread = 0.0;
+        let estimate = |seeds: &[VertexIndex], rng: &mut R| {
+            self.monte_carlo_spread(seeds, model, runs, rng)
+                .unwrap()
+                .mean_reached
+        };
+
+        let mut vertices: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        vertices.sort_unstable();
+        let mut queue: BinaryHeap<Gain> = vertices
+            .into_iter()
+            .map(|vertex| Gain {
+                gain: estimate(&[vertex], rng),
+                vertex,
+                round: 0,
+            })
+            .collect();
+        while seeds.len() < k {
+            let top = match queue.pop() {
+                Some(top) => top,
+                None => break,
+            };
+            if top.round == seeds.len() {
+                seeds.push(top.vertex);
+                spread += top.gain;
+                continue;
+            }
+            seeds.push(top.vertex);
+            let gain = estimate(&seeds, rng) - spread;
+            seeds.pop();
+            queue.push(Gain {
+                gain,
+                vertex: top.vertex,
+                round: seeds.len(),
+            });
+        }
+
+        Some((seeds, spread))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn simulate_spread() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..6).map(|i| graph.insert(i)).collect();
+        for pair in vertices.windows(2) {
+            graph.connect(pair[0], pair[1]);
+        }
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let certain = SpreadModel::IndependentCascade { p: 1.0 };
+        let run = graph
+            .simulate_spread(&[vertices[2]], certain, &mut rng)
+            .unwrap();
+        assert_eq!(run.reached, vertices[2..]);
+        assert_eq!(run.rounds, 4);
+        let never = SpreadModel::IndependentCascade { p: 0.0 };
+        let run = graph.simulate_spread(&[0, 0, 3], never, &mut rng).unwrap();
+        assert_eq!(run.reached, vec![0, 3]);
+        assert!(graph.simulate_spread(&[42], certain, &mut rng).is_none());
+        for &model in [
+            SpreadModel::IndependentCascade { p: f64::NAN },
+            SpreadModel::IndependentCascade { p: 1.5 },
+            SpreadModel::Sir {
+                infection: 0.5,
+                recovery: 0.0,
+            },
+        ]
+        .iter()
+        {
+            assert!(graph.simulate_spread(&[0], model, &mut rng).is_none());
+            assert!(graph.maximize_influence(1, model, 1, &mut rng).is_none());
+        }
+
+        // A lingering infection gets more chances along the chain than a
+        // cascade with the same per-contact probability.
+        let cascade = SpreadModel::IndependentCascade { p: 0.5 };
+        let sir = SpreadModel::Sir {
+            infection: 0.5,
+            recovery: 0.2,
+        };
+        let ic = graph
+            .monte_carlo_spread(&[vertices[0]], cascade, 2000, &mut rng)
+            .unwrap();
+        let slow = graph
+            .monte_carlo_spread(&[vertices[0]], sir, 2000, &mut rng)
+            .unwrap();
+        assert_eq!(ic.probability[&vertices[0]], 1.0);
+        assert!((ic.probability[&vertices[1]] - 0.5).abs() < 0.05);
+        assert!((ic.mean_reached - 1.97).abs() < 0.1);
+        assert!(slow.mean_reached > ic.mean_reached + 1.0);
+        assert!(slow.max_reached <= 6);
+    }
+
+    #[test]
+    fn maximize_influence() {
+        // Two disjoint stars of different sizes plus an isolated vertex.
+        let mut graph = Graph::new();
+        let big = graph.insert("big");
+        let small = graph.insert("small");
+        graph.insert("alone");
+        for _ in 0..6 {
+            let leaf = graph.insert("leaf");
+            graph.connect(big, leaf);
+        }
+        for _ in 0..3 {
+            let leaf = graph.insert("leaf");
+            graph.connect(small, leaf);
+        }
+        let mut rng = StdRng::seed_from_u64(9);
+
+        let model = SpreadModel::IndependentCascade { p: 1.0 };
+        let (seeds, spread) = graph.maximize_influence(2, model, 1, &mut rng).unwrap();
+        assert_eq!(seeds, vec![big, small]);
+        assert_eq!(spread, 11.0);
+
+        let model = SpreadModel::IndependentCascade { p: 0.5 };
+        let (seeds, spread) = graph.maximize_influence(1, model, 200, &mut rng).unwrap();
+        assert_eq!(seeds, vec![big]);
+        assert!((spread - 4.0).abs() < 0.5);
+        assert_eq!(
+            graph.maximize_influence(0, model, 10, &mut rng).unwrap().0,
+            vec![]
+        );
+    }
+}
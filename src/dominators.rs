@@ -0,0 +1,232 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+#[derive(Clone, Debug)]
+pub struct Dominators {
+    root: VertexIndex,
+    idom: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl Dominators {
+    #[inline]
+    pub fn root(&self) -> VertexIndex {
+        self.root
+    }
+
+    #[inline]
+    pub fn immediate_dominator(&self, vertex: VertexIndex) -> Option<VertexIndex> {
+        if vertex == self.root {
+            None
+        } else {
+            self.idom.get(&vertex).cloned()
+        }
+    }
+
+    /// All dominators of `vertex`, starting at `vertex` itself and walking up
+    /// to the root. `None` if `vertex` is unreachable from the root.
+    #[inline]
+    pub fn dominators(&self, vertex: VertexIndex) -> Option<DominatorsIter<'_>> {
+        if self.idom.contains_key(&vertex) {
+            Some(DominatorsIter {
+                dominators: self,
+                next: Some(vertex),
+            })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn dominates(&self, dominator: VertexIndex, vertex: VertexIndex) -> bool {
+        self.dominators(vertex)
+            .is_some_and(|mut iter| iter.any(|v| v == dominator))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DominatorsIter<'a> {
+    dominators: &'a Dominators,
+    next: Option<VertexIndex>,
+}
+
+impl<'a> Iterator for DominatorsIter<'a> {
+    type Item = VertexIndex;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let vertex = self.next?;
+        self.next = self.dominators.immediate_dominator(vertex);
+        Some(vertex)
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Dominator tree rooted at `entry`, computed with the Cooper, Harvey and
+    /// Kennedy iterative algorithm.
+    #[inline]
+    pub fn dominators(&self, entry: VertexIndex) -> Option<Dominators> {
+        let _span = trace_span!("dominators", entry, vertices = self.nodes.len());
+        self.dominator_tree(entry, true)
+    }
+
+    /// Post-dominator tree rooted at `exit`: the dominator tree of the graph
+    /// with every edge reversed, computed without building that graph.
+    #[inline]
+    pub fn post_dominators(&self, exit: VertexIndex) -> Option<Dominators> {
+        let _span = trace_span!("post_dominators", exit, vertices = self.nodes.len());
+        self.dominator_tree(exit, false)
+    }
+
+    /// Reverse postorder from `entry` following edges forward or backward.
+    fn reverse_postorder_along(
+        &self,
+        entry: VertexIndex,
+        forward: bool,
+    ) -> Option<Vec<VertexIndex>> {
+        let next = |vertex: VertexIndex| {
+            let node = &self.nodes[vertex];
+            if forward {
+                node.posset.iter()
+            } else {
+                node.preset.iter()
+            }
+        };
+        self.nodes.get(entry)?;
+        let mut order = Vec::new();
+        let mut visited: HashSet<VertexIndex> = HashSet::new();
+        let mut stack = vec![(entry, next(entry))];
+        visited.insert(entry);
+        while let Some((vertex, succs)) = stack.last_mut() {
+            match succs.find(|&&dst| !visited.contains(&dst)) {
+                Some(&dst) => {
+                    visited.insert(dst);
+                    stack.push((dst, next(dst)));
+                }
+                None => {
+                    order.push(*vertex);
+                    stack.pop();
+                }
+            }
+        }
+        order.reverse();
+        Some(order)
+    }
+
+    fn dominator_tree(&self, entry: VertexIndex, forward: bool) -> Option<Dominators> {
+        let order = self.reverse_postorder_along(entry, forward)?;
+        let rank: HashMap<VertexIndex, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(pos, &vertex)| (vertex, pos))
+            .collect();
+        let mut idom: HashMap<VertexIndex, VertexIndex> = HashMap::with_capacity(order.len());
+        idom.insert(entry, entry);
+
+        let intersect = |idom: &HashMap<VertexIndex, VertexIndex>, mut a, mut b| {
+            while a != b {
+                while rank[&a] > rank[&b] {
+                    a = idom[&a];
+                }
+                while rank[&b] > rank[&a] {
+                    b = idom[&b];
+                }
+            }
+            a
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &vertex in order.iter().skip(1) {
+                let node = &self.nodes[vertex];
+                let preds = if forward { &node.preset } else { &node.posset };
+                let mut preds = preds.iter().filter(|pred| idom.contains_key(pred));
+                let first = *preds.next().unwrap();
+                let new = preds.fold(first, |acc, &pred| intersect(&idom, acc, pred));
+                if idom.get(&vertex) != Some(&new) {
+                    idom.insert(vertex, new);
+                    changed = true;
+                }
+            }
+        }
+
+        Some(Dominators { root: entry, idom })
+    }
+
+    /// Whether every path from `src` to `dst` passes through `vertex`.
+    /// `None` if `dst` is unreachable from `src`, as for
+    /// `unavoidable_vertices`.
+    #[inline]
+    pub fn is_dominator_between(
+        &self,
+        vertex: VertexIndex,
+        src: VertexIndex,
+        dst: VertexIndex,
+    ) -> Option<bool> {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+        Some(self.dominators(src)?.dominators(dst)?.any(|v| v == vertex))
+    }
+
+    /// Vertices lying on every path from `src` to `dst`, ordered from `dst`
+    /// back to `src`. `None` if `dst` is unreachable from `src`.
+    #[inline]
+    pub fn unavoidable_vertices(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+    ) -> Option<Vec<VertexIndex>> {
+        Some(self.dominators(src)?.dominators(dst)?.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn dominator_tree() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        let f = graph.insert("f");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(b, d);
+        graph.connect(c, e);
+        graph.connect(d, e);
+        graph.connect(e, b);
+        graph.connect(e, f);
+
+        let dom = graph.dominators(a).unwrap();
+        assert_eq!(dom.immediate_dominator(a), None);
+        assert_eq!(dom.immediate_dominator(c), Some(b));
+        assert_eq!(dom.immediate_dominator(e), Some(b));
+        assert_eq!(dom.immediate_dominator(f), Some(e));
+        assert!(dom.dominates(b, f));
+        assert!(!dom.dominates(c, f));
+
+        assert_eq!(graph.is_dominator_between(e, b, f), Some(true));
+        assert_eq!(graph.is_dominator_between(d, b, f), Some(false));
+        assert_eq!(graph.is_dominator_between(e, f, a), None);
+        assert_eq!(graph.unavoidable_vertices(a, f), Some(vec![f, e, b, a]));
+        assert_eq!(graph.unavoidable_vertices(f, a), None);
+
+        let post = graph.post_dominators(f).unwrap();
+        assert_eq!(post.root(), f);
+        assert_eq!(post.immediate_dominator(b), Some(e));
+        assert_eq!(post.immediate_dominator(c), Some(e));
+        assert_eq!(post.immediate_dominator(a), Some(b));
+        assert_eq!(
+            post.dominators(a).unwrap().collect::<Vec<_>>(),
+            vec![a, b, e, f]
+        );
+        assert!(!post.dominates(c, b));
+        assert!(graph.post_dominators(42).is_none());
+    }
+}
@@ -0,0 +1,908 @@
+//! Loaders for the edge-list formats public benchmark graphs are distributed
+//! in, and writers for them and Graphviz DOT. Vertices are labeled by their
+//! identifier in the source file.
+
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::io::{self, BufRead, Write};
+
+/// Data-quality counters gathered while loading an edge list, since repeated
+/// edges are otherwise silently collapsed into one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadStats {
+    /// Edge lines read, including duplicates and self-loops.
+    pub edges: usize,
+    /// Edge lines naming an edge that was already loaded.
+    pub duplicates: usize,
+    /// Edge lines connecting a vertex to itself.
+    pub self_loops: usize,
+    /// 1-based line numbers of the duplicates, if requested.
+    pub duplicate_lines: Vec<usize>,
+    /// 1-based line numbers of the self-loops, if requested.
+    pub self_loop_lines: Vec<usize>,
+}
+
+/// How a loader reacts to a line it cannot use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadPolicy {
+    /// Fail on the first issue.
+    Strict,
+    /// Skip the offending line, record the issue and keep loading.
+    Lenient,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The line could not be parsed.
+    Malformed(String),
+    /// A vertex identifier outside the range declared by the header.
+    UnknownVertex(String),
+    /// A declaration already made by an earlier line, such as a second
+    /// problem line or terminal.
+    DuplicateDeclaration(String),
+}
+
+/// A recoverable problem found on a line of the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadIssue {
+    /// 1-based line number.
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for LoadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            IssueKind::Malformed(message) => write!(f, "{}", message),
+            IssueKind::UnknownVertex(id) => write!(f, "unknown vertex `{}`", id),
+            IssueKind::DuplicateDeclaration(what) => write!(f, "duplicate {}", what),
+        }
+    }
+}
+
+impl Error for LoadIssue {}
+
+impl From<LoadIssue> for io::Error {
+    fn from(issue: LoadIssue) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, issue.to_string())
+    }
+}
+
+/// Everything a lenient load had to skip, along with its edge statistics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    pub stats: LoadStats,
+    pub issues: Vec<LoadIssue>,
+}
+
+struct Loader {
+    graph: Graph<String>,
+    index: HashMap<String, VertexIndex>,
+    stats: LoadStats,
+    positions: bool,
+    policy: LoadPolicy,
+    issues: Vec<LoadIssue>,
+    declared: Option<usize>,
+}
+
+impl Loader {
+    fn new(policy: LoadPolicy, positions: bool) -> Self {
+        Loader {
+            graph: Graph::new(),
+            index: HashMap::new(),
+            stats: LoadStats::default(),
+            positions,
+            policy,
+            issues: Vec::new(),
+            declared: None,
+        }
+    }
+
+    fn vertex(&mut self, label: &str) -> VertexIndex {
+        if let Some(&vertex) = self.index.get(label) {
+            return vertex;
+        }
+        let vertex = self.graph.insert(label.to_string());
+        self.index.insert(label.to_string(), vertex);
+        vertex
+    }
+
+    /// Creates the vertices `1..=count` declared by a header line.
+    fn declare(&mut self, count: usize, line: usize) -> Result<(), LoadIssue> {
+        if self.declared.is_some() {
+            return Err(issue(
+                line,
+                IssueKind::DuplicateDeclaration("problem line".into()),
+            ));
+        }
+        for vertex in 1..=count {
+            self.vertex(&vertex.to_string());
+        }
+        self.declared = Some(count);
+        Ok(())
+    }
+
+    /// The field at `pos` as a vertex identifier within the declared range.
+    fn known<'a>(&self, fields: &[&'a str], pos: usize, line: usize) -> Result<&'a str, LoadIssue> {
+        let id = number(fields, pos, line)?;
+        match self.declared {
+            Some(count) if id == 0 || id > count => Err(issue(
+                line,
+                IssueKind::UnknownVertex(fields[pos].to_string()),
+            )),
+            _ => Ok(fields[pos]),
+        }
+    }
+
+    fn connect(&mut self, src: &str, dst: &str, line: usize) -> EdgeIndex {
+        let src = self.vertex(src);
+        let dst = self.vertex(dst);
+        self.stats.edges += 1;
+        if src == dst {
+            self.stats.self_loops += 1;
+            if self.positions {
+                self.stats.self_loop_lines.push(line + 1);
+            }
+        }
+        if self.graph.nodes[src].posset.contains(&dst) {
+            self.stats.duplicates += 1;
+            if self.positions {
+                self.stats.duplicate_lines.push(line + 1);
+            }
+        }
+        self.graph.connect(src, dst).unwrap()
+    }
+
+    /// Records the issue of a failed line, or fails with it under
+    /// `LoadPolicy::Strict`.
+    fn recover(&mut self, outcome: Result<(), LoadIssue>) -> io::Result<()> {
+        match (outcome, self.policy) {
+            (Ok(()), _) => Ok(()),
+            (Err(issue), LoadPolicy::Strict) => Err(issue.into()),
+            (Err(issue), LoadPolicy::Lenient) => {
+                self.issues.push(issue);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> (Graph<String>, LoadReport) {
+        (
+            self.graph,
+            LoadReport {
+                stats: self.stats,
+                issues: self.issues,
+            },
+        )
+    }
+}
+
+fn issue(line: usize, kind: IssueKind) -> LoadIssue {
+    LoadIssue {
+        line: line + 1,
+        kind,
+    }
+}
+
+fn invalid(line: usize, message: &str) -> LoadIssue {
+    issue(line, IssueKind::Malformed(message.to_string()))
+}
+
+fn field<'a>(fields: &[&'a str], pos: usize, line: usize) -> Result<&'a str, LoadIssue> {
+    fields
+        .get(pos)
+        .cloned()
+        .ok_or_else(|| invalid(line, "missing field"))
+}
+
+fn number(fields: &[&str], pos: usize, line: usize) -> Result<usize, LoadIssue> {
+    field(fields, pos, line)?
+        .parse()
+        .map_err(|_| invalid(line, "expected a non-negative integer"))
+}
+
+/// SNAP edge lists: one whitespace separated `src dst` pair per line, with
+/// `#` comments.
+pub fn load_snap<R: BufRead>(reader: R) -> io::Result<Graph<String>> {
+    read_snap(reader, Loader::new(LoadPolicy::Strict, false)).map(|(graph, _)| graph)
+}
+
+/// Like `load_snap`, also counting duplicate edges and self-loops and, when
+/// `positions` is set, recording the lines they appear on.
+pub fn load_snap_with_stats<R: BufRead>(
+    reader: R,
+    positions: bool,
+) -> io::Result<(Graph<String>, LoadStats)> {
+    read_snap(reader, Loader::new(LoadPolicy::Strict, positions))
+        .map(|(graph, report)| (graph, report.stats))
+}
+
+/// Like `load_snap`, handling unusable lines according to `policy`.
+pub fn load_snap_with_policy<R: BufRead>(
+    reader: R,
+    policy: LoadPolicy,
+) -> io::Result<(Graph<String>, LoadReport)> {
+    read_snap(reader, Loader::new(policy, true))
+}
+
+fn read_snap<R: BufRead>(reader: R, mut loader: Loader) -> io::Result<(Graph<String>, LoadReport)> {
+    for (line, text) in reader.lines().enumerate() {
+        let text = text?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        if fields.is_empty() || fields[0].starts_with('#') {
+            continue;
+        }
+        let outcome = field(&fields, 0, line)
+            .and_then(|src| Ok(loader.connect(src, field(&fields, 1, line)?, line)));
+        loader.recover(outcome.map(|_| ()))?;
+    }
+    Ok(loader.finish())
+}
+
+/// DIMACS graphs: a `p <format> <vertices> <edges>` problem line followed by
+/// `e u v` edge or `a u v [capacity]` arc lines. Every vertex declared in the
+/// problem line is created, even if isolated.
+pub fn load_dimacs<R: BufRead>(reader: R) -> io::Result<Graph<String>> {
+    read_dimacs(reader, Loader::new(LoadPolicy::Strict, false)).map(|(graph, _)| graph)
+}
+
+/// Like `load_dimacs`, also counting duplicate edges and self-loops and, when
+/// `positions` is set, recording the lines they appear on.
+pub fn load_dimacs_with_stats<R: BufRead>(
+    reader: R,
+    positions: bool,
+) -> io::Result<(Graph<String>, LoadStats)> {
+    read_dimacs(reader, Loader::new(LoadPolicy::Strict, positions))
+        .map(|(graph, report)| (graph, report.stats))
+}
+
+/// Like `load_dimacs`, handling unusable lines according to `policy`.
+pub fn load_dimacs_with_policy<R: BufRead>(
+    reader: R,
+    policy: LoadPolicy,
+) -> io::Result<(Graph<String>, LoadReport)> {
+    read_dimacs(reader, Loader::new(policy, true))
+}
+
+fn dimacs_line(loader: &mut Loader, fields: &[&str], line: usize) -> Result<(), LoadIssue> {
+    match fields.first().cloned() {
+        None | Some("c") | Some("n") => Ok(()),
+        Some("p") => loader.declare(number(fields, 2, line)?, line),
+        Some("e") | Some("a") if loader.declared.is_some() => {
+            let src = loader.known(fields, 1, line)?;
+            let dst = loader.known(fields, 2, line)?;
+            loader.connect(src, dst, line);
+            Ok(())
+        }
+        Some("e") | Some("a") => Err(invalid(line, "edge before problem line")),
+        Some(_) => Err(invalid(line, "unknown line descriptor")),
+    }
+}
+
+fn read_dimacs<R: BufRead>(
+    reader: R,
+    mut loader: Loader,
+) -> io::Result<(Graph<String>, LoadReport)> {
+    for (line, text) in reader.lines().enumerate() {
+        let text = text?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let outcome = dimacs_line(&mut loader, &fields, line);
+        loader.recover(outcome)?;
+    }
+    Ok(loader.finish())
+}
+
+/// A DIMACS max-flow instance.
+#[derive(Clone)]
+pub struct FlowProblem {
+    pub graph: Graph<String>,
+    pub source: Option<VertexIndex>,
+    pub sink: Option<VertexIndex>,
+    pub capacity: HashMap<EdgeIndex, u64>,
+}
+
+/// DIMACS max-flow files: `p max <vertices> <arcs>`, `n <id> s|t` terminal
+/// designations and `a <src> <dst> <capacity>` arcs. Parallel arcs have their
+/// capacities summed.
+pub fn load_dimacs_flow<R: BufRead>(reader: R) -> io::Result<FlowProblem> {
+    load_dimacs_flow_with_policy(reader, LoadPolicy::Strict).map(|(problem, _)| problem)
+}
+
+fn flow_line(
+    loader: &mut Loader,
+    problem: &mut FlowProblem,
+    fields: &[&str],
+    line: usize,
+) -> Result<(), LoadIssue> {
+    match fields.first().cloned() {
+        None | Some("c") => Ok(()),
+        Some("p") => {
+            if field(fields, 1, line)? != "max" {
+                return Err(invalid(line, "expected a max-flow problem"));
+            }
+            loader.declare(number(fields, 2, line)?, line)
+        }
+        Some(_) if loader.declared.is_none() => Err(invalid(line, "line before problem line")),
+        Some("n") => {
+            let id = loader.known(fields, 1, line)?;
+            let terminal = match field(fields, 2, line)? {
+                "s" => &mut problem.source,
+                "t" => &mut problem.sink,
+                _ => return Err(invalid(line, "expected `s` or `t`")),
+            };
+            if terminal.is_some() {
+                let what = format!("terminal `{}`", fields[2]);
+                return Err(issue(line, IssueKind::DuplicateDeclaration(what)));
+            }
+            *terminal = Some(loader.vertex(id));
+            Ok(())
+        }
+        Some("a") => {
+            let src = loader.known(fields, 1, line)?;
+            let dst = loader.known(fields, 2, line)?;
+            let amount = number(fields, 3, line)? as u64;
+            let edge = loader.connect(src, dst, line);
+            *problem.capacity.entry(edge).or_insert(0) += amount;
+            Ok(())
+        }
+        Some(_) => Err(invalid(line, "unknown line descriptor")),
+    }
+}
+
+/// Like `load_dimacs_flow`, handling unusable lines according to `policy`.
+pub fn load_dimacs_flow_with_policy<R: BufRead>(
+    reader: R,
+    policy: LoadPolicy,
+) -> io::Result<(FlowProblem, LoadReport)> {
+    let mut loader = Loader::new(policy, true);
+    let mut problem = FlowProblem {
+        graph: Graph::new(),
+        source: None,
+        sink: None,
+        capacity: HashMap::new(),
+    };
+    for (line, text) in reader.lines().enumerate() {
+        let text = text?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let outcome = flow_line(&mut loader, &mut problem, &fields, line);
+        loader.recover(outcome)?;
+    }
+    let (graph, report) = loader.finish();
+    problem.graph = graph;
+    Ok((problem, report))
+}
+
+/// Dense 1-based DIMACS identifiers for the vertices of `graph`, assigned in
+/// increasing index order.
+pub fn dimacs_ids<V: Eq + Hash + Clone>(graph: &Graph<V>) -> HashMap<VertexIndex, usize> {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(pos, (vertex, _))| (vertex, pos + 1))
+        .collect()
+}
+
+/// Writes `graph` as a DIMACS `.col` file. Edges are undirected in that
+/// format, so each connected pair is written once and self-loops are dropped.
+/// Vertices are numbered as in `dimacs_ids`.
+pub fn write_dimacs_col<V, W>(graph: &Graph<V>, mut writer: W) -> io::Result<()>
+where
+    V: Eq + Hash + Clone,
+    W: Write,
+{
+    let ids = dimacs_ids(graph);
+    let mut edges: Vec<(usize, usize)> = graph
+        .nodes
+        .iter()
+        .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+        .filter(|&(src, dst)| src != dst)
+        .map(|(src, dst)| {
+            let (a, b) = (ids[&src], ids[&dst]);
+            (a.min(b), a.max(b))
+        })
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    writeln!(writer, "p edge {} {}", ids.len(), edges.len())?;
+    for (src, dst) in edges {
+        writeln!(writer, "e {} {}", src, dst)?;
+    }
+    Ok(())
+}
+
+/// Writes a DIMACS max-flow instance with capacities taken from `capacity`.
+/// Vertices are numbered as in `dimacs_ids`.
+pub fn write_dimacs_flow<V, W, C>(
+    graph: &Graph<V>,
+    source: VertexIndex,
+    sink: VertexIndex,
+    capacity: C,
+    mut writer: W,
+) -> io::Result<()>
+where
+    V: Eq + Hash + Clone,
+    W: Write,
+    C: Fn(EdgeIndex) -> u64,
+{
+    let ids = dimacs_ids(graph);
+    let terminal = |vertex| {
+        ids.get(&vertex).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "terminal is not in the graph")
+        })
+    };
+    let (source_id, sink_id) = (terminal(source)?, terminal(sink)?);
+    let mut arcs: Vec<(usize, usize, u64)> = graph
+        .nodes
+        .iter()
+        .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+        .map(|edge| (ids[&edge.0], ids[&edge.1], capacity(edge)))
+        .collect();
+    arcs.sort_unstable();
+
+    writeln!(writer, "p max {} {}", ids.len(), arcs.len())?;
+    writeln!(writer, "n {} s", source_id)?;
+    writeln!(writer, "n {} t", sink_id)?;
+    for (src, dst, amount) in arcs {
+        writeln!(writer, "a {} {} {}", src, dst, amount)?;
+    }
+    Ok(())
+}
+
+/// Matrix Market coordinate files: each nonzero `i j [value]` becomes an edge
+/// from row `i` to column `j`, mirrored for symmetric and hermitian matrices
+/// and mirrored with the value negated for skew-symmetric ones.
+pub fn load_matrix_market<R: BufRead>(reader: R) -> io::Result<Graph<String>> {
+    load_matrix_market_weighted(reader).map(|(graph, _)| graph)
+}
+
+/// Like `load_matrix_market`, also returning the value of every nonzero as
+/// the weight of its edge. Pattern matrices weigh 1 and duplicate entries
+/// are summed.
+pub fn load_matrix_market_weighted<R: BufRead>(
+    reader: R,
+) -> io::Result<(Graph<String>, HashMap<EdgeIndex, f64>)> {
+    load_matrix_market_with_policy(reader, LoadPolicy::Strict)
+        .map(|(graph, weights, _)| (graph, weights))
+}
+
+/// Like `load_matrix_market_weighted`, handling unusable entry lines
+/// according to `policy`. A missing or unsupported header always fails.
+pub fn load_matrix_market_with_policy<R: BufRead>(
+    reader: R,
+    policy: LoadPolicy,
+) -> io::Result<(Graph<String>, HashMap<EdgeIndex, f64>, LoadReport)> {
+    let mut loader = Loader::new(policy, true);
+    let mut weights = HashMap::new();
+    let mut lines = reader.lines().enumerate();
+    // Sign given to the mirror of every off-diagonal entry, if mirrored.
+    let (mirror, pattern) = match lines.next() {
+        Some((_, header)) => {
+            let header = header?.to_lowercase();
+            let tokens: Vec<&str> = header.split_whitespace().collect();
+            if tokens.len() != 5 || tokens[..3] != ["%%matrixmarket", "matrix", "coordinate"] {
+                return Err(invalid(0, "expected a coordinate MatrixMarket header").into());
+            }
+            let pattern = match tokens[3] {
+                "real" | "integer" => false,
+                "pattern" => true,
+                "complex" => {
+                    return Err(invalid(0, "complex matrices are not supported").into());
+                }
+                _ => return Err(invalid(0, "unknown MatrixMarket field").into()),
+            };
+            let mirror = match tokens[4] {
+                "general" => None,
+                // Real hermitian matrices are symmetric.
+                "symmetric" | "hermitian" => Some(1.0),
+                "skew-symmetric" if !pattern => Some(-1.0),
+                "skew-symmetric" => {
+                    return Err(invalid(0, "pattern matrices cannot be skew-symmetric").into());
+                }
+                _ => return Err(invalid(0, "unknown MatrixMarket symmetry").into()),
+            };
+            (mirror, pattern)
+        }
+        None => return Err(invalid(0, "empty file").into()),
+    };
+
+    let mut entry = |loader: &mut Loader, fields: &[&str], line: usize| {
+        if loader.declared.is_none() {
+            let size = number(fields, 0, line)?.max(number(fields, 1, line)?);
+            return loader.declare(size, line);
+        }
+        let row = loader.known(fields, 0, line)?;
+        let column = loader.known(fields, 1, line)?;
+        let weight = if pattern {
+            1.0
+        } else {
+            field(fields, 2, line)?
+                .parse()
+                .map_err(|_| invalid(line, "expected a numeric value"))?
+        };
+        let edge = loader.connect(row, column, line);
+        *weights.entry(edge).or_insert(0.0) += weight;
+        if let Some(sign) = mirror.filter(|_| row != column) {
+            let edge = loader.connect(column, row, line);
+            *weights.entry(edge).or_insert(0.0) += sign * weight;
+        }
+        Ok(())
+    };
+    for (line, text) in lines {
+        let text = text?;
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        if fields.is_empty() || fields[0].starts_with('%') {
+            continue;
+        }
+        let outcome = entry(&mut loader, &fields, line);
+        loader.recover(outcome)?;
+    }
+    let (graph, report) = loader.finish();
+    Ok((graph, weights, report))
+}
+
+/// Writes the adjacency matrix of `graph` as a general real Matrix Market
+/// coordinate file with one nonzero per edge. Vertices are numbered as in
+/// `dimacs_ids`.
+pub fn write_matrix_market<V, W, F>(graph: &Graph<V>, weight: F, mut writer: W) -> io::Result<()>
+where
+    V: Eq + Hash + Clone,
+    W: Write,
+    F: Fn(EdgeIndex) -> f64,
+{
+    let ids = dimacs_ids(graph);
+    let mut entries: Vec<(usize, usize, f64)> = graph
+        .nodes
+        .iter()
+        .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+        .map(|edge| (ids[&edge.0], ids[&edge.1], weight(edge)))
+        .collect();
+    entries.sort_unstable_by_key(|&(src, dst, _)| (src, dst));
+
+    writeln!(writer, "%%MatrixMarket matrix coordinate real general")?;
+    writeln!(writer, "{} {} {}", ids.len(), ids.len(), entries.len())?;
+    for (src, dst, value) in entries {
+        writeln!(writer, "{} {} {}", src, dst, value)?;
+    }
+    Ok(())
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Writes `graph` as a Graphviz DOT digraph with one node per vertex, named
+/// `n<index>` and labeled with its labels sorted and comma separated.
+pub fn write_dot<V, W>(graph: &Graph<V>, writer: W) -> io::Result<()>
+where
+    V: Eq + Hash + Clone + Display,
+    W: Write,
+{
+    write_dot_with(
+        graph,
+        |_, labels| {
+            let mut labels: Vec<String> = labels.iter().map(|label| label.to_string()).collect();
+            labels.sort_unstable();
+            labels.join(", ")
+        },
+        writer,
+    )
+}
+
+/// Like `write_dot`, with each node labeled by `format` from its vertex and
+/// labels, e.g. to join the aliases of merged vertices as `a+b+c` or show a
+/// single representative. Labels are passed in no particular order.
+pub fn write_dot_with<V, W, F>(graph: &Graph<V>, format: F, mut writer: W) -> io::Result<()>
+where
+    V: Eq + Hash + Clone,
+    W: Write,
+    F: Fn(VertexIndex, &[&V]) -> String,
+{
+    writeln!(writer, "digraph {{")?;
+    for (vertex, node) in graph.nodes.iter() {
+        let labels: Vec<&V> = node.aliases.iter().collect();
+        writeln!(
+            writer,
+            "  n{} [label=\"{}\"];",
+            vertex,
+            dot_escape(&format(vertex, &labels))
+        )?;
+    }
+    let mut edges: Vec<EdgeIndex> = graph
+        .nodes
+        .iter()
+        .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+        .collect();
+    edges.sort_unstable();
+    for (src, dst) in edges {
+        writeln!(writer, "  n{} -> n{};", src, dst)?;
+    }
+    writeln!(writer, "}}")
+}
+
+/// Writes `graph` as a DOT digraph with its vertices bundled by `group`, so
+/// that exports of dense graphs stay readable: one node per group, labeled
+/// with its key and size, and one edge per ordered pair of groups labeled
+/// with the number of edges it stands for and drawn thicker the more there
+/// are. Edges within a group become a self-loop on it. Groups are numbered
+/// `g<n>` in key order.
+pub fn write_dot_bundled<V, W, G, F>(graph: &Graph<V>, group: F, mut writer: W) -> io::Result<()>
+where
+    V: Eq + Hash + Clone,
+    W: Write,
+    G: Ord + Display,
+    F: Fn(VertexIndex) -> G,
+{
+    let keys: HashMap<VertexIndex, G> = graph
+        .nodes
+        .iter()
+        .map(|(vertex, _)| (vertex, group(vertex)))
+        .collect();
+    let mut sizes: BTreeMap<&G, usize> = BTreeMap::new();
+    for key in keys.values() {
+        *sizes.entry(key).or_insert(0) += 1;
+    }
+    let ids: BTreeMap<&G, usize> = sizes
+        .keys()
+        .enumerate()
+        .map(|(id, &key)| (key, id))
+        .collect();
+    let mut bundles: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for (src, node) in graph.nodes.iter() {
+        for dst in node.posset.iter() {
+            *bundles
+                .entry((ids[&keys[&src]], ids[&keys[dst]]))
+                .or_insert(0) += 1;
+        }
+    }
+
+    writeln!(writer, "digraph {{")?;
+    for (id, (key, size)) in sizes.iter().enumerate() {
+        let label = dot_escape(&key.to_string());
+        writeln!(writer, "  g{} [label=\"{} ({})\"];", id, label, size)?;
+    }
+    for ((src, dst), count) in bundles {
+        let width = 1.0 + (count as f64).log2();
+        writeln!(
+            writer,
+            "  g{} -> g{} [label=\"{}\", penwidth={:.2}];",
+            src, dst, count, width
+        )?;
+    }
+    writeln!(writer, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn successors(graph: &Graph<String>, label: &str) -> Vec<String> {
+        let vertex = graph.get(label).unwrap().next().unwrap();
+        let mut labels: Vec<String> = graph
+            .posset(vertex)
+            .unwrap()
+            .flat_map(|v| graph.labels(v).unwrap().cloned())
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn load_formats() {
+        let snap = load_snap("# comment\n1 2\n1 3\n\n3\t1\n".as_bytes()).unwrap();
+        assert_eq!(successors(&snap, "1"), vec!["2", "3"]);
+        assert_eq!(successors(&snap, "3"), vec!["1"]);
+        assert!(load_snap("1\n".as_bytes()).is_err());
+
+        let dimacs = load_dimacs("c test\np edge 4 2\ne 1 2\ne 2 3\n".as_bytes()).unwrap();
+        assert_eq!(successors(&dimacs, "2"), vec!["3"]);
+        assert_eq!(successors(&dimacs, "4"), Vec::<String>::new());
+        assert!(load_dimacs("e 1 2\n".as_bytes()).is_err());
+
+        let mtx = "%%MatrixMarket matrix coordinate real symmetric\n% c\n3 3 2\n1 2 0.5\n3 1 1\n";
+        let mtx = load_matrix_market(mtx.as_bytes()).unwrap();
+        assert_eq!(successors(&mtx, "1"), vec!["2", "3"]);
+        assert_eq!(successors(&mtx, "2"), vec!["1"]);
+        assert!(load_matrix_market("1 2\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn load_stats() {
+        let text = "1 2\n# comment\n1 2\n3 3\n2 1\n3 3\n";
+        let (graph, stats) = load_snap_with_stats(text.as_bytes(), true).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(
+            stats,
+            LoadStats {
+                edges: 5,
+                duplicates: 2,
+                self_loops: 2,
+                duplicate_lines: vec![3, 6],
+                self_loop_lines: vec![4, 6],
+            }
+        );
+
+        let text = "p edge 3 3\ne 1 2\ne 1 2\ne 2 2\n";
+        let (_, stats) = load_dimacs_with_stats(text.as_bytes(), false).unwrap();
+        assert_eq!((stats.edges, stats.duplicates, stats.self_loops), (3, 1, 1));
+        assert!(stats.duplicate_lines.is_empty());
+    }
+
+    #[test]
+    fn lenient_load() {
+        let text = "p max 3 2\nn 1 s\nn 2 s\nn 3 t\na 1 2 x\na 1 4 1\nq\na 1 3 5\np max 3 2\n";
+        assert!(load_dimacs_flow(text.as_bytes()).is_err());
+        let (problem, report) =
+            load_dimacs_flow_with_policy(text.as_bytes(), LoadPolicy::Lenient).unwrap();
+        let one = problem.graph.get("1").unwrap().next().unwrap();
+        assert_eq!(problem.source, Some(one));
+        assert_eq!(problem.capacity.values().sum::<u64>(), 5);
+        assert_eq!(problem.graph.nodes.len(), 3);
+        let lines: Vec<usize> = report.issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, vec![3, 5, 6, 7, 9]);
+        assert_eq!(
+            report.issues[2].kind,
+            IssueKind::UnknownVertex("4".to_string())
+        );
+        assert_eq!(
+            report.issues[0].to_string(),
+            "line 3: duplicate terminal `s`"
+        );
+
+        let (graph, report) =
+            load_snap_with_policy("1 2\n3\n2 2\n".as_bytes(), LoadPolicy::Lenient).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(report.stats.self_loop_lines, vec![3]);
+        assert_eq!(
+            report.issues,
+            vec![LoadIssue {
+                line: 2,
+                kind: IssueKind::Malformed("missing field".to_string())
+            }]
+        );
+    }
+
+    #[test]
+    fn dot_export() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a".to_string());
+        let b = graph.insert("b\"".to_string());
+        let c = graph.insert("c".to_string());
+        let d = graph.insert("d".to_string());
+        graph.append_label(a, "z".to_string());
+        for &(src, dst) in [(a, b), (a, c), (b, d), (c, d), (d, a), (a, a)].iter() {
+            graph.connect(src, dst);
+        }
+
+        let mut out = Vec::new();
+        write_dot(&graph, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("digraph {\n  n0 [label=\"a, z\"];\n  n1 [label=\"b\\\"\"];\n"));
+        assert!(text.ends_with(
+            "  n0 -> n0;\n  n0 -> n1;\n  n0 -> n2;\n  n1 -> n3;\n  n2 -> n3;\n  n3 -> n0;\n}\n"
+        ));
+
+        let mut out = Vec::new();
+        let plus = |_, labels: &[&String]| {
+            let mut labels = labels.to_vec();
+            labels.sort_unstable_by(|x, y| y.cmp(x));
+            labels
+                .iter()
+                .map(|label| label.as_str())
+                .collect::<Vec<_>>()
+                .join("+")
+        };
+        write_dot_with(&graph, plus, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("digraph {\n  n0 [label=\"z+a\"];\n"));
+
+        let mut out = Vec::new();
+        let layer = |vertex: VertexIndex| {
+            if vertex == a || vertex == d {
+                "outer"
+            } else {
+                "inner"
+            }
+        };
+        write_dot_bundled(&graph, layer, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "digraph {\n  g0 [label=\"inner (2)\"];\n  g1 [label=\"outer (2)\"];\n  \
+             g0 -> g1 [label=\"2\", penwidth=2.00];\n  \
+             g1 -> g0 [label=\"2\", penwidth=2.00];\n  \
+             g1 -> g1 [label=\"2\", penwidth=2.00];\n}\n"
+        );
+    }
+
+    #[test]
+    fn matrix_market_round_trip() {
+        let mtx = "%%MatrixMarket matrix coordinate real general\n3 3 3\n1 2 0.5\n2 3 -2\n1 2 1\n";
+        let (graph, weights) = load_matrix_market_weighted(mtx.as_bytes()).unwrap();
+        let one = graph.get("1").unwrap().next().unwrap();
+        let two = graph.get("2").unwrap().next().unwrap();
+        assert_eq!(weights[&(one, two)], 1.5);
+
+        let mut out = Vec::new();
+        write_matrix_market(&graph, |e| weights[&e], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "%%MatrixMarket matrix coordinate real general\n3 3 2\n1 2 1.5\n2 3 -2\n"
+        );
+
+        let pattern = "%%MatrixMarket matrix coordinate pattern symmetric\n2 2 2\n1 2\n2 2\n";
+        let (graph, weights) = load_matrix_market_weighted(pattern.as_bytes()).unwrap();
+        assert_eq!(weights.len(), 3);
+        assert_eq!(successors(&graph, "2"), vec!["1", "2"]);
+        assert!(load_matrix_market_weighted(
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2\n".as_bytes()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn matrix_market_symmetry() {
+        let skew = "%%MatrixMarket matrix coordinate real skew-symmetric\n3 3 2\n2 1 4\n3 2 -1.5\n";
+        let (graph, weights) = load_matrix_market_weighted(skew.as_bytes()).unwrap();
+        let vertex = |label: &str| graph.get(label).unwrap().next().unwrap();
+        let (one, two, three) = (vertex("1"), vertex("2"), vertex("3"));
+        assert_eq!(weights[&(two, one)], 4.0);
+        assert_eq!(weights[&(one, two)], -4.0);
+        assert_eq!(weights[&(two, three)], 1.5);
+
+        let hermitian = "%%MatrixMarket matrix coordinate real hermitian\n2 2 1\n2 1 3\n";
+        let (graph, weights) = load_matrix_market_weighted(hermitian.as_bytes()).unwrap();
+        assert_eq!(weights.values().sum::<f64>(), 6.0);
+        assert_eq!(successors(&graph, "1"), vec!["2"]);
+
+        for header in [
+            "%%MatrixMarket matrix coordinate pattern skew-symmetric",
+            "%%MatrixMarket matrix coordinate real nonsymmetric",
+            "%%MatrixMarket matrix coordinate real",
+        ] {
+            let text = format!("{}\n2 2 1\n2 1 3\n", header);
+            assert!(load_matrix_market(text.as_bytes()).is_err());
+        }
+    }
+
+    #[test]
+    fn dimacs_round_trip() {
+        let text = "c flow\np max 4 5\nn 1 s\nn 4 t\na 1 2 3\na 1 3 2\na 2 4 2\na 3 4 3\na 2 3 1\n";
+        let problem = load_dimacs_flow(text.as_bytes()).unwrap();
+        let one = problem.graph.get("1").unwrap().next().unwrap();
+        let two = problem.graph.get("2").unwrap().next().unwrap();
+        assert_eq!(problem.source, Some(one));
+        assert_eq!(problem.capacity[&(one, two)], 3);
+
+        let mut out = Vec::new();
+        let (source, sink) = (problem.source.unwrap(), problem.sink.unwrap());
+        write_dimacs_flow(
+            &problem.graph,
+            source,
+            sink,
+            |e| problem.capacity[&e],
+            &mut out,
+        )
+        .unwrap();
+        let again = load_dimacs_flow(out.as_slice()).unwrap();
+        assert_eq!(again.capacity.len(), 5);
+        assert_eq!(again.capacity.values().sum::<u64>(), 11);
+
+        let mut out = Vec::new();
+        write_dimacs_col(&problem.graph, &mut out).unwrap();
+        let col = String::from_utf8(out).unwrap();
+        assert!(col.starts_with("p edge 4 5\n"));
+        assert_eq!(
+            load_dimacs(col.as_bytes())
+                .unwrap()
+                .count_labeled(&"4".to_string()),
+            Some(1)
+        );
+        assert!(load_dimacs_flow("p edge 2 1\n".as_bytes()).is_err());
+    }
+}
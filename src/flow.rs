@@ -0,0 +1,337 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+pub(crate) const INFINITE: u64 = u64::MAX / 4;
+
+/// Residual network solved with Dinic's algorithm. Arc `id ^ 1` is the
+/// reverse of arc `id`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FlowNetwork {
+    arcs: Vec<Vec<usize>>,
+    head: Vec<usize>,
+    residual: Vec<u64>,
+    level: Vec<usize>,
+    cursor: Vec<usize>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> Self {
+        FlowNetwork {
+            arcs: vec![Vec::new(); nodes],
+            ..Default::default()
+        }
+    }
+
+    pub fn add_arc(&mut self, src: usize, dst: usize, capacity: u64) -> usize {
+        let id = self.head.len();
+        self.arcs[src].push(id);
+        self.head.push(dst);
+        self.residual.push(capacity);
+        self.arcs[dst].push(id + 1);
+        self.head.push(src);
+        self.residual.push(0);
+        id
+    }
+
+    fn levels(&mut self, source: usize, sink: usize) -> bool {
+        self.level = vec![usize::MAX; self.arcs.len()];
+        self.level[source] = 0;
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            for &arc in self.arcs[node].iter() {
+                let dst = self.head[arc];
+                if self.residual[arc] > 0 && self.level[dst] == usize::MAX {
+                    self.level[dst] = self.level[node] + 1;
+                    queue.push_back(dst);
+                }
+            }
+        }
+        self.level[sink] != usize::MAX
+    }
+
+    /// Pushes flow along one path of the level graph, advancing the arc
+    /// cursors past dead ends. Walks with an explicit stack of arcs so that
+    /// long paths cannot overflow the call stack.
+    fn augment(&mut self, source: usize, sink: usize) -> u64 {
+        let mut path: Vec<usize> = Vec::new();
+        let mut node = source;
+        loop {
+            if node == sink {
+                let pushed = path
+                    .iter()
+                    .map(|&arc| self.residual[arc])
+                    .fold(INFINITE, u64::min);
+                for &arc in path.iter() {
+                    self.residual[arc] -= pushed;
+                    self.residual[arc ^ 1] += pushed;
+                }
+                return pushed;
+            }
+            let next = self.arcs[node][self.cursor[node]..]
+                .iter()
+                .position(|&arc| {
+                    self.residual[arc] > 0 && self.level[self.head[arc]] == self.level[node] + 1
+                });
+            match next {
+                Some(offset) => {
+                    self.cursor[node] += offset;
+                    let arc = self.arcs[node][self.cursor[node]];
+                    path.push(arc);
+                    node = self.head[arc];
+                }
+                None => {
+                    self.cursor[node] = self.arcs[node].len();
+                    match path.pop() {
+                        None => return 0,
+                        Some(arc) => {
+                            node = self.head[arc ^ 1];
+                            self.cursor[node] += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> u64 {
+        let _span = trace_span!("max_flow", nodes = self.arcs.len(), arcs = self.head.len());
+        let mut total = 0;
+        if source == sink {
+            return total;
+        }
+        while self.levels(source, sink) {
+            self.cursor = vec![0; self.arcs.len()];
+            loop {
+                let pushed = self.augment(source, sink);
+                if pushed == 0 {
+                    break;
+                }
+                total = total.saturating_add(pushed);
+            }
+        }
+        total
+    }
+
+    /// Flow currently routed through arc `id`.
+    #[inline]
+    pub fn flow(&self, id: usize) -> u64 {
+        self.residual[id ^ 1]
+    }
+
+    /// Nodes reachable from `source` in the residual network, i.e. the source
+    /// side of a minimum cut once `max_flow` has run.
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.arcs.len()];
+        let mut stack = vec![source];
+        seen[source] = true;
+        while let Some(node) = stack.pop() {
+            for &arc in self.arcs[node].iter() {
+                let dst = self.head[arc];
+                if self.residual[arc] > 0 && !seen[dst] {
+                    seen[dst] = true;
+                    stack.push(dst);
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// A maximum flow and how it is routed over the edges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaxFlow {
+    value: u64,
+    flow: HashMap<EdgeIndex, u64>,
+}
+
+impl MaxFlow {
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Flow routed through `edge`, zero for edges carrying none.
+    #[inline]
+    pub fn flow(&self, edge: EdgeIndex) -> u64 {
+        self.flow.get(&edge).cloned().unwrap_or(0)
+    }
+
+    /// Every edge carrying a positive flow.
+    #[inline]
+    pub fn flows(&self) -> &HashMap<EdgeIndex, u64> {
+        &self.flow
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Maximum flow from `source` to `sink` with edge capacities supplied by
+    /// `capacity`, computed with Dinic's algorithm. `None` if either vertex
+    /// is missing.
+    pub fn max_flow<F>(
+        &self,
+        source: VertexIndex,
+        sink: VertexIndex,
+        capacity: F,
+    ) -> Option<MaxFlow>
+    where
+        F: Fn(EdgeIndex) -> u64,
+    {
+        if !(self.nodes.contains(source) && self.nodes.contains(sink)) {
+            return None;
+        }
+
+        let mut network = FlowNetwork::new(self.nodes.capacity());
+        let mut arcs = Vec::new();
+        for (src, node) in self.nodes.iter() {
+            for &dst in node.posset.iter().filter(|&&dst| dst != src) {
+                arcs.push(((src, dst), network.add_arc(src, dst, capacity((src, dst)))));
+            }
+        }
+        let value = network.max_flow(source, sink);
+
+        Some(MaxFlow {
+            value,
+            flow: arcs
+                .into_iter()
+                .map(|(edge, id)| (edge, network.flow(id)))
+                .filter(|&(_, flow)| flow > 0)
+                .collect(),
+        })
+    }
+
+    /// Smallest set of vertices whose removal disconnects `dst` from `src`.
+    /// Returns `None` if either vertex is missing, they are the same vertex, or
+    /// `src` connects directly to `dst` so that no vertex cut exists.
+    pub fn min_vertex_cut(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+    ) -> Option<HashSet<VertexIndex>> {
+        let node = self.nodes.get(src)?;
+        if src == dst || !self.nodes.contains(dst) || node.posset.contains(&dst) {
+            return None;
+        }
+
+        let inside = |vertex: VertexIndex| 2 * vertex;
+        let outside = |vertex: VertexIndex| 2 * vertex + 1;
+        let mut network = FlowNetwork::new(2 * self.nodes.capacity());
+        for (vertex, node) in self.nodes.iter() {
+            let capacity = if vertex == src || vertex == dst {
+                INFINITE
+            } else {
+                1
+            };
+            network.add_arc(inside(vertex), outside(vertex), capacity);
+            for &next in node.posset.iter() {
+                network.add_arc(outside(vertex), inside(next), INFINITE);
+            }
+        }
+
+        network.max_flow(outside(src), inside(dst));
+        let side = network.source_side(outside(src));
+        Some(
+            self.nodes
+                .iter()
+                .map(|(vertex, _)| vertex)
+                .filter(|&vertex| side[inside(vertex)] && !side[outside(vertex)])
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn vertex_cut() {
+        let mut graph = Graph::new();
+        let s = graph.insert("s");
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let t = graph.insert("t");
+        graph.connect(s, a);
+        graph.connect(s, b);
+        graph.connect(a, c);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(c, t);
+        graph.connect(d, t);
+
+        assert_eq!(
+            graph.min_vertex_cut(s, t),
+            Some(vec![c].into_iter().collect())
+        );
+        assert_eq!(
+            graph.min_vertex_cut(s, c),
+            Some(vec![a, b].into_iter().collect())
+        );
+        assert_eq!(graph.min_vertex_cut(t, s), Some(HashSet::new()));
+        assert_eq!(graph.min_vertex_cut(s, a), None);
+        assert_eq!(graph.min_vertex_cut(s, s), None);
+    }
+
+    #[test]
+    fn max_flow() {
+        let mut graph = Graph::new();
+        let s = graph.insert("s");
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let t = graph.insert("t");
+        graph.connect(s, a);
+        graph.connect(s, b);
+        graph.connect(a, b);
+        graph.connect(a, t);
+        graph.connect(b, t);
+        graph.connect(t, s);
+
+        let capacity = |edge| match edge {
+            (src, dst) if (src, dst) == (s, a) => 5,
+            (src, dst) if (src, dst) == (a, t) => 2,
+            (src, dst) if (src, dst) == (a, b) => 4,
+            _ => 3,
+        };
+        let flow = graph.max_flow(s, t, capacity).unwrap();
+        assert_eq!(flow.value(), 5);
+        assert_eq!(flow.flow((a, t)), 2);
+        assert_eq!(flow.flow((b, t)), 3);
+        assert_eq!(flow.flow((t, s)), 0);
+        for vertex in [a, b] {
+            let inflow: u64 = graph
+                .preset(vertex)
+                .unwrap()
+                .map(|p| flow.flow((p, vertex)))
+                .sum();
+            let outflow: u64 = graph
+                .posset(vertex)
+                .unwrap()
+                .map(|n| flow.flow((vertex, n)))
+                .sum();
+            assert_eq!(inflow, outflow);
+        }
+        assert!(flow.flows().iter().all(|(&edge, &f)| f <= capacity(edge)));
+        assert_eq!(graph.max_flow(s, s, capacity).unwrap().value(), 0);
+        assert!(graph.max_flow(s, 42, capacity).is_none());
+    }
+
+    #[test]
+    fn long_augmenting_path() {
+        let mut graph = Graph::new();
+        let chain: Vec<VertexIndex> = (0..600).map(|_| graph.insert(0)).collect();
+        for pair in chain.windows(2) {
+            graph.connect(pair[0], pair[1]);
+        }
+        let (first, last) = (chain[0], chain[599]);
+        let flow = std::thread::Builder::new()
+            .stack_size(32 * 1024)
+            .spawn(move || graph.max_flow(first, last, |_| 3).unwrap().value())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(flow, 3);
+    }
+}
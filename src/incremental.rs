@@ -0,0 +1,668 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+/// In- and out-degree histograms kept current under edits. Each edge or
+/// vertex change costs O(log d) for the largest degree d.
+#[derive(Clone, Debug, Default)]
+pub struct DegreeTally {
+    edges: usize,
+    indegree: BTreeMap<usize, usize>,
+    outdegree: BTreeMap<usize, usize>,
+    dirty: bool,
+}
+
+fn shift(histogram: &mut BTreeMap<usize, usize>, from: Option<usize>, to: Option<usize>) {
+    if let Some(from) = from {
+        let count = histogram.get_mut(&from).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            histogram.remove(&from);
+        }
+    }
+    if let Some(to) = to {
+        *histogram.entry(to).or_insert(0) += 1;
+    }
+}
+
+impl DegreeTally {
+    #[inline]
+    pub fn vertices(&self) -> usize {
+        self.indegree.values().sum()
+    }
+
+    #[inline]
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+
+    #[inline]
+    pub fn max_indegree(&self) -> usize {
+        self.indegree.keys().next_back().cloned().unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn max_outdegree(&self) -> usize {
+        self.outdegree.keys().next_back().cloned().unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn min_indegree(&self) -> usize {
+        self.indegree.keys().next().cloned().unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn min_outdegree(&self) -> usize {
+        self.outdegree.keys().next().cloned().unwrap_or(0)
+    }
+
+    /// Mean in-degree, which is also the mean out-degree; zero for an empty
+    /// graph.
+    #[inline]
+    pub fn mean_degree(&self) -> f64 {
+        match self.vertices() {
+            0 => 0.0,
+            vertices => self.edges as f64 / vertices as f64,
+        }
+    }
+
+    /// `(degree, vertices)` pairs for every in-degree present, ascending.
+    #[inline]
+    pub fn indegree_histogram(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.indegree
+            .iter()
+            .map(|(&degree, &count)| (degree, count))
+    }
+
+    /// `(degree, vertices)` pairs for every out-degree present, ascending.
+    #[inline]
+    pub fn outdegree_histogram(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.outdegree
+            .iter()
+            .map(|(&degree, &count)| (degree, count))
+    }
+
+    /// Number of vertices with exactly `degree` incoming edges.
+    #[inline]
+    pub fn indegree_count(&self, degree: usize) -> usize {
+        self.indegree.get(&degree).cloned().unwrap_or(0)
+    }
+
+    /// Number of vertices with exactly `degree` outgoing edges.
+    #[inline]
+    pub fn outdegree_count(&self, degree: usize) -> usize {
+        self.outdegree.get(&degree).cloned().unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RootReach {
+    roots: HashSet<VertexIndex>,
+    reached: HashSet<VertexIndex>,
+    dirty: bool,
+}
+
+#[derive(Clone, Debug)]
+struct SccMembership {
+    component: HashMap<VertexIndex, usize>,
+    next: usize,
+    dirty: bool,
+}
+
+/// Vertices keyed by their total degree, in- plus out-degree.
+#[derive(Clone, Debug, Default)]
+struct DegreeOrder {
+    degree: HashMap<VertexIndex, usize>,
+    order: BTreeSet<(usize, VertexIndex)>,
+    /// Insertion stamp of every vertex, telling it apart from a later vertex
+    /// reusing its index. Kept current even while dirty.
+    stamps: HashMap<VertexIndex, u64>,
+    next_stamp: u64,
+    dirty: bool,
+}
+
+impl DegreeOrder {
+    fn stamp(&mut self, vertex: VertexIndex) {
+        self.stamps.insert(vertex, self.next_stamp);
+        self.next_stamp += 1;
+    }
+
+    fn adjust(&mut self, vertex: VertexIndex, grow: bool) {
+        let degree = self.degree.get_mut(&vertex).unwrap();
+        self.order.remove(&(*degree, vertex));
+        if grow {
+            *degree += 1;
+        } else {
+            *degree -= 1;
+        }
+        self.order.insert((*degree, vertex));
+    }
+}
+
+/// Cursor yielding every vertex once, each time the one with the lowest (or
+/// highest) current total degree among those not yet yielded. It holds no
+/// borrow, so the graph can be edited between steps: vertices inserted
+/// meanwhile are yielded too, even into a freed index, and removed ones never
+/// are. Ties go to the lowest index in ascending order and to the highest in
+/// descending order. Untracking the degree order restarts the stream.
+#[derive(Clone, Debug)]
+pub struct DegreeStream {
+    descending: bool,
+    /// Vertices yielded so far with their insertion stamps.
+    yielded: HashSet<(VertexIndex, u64)>,
+}
+
+impl DegreeStream {
+    /// The next vertex in degree order, or `None` once every vertex of
+    /// `graph` was yielded. Skipping vertices yielded but not removed costs a
+    /// step each.
+    pub fn next<V: Eq + Hash + Clone>(&mut self, graph: &mut Graph<V>) -> Option<VertexIndex> {
+        let order = graph.degree_order();
+        let yielded = &self.yielded;
+        let fresh = |&(_, vertex): &(usize, VertexIndex)| {
+            let key = (vertex, order.stamps[&vertex]);
+            if yielded.contains(&key) {
+                None
+            } else {
+                Some(key)
+            }
+        };
+        let next = if self.descending {
+            order.order.iter().rev().find_map(fresh)
+        } else {
+            order.order.iter().find_map(fresh)
+        }?;
+        self.yielded.insert(next);
+        Some(next.0)
+    }
+}
+
+/// Query results maintained alongside the graph. Anything an edit cannot
+/// update cheaply is marked dirty and recomputed on the next query.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Maintained {
+    reach: Option<RootReach>,
+    sccs: Option<SccMembership>,
+    degrees: Option<DegreeTally>,
+    order: Option<DegreeOrder>,
+}
+
+impl Maintained {
+    pub fn invalidate(&mut self) {
+        if let Some(reach) = self.reach.as_mut() {
+            reach.dirty = true;
+        }
+        if let Some(sccs) = self.sccs.as_mut() {
+            sccs.dirty = true;
+        }
+        if let Some(degrees) = self.degrees.as_mut() {
+            degrees.dirty = true;
+        }
+        if let Some(order) = self.order.as_mut() {
+            order.dirty = true;
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    pub(crate) fn maintain_insert(&mut self, vertex: VertexIndex) {
+        if let Some(sccs) = self.maintained.sccs.as_mut() {
+            sccs.component.insert(vertex, sccs.next);
+            sccs.next += 1;
+        }
+        if let Some(degrees) = self.maintained.degrees.as_mut().filter(|d| !d.dirty) {
+            shift(&mut degrees.indegree, None, Some(0));
+            shift(&mut degrees.outdegree, None, Some(0));
+        }
+        if let Some(order) = self.maintained.order.as_mut() {
+            order.stamp(vertex);
+            if !order.dirty {
+                order.degree.insert(vertex, 0);
+                order.order.insert((0, vertex));
+            }
+        }
+    }
+
+    pub(crate) fn maintain_remove(&mut self, vertex: VertexIndex) {
+        if let Some(reach) = self.maintained.reach.as_mut() {
+            reach.dirty |= reach.roots.remove(&vertex);
+            reach.reached.remove(&vertex);
+        }
+        if let Some(sccs) = self.maintained.sccs.as_mut() {
+            sccs.component.remove(&vertex);
+        }
+        if let Some(degrees) = self.maintained.degrees.as_mut().filter(|d| !d.dirty) {
+            shift(&mut degrees.indegree, Some(0), None);
+            shift(&mut degrees.outdegree, Some(0), None);
+        }
+        if let Some(order) = self.maintained.order.as_mut() {
+            order.stamps.remove(&vertex);
+            if !order.dirty {
+                let degree = order.degree.remove(&vertex).unwrap();
+                order.order.remove(&(degree, vertex));
+            }
+        }
+    }
+
+    /// Called after `members` were merged into the new vertex `id`. The merge
+    /// rewires edges wholesale, so everything is recomputed on the next
+    /// query; a merged reachability root passes its role on to `id`.
+    pub(crate) fn maintain_merge(&mut self, members: &[VertexIndex], id: VertexIndex) {
+        self.maintained.invalidate();
+        let rooted = self
+            .maintained
+            .reach
+            .as_ref()
+            .is_some_and(|reach| members.iter().any(|vertex| reach.roots.contains(vertex)));
+        for &vertex in members.iter() {
+            self.maintain_remove(vertex);
+        }
+        self.maintain_insert(id);
+        if rooted {
+            self.maintained.reach.as_mut().unwrap().roots.insert(id);
+        }
+    }
+
+    /// Called after a new edge is added. Reachability grows by a search over
+    /// the newly reached vertices only; joining two components marks SCCs
+    /// dirty.
+    pub(crate) fn maintain_connect(&mut self, src: VertexIndex, dst: VertexIndex) {
+        let nodes = &self.nodes;
+        if let Some(reach) = self.maintained.reach.as_mut() {
+            if !reach.dirty && reach.reached.contains(&src) && reach.reached.insert(dst) {
+                let mut stack = vec![dst];
+                while let Some(vertex) = stack.pop() {
+                    for &next in nodes[vertex].posset.iter() {
+                        if reach.reached.insert(next) {
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(sccs) = self.maintained.sccs.as_mut() {
+            sccs.dirty |= sccs.component.get(&src) != sccs.component.get(&dst);
+        }
+        if let Some(degrees) = self.maintained.degrees.as_mut().filter(|d| !d.dirty) {
+            let (out, inc) = (nodes[src].posset.len(), nodes[dst].preset.len());
+            shift(&mut degrees.outdegree, Some(out - 1), Some(out));
+            shift(&mut degrees.indegree, Some(inc - 1), Some(inc));
+            degrees.edges += 1;
+        }
+        if let Some(order) = self.maintained.order.as_mut().filter(|o| !o.dirty) {
+            order.adjust(src, true);
+            order.adjust(dst, true);
+        }
+    }
+
+    /// Called after an edge is removed. Only removals that can shrink the
+    /// reached set or split a component mark them dirty.
+    pub(crate) fn maintain_disconnect(&mut self, src: VertexIndex, dst: VertexIndex) {
+        let nodes = &self.nodes;
+        if let Some(reach) = self.maintained.reach.as_mut() {
+            reach.dirty |= reach.reached.contains(&src);
+        }
+        if let Some(sccs) = self.maintained.sccs.as_mut() {
+            sccs.dirty |= sccs.component.get(&src) == sccs.component.get(&dst);
+        }
+        if let Some(degrees) = self.maintained.degrees.as_mut().filter(|d| !d.dirty) {
+            let (out, inc) = (nodes[src].posset.len(), nodes[dst].preset.len());
+            shift(&mut degrees.outdegree, Some(out + 1), Some(out));
+            shift(&mut degrees.indegree, Some(inc + 1), Some(inc));
+            degrees.edges -= 1;
+        }
+        if let Some(order) = self.maintained.order.as_mut().filter(|o| !o.dirty) {
+            order.adjust(src, false);
+            order.adjust(dst, false);
+        }
+    }
+
+    /// Starts maintaining the set of vertices reachable from `roots`. Edge
+    /// insertions cost time proportional to the newly reached vertices;
+    /// removals from the reached set trigger a full search on the next
+    /// query.
+    pub fn track_reachability<I>(&mut self, roots: I)
+    where
+        I: IntoIterator<Item = VertexIndex>,
+    {
+        let roots = roots
+            .into_iter()
+            .filter(|&root| self.nodes.contains(root))
+            .collect();
+        self.maintained.reach = Some(RootReach {
+            roots,
+            reached: HashSet::new(),
+            dirty: true,
+        });
+    }
+
+    #[inline]
+    pub fn untrack_reachability(&mut self) {
+        self.maintained.reach = None;
+    }
+
+    /// Whether `vertex` is reachable from the tracked roots, or `None` if
+    /// reachability is not tracked or the vertex is missing.
+    pub fn is_reached(&mut self, vertex: VertexIndex) -> Option<bool> {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+        let reach = self.maintained.reach.as_mut()?;
+        if reach.dirty {
+            reach.reached = reach.roots.clone();
+            let mut stack: Vec<VertexIndex> = reach.roots.iter().cloned().collect();
+            while let Some(vertex) = stack.pop() {
+                for &next in self.nodes[vertex].posset.iter() {
+                    if reach.reached.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            reach.dirty = false;
+        }
+        Some(reach.reached.contains(&vertex))
+    }
+
+    /// Starts maintaining strongly connected component membership. Edges
+    /// inside a component or between components that stay apart cost O(1);
+    /// other edits trigger a full Tarjan pass on the next query.
+    #[inline]
+    pub fn track_sccs(&mut self) {
+        if self.maintained.sccs.is_none() {
+            self.maintained.sccs = Some(SccMembership {
+                component: HashMap::new(),
+                next: 0,
+                dirty: true,
+            });
+        }
+    }
+
+    #[inline]
+    pub fn untrack_sccs(&mut self) {
+        self.maintained.sccs = None;
+    }
+
+    /// Whether `one` and `other` are strongly connected. Starts tracking
+    /// SCCs if it was not enabled.
+    pub fn same_scc(&mut self, one: VertexIndex, other: VertexIndex) -> Option<bool> {
+        if !(self.nodes.contains(one) && self.nodes.contains(other)) {
+            return None;
+        }
+        self.track_sccs();
+        if self.maintained.sccs.as_ref().unwrap().dirty {
+            let components = self.strongly_connected_components();
+            let sccs = self.maintained.sccs.as_mut().unwrap();
+            sccs.component = components
+                .iter()
+                .enumerate()
+                .flat_map(|(id, members)| members.iter().map(move |&vertex| (vertex, id)))
+                .collect();
+            sccs.next = components.len();
+            sccs.dirty = false;
+        }
+        let sccs = self.maintained.sccs.as_ref().unwrap();
+        Some(sccs.component[&one] == sccs.component[&other])
+    }
+
+    #[inline]
+    pub fn track_degrees(&mut self) {
+        if self.maintained.degrees.is_none() {
+            self.maintained.degrees = Some(DegreeTally {
+                dirty: true,
+                ..DegreeTally::default()
+            });
+        }
+    }
+
+    #[inline]
+    pub fn untrack_degrees(&mut self) {
+        self.maintained.degrees = None;
+    }
+
+    /// Current degree histograms. Starts tracking degrees if it was not
+    /// enabled.
+    pub fn degree_tally(&mut self) -> &DegreeTally {
+        self.track_degrees();
+        if self.maintained.degrees.as_ref().unwrap().dirty {
+            self.maintained.degrees = Some(self.degree_stats());
+        }
+        self.maintained.degrees.as_ref().unwrap()
+    }
+
+    /// Degree statistics computed afresh, without tracking them; use
+    /// `degree_tally` to keep them current across edits instead.
+    pub fn degree_stats(&self) -> DegreeTally {
+        let mut degrees = DegreeTally::default();
+        for (_, node) in self.nodes.iter() {
+            shift(&mut degrees.indegree, None, Some(node.preset.len()));
+            shift(&mut degrees.outdegree, None, Some(node.posset.len()));
+            degrees.edges += node.posset.len();
+        }
+        degrees
+    }
+
+    /// Starts keeping the vertices ordered by total degree, for
+    /// `DegreeStream`, `min_degree_vertex` and `max_degree_vertex`. Each edit
+    /// then costs O(log n).
+    #[inline]
+    pub fn track_degree_order(&mut self) {
+        if self.maintained.order.is_none() {
+            self.maintained.order = Some(DegreeOrder {
+                dirty: true,
+                ..DegreeOrder::default()
+            });
+        }
+    }
+
+    #[inline]
+    pub fn untrack_degree_order(&mut self) {
+        self.maintained.order = None;
+    }
+
+    fn degree_order(&mut self) -> &DegreeOrder {
+        self.track_degree_order();
+        let order = self.maintained.order.as_mut().unwrap();
+        if order.dirty {
+            order.degree = self
+                .nodes
+                .iter()
+                .map(|(vertex, node)| (vertex, node.preset.len() + node.posset.len()))
+                .collect();
+            order.order = order
+                .degree
+                .iter()
+                .map(|(&vertex, &d)| (d, vertex))
+                .collect();
+            for (vertex, _) in self.nodes.iter() {
+                if !order.stamps.contains_key(&vertex) {
+                    order.stamp(vertex);
+                }
+            }
+            order.dirty = false;
+        }
+        order
+    }
+
+    /// A vertex of least total degree, the lowest index winning ties. Starts
+    /// tracking the degree order if it was not enabled.
+    #[inline]
+    pub fn min_degree_vertex(&mut self) -> Option<VertexIndex> {
+        self.degree_order()
+            .order
+            .iter()
+            .next()
+            .map(|&(_, vertex)| vertex)
+    }
+
+    /// A vertex of greatest total degree, the highest index winning ties.
+    /// Starts tracking the degree order if it was not enabled.
+    #[inline]
+    pub fn max_degree_vertex(&mut self) -> Option<VertexIndex> {
+        self.degree_order()
+            .order
+            .iter()
+            .next_back()
+            .map(|&(_, vertex)| vertex)
+    }
+
+    /// A `DegreeStream` over the vertices in non-decreasing total degree, or
+    /// non-increasing with `descending`. Starts tracking the degree order.
+    #[inline]
+    pub fn degree_stream(&mut self, descending: bool) -> DegreeStream {
+        self.track_degree_order();
+        DegreeStream {
+            descending,
+            yielded: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn maintained_queries() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.track_reachability(vec![a]);
+        graph.track_sccs();
+        graph.track_degrees();
+        assert_eq!(graph.is_reached(b), Some(false));
+        assert_eq!(graph.same_scc(a, b), Some(false));
+
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, b);
+        assert_eq!(graph.is_reached(c), Some(true));
+        assert_eq!(graph.same_scc(b, c), Some(true));
+        assert_eq!(graph.degree_tally().edges(), 3);
+        assert_eq!(graph.degree_tally().max_indegree(), 2);
+
+        graph.disconnect((a, b));
+        let d = graph.insert("d");
+        graph.connect(d, a);
+        assert_eq!(graph.is_reached(c), Some(false));
+        assert_eq!(graph.same_scc(b, c), Some(true));
+        let tally = graph.degree_tally();
+        assert_eq!(tally.vertices(), 4);
+        assert_eq!(tally.edges(), 3);
+        assert_eq!(tally.outdegree_count(1), 3);
+
+        graph.remove(b);
+        assert_eq!(graph.same_scc(c, c), Some(true));
+        assert_eq!(graph.degree_tally().indegree_count(0), 2);
+        assert_eq!(graph.is_reached(42), None);
+        graph.untrack_reachability();
+        assert_eq!(graph.is_reached(a), None);
+    }
+
+    #[test]
+    fn merged_root() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(b, c);
+        graph.track_reachability(vec![a]);
+        graph.track_sccs();
+        assert_eq!(graph.is_reached(c), Some(false));
+
+        let ab = graph.merge_vertices(vec![a, b]);
+        assert_eq!(graph.is_reached(c), Some(true));
+        assert_eq!(graph.is_reached(ab), Some(true));
+        assert_eq!(graph.is_reached(d), Some(false));
+        graph.connect(c, ab);
+        assert_eq!(graph.same_scc(ab, c), Some(true));
+    }
+
+    #[test]
+    fn degree_stats() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, c);
+
+        let stats = graph.degree_stats();
+        assert_eq!((stats.min_indegree(), stats.max_indegree()), (0, 2));
+        assert_eq!((stats.min_outdegree(), stats.max_outdegree()), (0, 2));
+        assert_eq!(stats.mean_degree(), 0.75);
+        assert_eq!(
+            stats.indegree_histogram().collect::<Vec<_>>(),
+            vec![(0, 2), (1, 1), (2, 1)]
+        );
+
+        graph.track_degrees();
+        graph.connect(c, a);
+        let expected: Vec<(usize, usize)> = graph.degree_stats().outdegree_histogram().collect();
+        let tally = graph.degree_tally();
+        assert_eq!(tally.min_indegree(), 0);
+        assert_eq!(tally.mean_degree(), 1.0);
+        assert_eq!(tally.outdegree_histogram().collect::<Vec<_>>(), expected);
+        assert_eq!(Graph::<u8>::new().degree_stats().mean_degree(), 0.0);
+    }
+
+    #[test]
+    fn degree_stream() {
+        // Min-degree elimination on a star with a pendant path.
+        let mut graph = Graph::new();
+        let hub = graph.insert("hub");
+        let spokes: Vec<VertexIndex> = (0..3).map(|_| graph.insert("spoke")).collect();
+        for &spoke in spokes.iter() {
+            graph.connect(hub, spoke);
+        }
+        let tail = graph.insert("tail");
+        graph.connect(spokes[0], tail);
+        graph.connect(tail, tail);
+
+        let mut stream = graph.degree_stream(false);
+        let mut eliminated = Vec::new();
+        while let Some(vertex) = stream.next(&mut graph) {
+            eliminated.push(vertex);
+            graph.remove(vertex);
+        }
+        assert_eq!(eliminated, vec![spokes[1], spokes[2], hub, spokes[0], tail]);
+        assert_eq!(graph.min_degree_vertex(), None);
+
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        assert_eq!(graph.min_degree_vertex(), Some(c));
+        assert_eq!(graph.max_degree_vertex(), Some(a.max(b)));
+        let mut stream = graph.degree_stream(true);
+        assert_eq!(stream.next(&mut graph), Some(a.max(b)));
+        graph.connect(c, a);
+        graph.connect(c, c);
+        let d = graph.insert("d");
+        assert_eq!(stream.next(&mut graph), Some(c));
+        assert_eq!(stream.next(&mut graph), Some(a.min(b)));
+        assert_eq!(stream.next(&mut graph), Some(d));
+        assert_eq!(stream.next(&mut graph), None);
+        graph.merge_vertices(vec![a, b]);
+        assert_eq!(graph.min_degree_vertex(), Some(d));
+    }
+
+    #[test]
+    fn degree_stream_reused_index() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let mut stream = graph.degree_stream(false);
+        assert_eq!(stream.next(&mut graph), Some(a));
+
+        graph.remove(a);
+        let e = graph.insert("e");
+        assert_eq!(e, a);
+        assert_eq!(stream.next(&mut graph), Some(e));
+        assert_eq!(stream.next(&mut graph), Some(b));
+        assert_eq!(stream.next(&mut graph), None);
+    }
+}
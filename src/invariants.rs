@@ -0,0 +1,176 @@
+use crate::{EdgeKind, Graph};
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Verifies the bookkeeping shared between vertices, trunks, leaves,
+    /// labels, flags, pins, protected edges and edge kinds, describing the
+    /// first inconsistency found. Takes time linear in the size of the graph.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        for (vertex, node) in self.nodes.iter() {
+            for &dst in node.posset.iter() {
+                match self.nodes.get(dst) {
+                    None => return Err(format!("vertex {} has missing successor {}", vertex, dst)),
+                    Some(other) if !other.preset.contains(&vertex) => {
+                        return Err(format!(
+                            "vertex {} lists successor {} which does not list it as predecessor",
+                            vertex, dst
+                        ))
+                    }
+                    _ => (),
+                }
+            }
+            for &src in node.preset.iter() {
+                match self.nodes.get(src) {
+                    None => {
+                        return Err(format!("vertex {} has missing predecessor {}", vertex, src))
+                    }
+                    Some(other) if !other.posset.contains(&vertex) => {
+                        return Err(format!(
+                            "vertex {} lists predecessor {} which does not list it as successor",
+                            vertex, src
+                        ))
+                    }
+                    _ => (),
+                }
+            }
+            if node.preset.is_empty() != self.trunks.contains(&vertex) {
+                return Err(format!(
+                    "vertex {} has {} predecessors but trunk membership is {}",
+                    vertex,
+                    node.preset.len(),
+                    self.trunks.contains(&vertex)
+                ));
+            }
+            if node.posset.is_empty() != self.leaves.contains(&vertex) {
+                return Err(format!(
+                    "vertex {} has {} successors but leaf membership is {}",
+                    vertex,
+                    node.posset.len(),
+                    self.leaves.contains(&vertex)
+                ));
+            }
+            if node.aliases.iter().any(|label| {
+                self.aliases
+                    .get(label)
+                    .is_none_or(|set| !set.contains(&vertex))
+            }) {
+                return Err(format!(
+                    "vertex {} has a label missing from the label index",
+                    vertex
+                ));
+            }
+        }
+
+        for (kind, set) in [
+            ("trunk", &self.trunks),
+            ("leaf", &self.leaves),
+            ("pinned", &self.pinned),
+        ] {
+            if let Some(vertex) = set.iter().find(|&&vertex| !self.nodes.contains(vertex)) {
+                return Err(format!("{} set holds missing vertex {}", kind, vertex));
+            }
+        }
+        for (label, set) in self.aliases.iter() {
+            if set.is_empty() {
+                return Err("label index holds a label without vertices".to_string());
+            }
+            if let Some(vertex) = set.iter().find(|&&vertex| {
+                self.nodes
+                    .get(vertex)
+                    .is_none_or(|node| !node.aliases.contains(label))
+            }) {
+                return Err(format!(
+                    "label index maps a label to vertex {} which lacks it",
+                    vertex
+                ));
+            }
+        }
+        for (&vertex, weights) in self.label_weights.iter() {
+            if self
+                .nodes
+                .get(vertex)
+                .is_none_or(|node| weights.keys().any(|label| !node.aliases.contains(label)))
+            {
+                return Err(format!(
+                    "vertex {} has a weight for a label it does not carry",
+                    vertex
+                ));
+            }
+        }
+        if let Some(&(src, dst)) = self.protected.iter().find(|&&(src, dst)| {
+            self.nodes
+                .get(src)
+                .is_none_or(|node| !node.posset.contains(&dst))
+        }) {
+            return Err(format!("protected edge ({}, {}) does not exist", src, dst));
+        }
+        for (&(src, dst), &kind) in self.edge_kinds.iter() {
+            if self
+                .nodes
+                .get(src)
+                .is_none_or(|node| !node.posset.contains(&dst))
+            {
+                return Err(format!("kinded edge ({}, {}) does not exist", src, dst));
+            }
+            if kind == EdgeKind::DEFAULT {
+                return Err(format!("edge ({}, {}) stores the default kind", src, dst));
+            }
+        }
+        for (slot, flags) in self.flags.iter().enumerate() {
+            if let Some(vertex) = (0..self.nodes.capacity())
+                .find(|&vertex| flags.contains(vertex) && !self.nodes.contains(vertex))
+            {
+                return Err(format!(
+                    "flag slot {} is set on missing vertex {}",
+                    slot, vertex
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Panics with the violated invariant after a mutation. Enabled by the
+    /// `paranoid` feature and in the crate's own tests; a full check after
+    /// every edit is too slow to impose on all debug builds.
+    #[inline]
+    pub(crate) fn debug_check_invariants(&self) {
+        #[cfg(any(test, feature = "paranoid"))]
+        {
+            if let Err(violation) = self.check_invariants() {
+                panic!("graph invariant violated: {}", violation);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn invariants() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.append_label(c, "a");
+        graph.set_flag(b, 1);
+        assert_eq!(graph.check_invariants(), Ok(()));
+
+        // Edges between merged vertices become a self-loop.
+        let ab = graph.merge_vertices(vec![a, b]);
+        assert_eq!(graph.posset(ab).unwrap().collect::<Vec<_>>().len(), 2);
+        assert_eq!(graph.preset(ab).unwrap().collect::<Vec<_>>(), vec![ab]);
+        graph.remove(c);
+        assert_eq!(graph.check_invariants(), Ok(()));
+
+        graph.leaves.insert(ab);
+        assert!(graph
+            .check_invariants()
+            .unwrap_err()
+            .contains("leaf membership"));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+const UNMAPPED: usize = usize::MAX;
+
+/// Dense copy of one side of a VF2 match.
+struct Side<'a, V: Eq + Hash + Clone> {
+    graph: &'a Graph<V>,
+    vertices: Vec<VertexIndex>,
+    succs: Vec<Vec<usize>>,
+    preds: Vec<Vec<usize>>,
+    edges: HashSet<(usize, usize)>,
+    /// Dense vertex matched to each vertex of the other side.
+    core: Vec<usize>,
+    /// Depth at which each vertex entered the out and in terminal sets, zero
+    /// while outside.
+    outs: Vec<usize>,
+    ins: Vec<usize>,
+}
+
+impl<'a, V: Eq + Hash + Clone> Side<'a, V> {
+    fn new(graph: &'a Graph<V>) -> Self {
+        let vertices: Vec<VertexIndex> = graph.nodes.iter().map(|(vertex, _)| vertex).collect();
+        let dense: HashMap<VertexIndex, usize> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let mut succs = vec![Vec::new(); vertices.len()];
+        let mut preds = vec![Vec::new(); vertices.len()];
+        let mut edges = HashSet::new();
+        for (i, &vertex) in vertices.iter().enumerate() {
+            for dst in graph.nodes[vertex].posset.iter() {
+                succs[i].push(dense[dst]);
+                preds[dense[dst]].push(i);
+                edges.insert((i, dense[dst]));
+            }
+        }
+        let len = vertices.len();
+        Side {
+            graph,
+            vertices,
+            succs,
+            preds,
+            edges,
+            core: vec![UNMAPPED; len],
+            outs: vec![0; len],
+            ins: vec![0; len],
+        }
+    }
+
+    /// Sorted in, out and self-loop counts, equal on isomorphic graphs.
+    fn degrees(&self) -> Vec<(usize, usize, bool)> {
+        let mut degrees: Vec<(usize, usize, bool)> = (0..self.vertices.len())
+            .map(|i| {
+                (
+                    self.preds[i].len(),
+                    self.succs[i].len(),
+                    self.edges.contains(&(i, i)),
+                )
+            })
+            .collect();
+        degrees.sort_unstable();
+        degrees
+    }
+
+    #[inline]
+    fn in_outs(&self, i: usize) -> bool {
+        self.core[i] == UNMAPPED && self.outs[i] > 0
+    }
+
+    #[inline]
+    fn in_ins(&self, i: usize) -> bool {
+        self.core[i] == UNMAPPED && self.ins[i] > 0
+    }
+
+    /// Unmapped vertices in the out terminal set, the in terminal set, or
+    /// neither, among `neighbors`.
+    fn look_ahead(&self, neighbors: &[usize]) -> (usize, usize, usize) {
+        let mut counts = (0, 0, 0);
+        for &m in neighbors.iter().filter(|&&m| self.core[m] == UNMAPPED) {
+            if self.in_outs(m) {
+                counts.0 += 1;
+            }
+            if self.in_ins(m) {
+                counts.1 += 1;
+            }
+            if self.outs[m] == 0 && self.ins[m] == 0 {
+                counts.2 += 1;
+            }
+        }
+        counts
+    }
+
+    /// Mapped neighbors of `i`, translated to the other side.
+    fn mapped(&self, neighbors: &[usize]) -> Vec<usize> {
+        neighbors
+            .iter()
+            .map(|&m| self.core[m])
+            .filter(|&m| m != UNMAPPED)
+            .collect()
+    }
+
+    fn push(&mut self, i: usize, image: usize, depth: usize) {
+        self.core[i] = image;
+        for &m in self.succs[i].iter().chain(std::iter::once(&i)) {
+            if self.outs[m] == 0 {
+                self.outs[m] = depth;
+            }
+        }
+        for &m in self.preds[i].iter().chain(std::iter::once(&i)) {
+            if self.ins[m] == 0 {
+                self.ins[m] = depth;
+            }
+        }
+    }
+
+    fn pop(&mut self, i: usize, depth: usize) {
+        self.core[i] = UNMAPPED;
+        for m in 0..self.vertices.len() {
+            if self.outs[m] == depth {
+                self.outs[m] = 0;
+            }
+            if self.ins[m] == depth {
+                self.ins[m] = 0;
+            }
+        }
+    }
+}
+
+/// A vertex of the second graph and the candidates of the first one still to
+/// be tried against it.
+struct Frame {
+    target: usize,
+    candidates: Vec<usize>,
+    next: usize,
+    tried: Option<usize>,
+}
+
+fn feasible<V: Eq + Hash + Clone>(
+    one: &Side<V>,
+    two: &Side<V>,
+    n1: usize,
+    n2: usize,
+    match_labels: bool,
+) -> bool {
+    if one.edges.contains(&(n1, n1)) != two.edges.contains(&(n2, n2)) {
+        return false;
+    }
+    if match_labels
+        && one.graph.nodes[one.vertices[n1]].aliases != two.graph.nodes[two.vertices[n2]].aliases
+    {
+        return false;
+    }
+
+    let succs = one.mapped(&one.succs[n1]);
+    if succs.len() != two.mapped(&two.succs[n2]).len()
+        || succs.iter().any(|&m2| !two.edges.contains(&(n2, m2)))
+    {
+        return false;
+    }
+    let preds = one.mapped(&one.preds[n1]);
+    if preds.len() != two.mapped(&two.preds[n2]).len()
+        || preds.iter().any(|&m2| !two.edges.contains(&(m2, n2)))
+    {
+        return false;
+    }
+
+    one.look_ahead(&one.succs[n1]) == two.look_ahead(&two.succs[n2])
+        && one.look_ahead(&one.preds[n1]) == two.look_ahead(&two.preds[n2])
+}
+
+/// Picks the next vertex of `two` to match and the vertices of `one` that may
+/// match it, preferring the out and then the in terminal sets.
+fn frame<V: Eq + Hash + Clone>(one: &Side<V>, two: &Side<V>) -> Frame {
+    let len = two.vertices.len();
+    let pick = |on_two: &dyn Fn(usize) -> bool, on_one: &dyn Fn(usize) -> bool| {
+        (0..len).find(|&i| on_two(i)).map(|target| Frame {
+            target,
+            candidates: (0..len).filter(|&i| on_one(i)).collect(),
+            next: 0,
+            tried: None,
+        })
+    };
+    pick(&|i| two.in_outs(i), &|i| one.in_outs(i))
+        .or_else(|| pick(&|i| two.in_ins(i), &|i| one.in_ins(i)))
+        .or_else(|| {
+            pick(&|i| two.core[i] == UNMAPPED, &|i| {
+                one.core[i] == UNMAPPED && one.outs[i] == 0 && one.ins[i] == 0
+            })
+        })
+        .unwrap()
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// A bijection between the vertices of `self` and those of `other` that
+    /// preserves edges, found with the VF2 algorithm. With `match_labels`,
+    /// matched vertices must also carry the same label sets. `None` if the
+    /// graphs are not isomorphic.
+    pub fn isomorphism(
+        &self,
+        other: &Graph<V>,
+        match_labels: bool,
+    ) -> Option<HashMap<VertexIndex, VertexIndex>> {
+        let _span = trace_span!("isomorphism", vertices = self.nodes.len());
+        let mut one = Side::new(self);
+        let mut two = Side::new(other);
+        if one.vertices.len() != two.vertices.len()
+            || one.edges.len() != two.edges.len()
+            || one.degrees() != two.degrees()
+        {
+            return None;
+        }
+
+        let len = one.vertices.len();
+        let mut stack: Vec<Frame> = Vec::new();
+        if len > 0 {
+            stack.push(frame(&one, &two));
+        }
+        while !stack.is_empty() {
+            let depth = stack.len();
+            let top = stack.last_mut().unwrap();
+            if let Some(n1) = top.tried.take() {
+                one.pop(n1, depth);
+                two.pop(top.target, depth);
+            }
+            let target = top.target;
+            let found = top.candidates[top.next..]
+                .iter()
+                .position(|&n1| feasible(&one, &two, n1, target, match_labels));
+            let n1 = match found {
+                Some(offset) => {
+                    top.next += offset + 1;
+                    top.candidates[top.next - 1]
+                }
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            top.tried = Some(n1);
+            one.push(n1, target, depth);
+            two.push(target, n1, depth);
+            if depth == len {
+                break;
+            }
+            let next = frame(&one, &two);
+            stack.push(next);
+        }
+
+        if stack.is_empty() && len > 0 {
+            return None;
+        }
+        Some(
+            one.core
+                .iter()
+                .enumerate()
+                .map(|(i, &image)| (one.vertices[i], two.vertices[image]))
+                .collect(),
+        )
+    }
+
+    /// Whether `self` and `other` are isomorphic, see `isomorphism`.
+    #[inline]
+    pub fn is_isomorphic_to(&self, other: &Graph<V>, match_labels: bool) -> bool {
+        self.isomorphism(other, match_labels).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn isomorphism() {
+        // A half adder drawn twice with different vertex orders.
+        let mut one = Graph::new();
+        let a = one.insert("in");
+        let b = one.insert("in");
+        let x = one.insert("xor");
+        let c = one.insert("and");
+        for &(src, dst) in [(a, x), (b, x), (a, c), (b, c)].iter() {
+            one.connect(src, dst);
+        }
+        let mut two = Graph::new();
+        let c2 = two.insert("and");
+        let x2 = two.insert("xor");
+        let a2 = two.insert("in");
+        let b2 = two.insert("in");
+        for &(src, dst) in [(b2, c2), (a2, x2), (a2, c2), (b2, x2)].iter() {
+            two.connect(src, dst);
+        }
+
+        let mapping = one.isomorphism(&two, true).unwrap();
+        assert_eq!(mapping[&x], x2);
+        assert_eq!(mapping[&c], c2);
+        for (src, dst) in [(a, x), (b, x), (a, c), (b, c)] {
+            assert!(two
+                .posset(mapping[&src])
+                .unwrap()
+                .any(|v| v == mapping[&dst]));
+        }
+
+        two.remove_vertex_label(&"xor", x2);
+        two.append_label(x2, "or");
+        assert!(!one.is_isomorphic_to(&two, true));
+        assert!(one.is_isomorphic_to(&two, false));
+        two.connect(x2, x2);
+        assert!(!one.is_isomorphic_to(&two, false));
+        assert!(Graph::<&str>::new().is_isomorphic_to(&Graph::new(), true));
+    }
+
+    #[test]
+    fn non_isomorphic_with_equal_degrees() {
+        // A directed 6-cycle against two directed 3-cycles.
+        let mut one = Graph::new();
+        let mut two = Graph::new();
+        let ring: Vec<VertexIndex> = (0..6).map(|_| one.insert(0)).collect();
+        let pair: Vec<VertexIndex> = (0..6).map(|_| two.insert(0)).collect();
+        for i in 0..6 {
+            one.connect(ring[i], ring[(i + 1) % 6]);
+            two.connect(pair[i], pair[(i + 1) % 3 + i / 3 * 3]);
+        }
+        assert!(!one.is_isomorphic_to(&two, false));
+        assert!(one.is_isomorphic_to(&one.clone(), false));
+        assert!(two.is_isomorphic_to(&two.clone(), false));
+    }
+}
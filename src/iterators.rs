@@ -14,6 +14,15 @@ impl<'a> VertexIter<'a> {
       inner: inner.cloned(),
     }
   }
+
+  /// Collects the remaining vertices in increasing index order, giving
+  /// reproducible output regardless of hashing.
+  #[inline]
+  pub fn sorted(self) -> Vec<VertexIndex> {
+    let mut vertices: Vec<VertexIndex> = self.collect();
+    vertices.sort_unstable();
+    vertices
+  }
 }
 
 impl<'a> Iterator for VertexIter<'a> {
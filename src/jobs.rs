@@ -0,0 +1,368 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Units of work performed between two polls of a cancellation flag.
+pub(crate) const CANCEL_INTERVAL: usize = 1024;
+
+/// Returned by operations aborted through their cancellation flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+#[inline]
+pub(crate) fn is_cancelled(cancel: Option<&AtomicBool>) -> bool {
+    cancel.is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// A long-running computation that can be advanced a bounded amount of work
+/// at a time, e.g. between frames of an interactive application.
+pub trait Job {
+    type Output;
+
+    /// Performs at most `budget` units of work and returns whether the job
+    /// has completed.
+    fn step(&mut self, budget: usize) -> bool;
+
+    fn is_done(&self) -> bool;
+
+    fn progress(&self) -> Progress;
+
+    /// Runs the job to completion and returns its result.
+    fn finish(self) -> Self::Output;
+
+    /// Runs the job to completion unless `cancel` is raised first.
+    #[inline]
+    fn finish_cancellable(self, cancel: &AtomicBool) -> Result<Self::Output, Cancelled>
+    where
+        Self: Sized,
+    {
+        self.finish_with(Some(cancel), |_| ())
+    }
+
+    /// Runs the job to completion, reporting progress after every chunk of
+    /// work and polling the optional cancellation flag in between.
+    fn finish_with<F>(
+        mut self,
+        cancel: Option<&AtomicBool>,
+        mut progress: F,
+    ) -> Result<Self::Output, Cancelled>
+    where
+        Self: Sized,
+        F: FnMut(Progress),
+    {
+        let _span = trace_span!("job");
+        loop {
+            if is_cancelled(cancel) {
+                return Err(Cancelled);
+            }
+            let done = self.step(CANCEL_INTERVAL);
+            progress(self.progress());
+            if done {
+                return Ok(self.finish());
+            }
+        }
+    }
+}
+
+struct Frame {
+    vertex: VertexIndex,
+    succs: Vec<VertexIndex>,
+    next: usize,
+}
+
+/// Resumable Tarjan strongly connected components. One unit of work is
+/// either an edge inspection or a vertex retirement.
+pub struct SccJob<'a, V: Hash + Eq + Clone> {
+    graph: &'a Graph<V>,
+    roots: Vec<VertexIndex>,
+    counter: usize,
+    index: HashMap<VertexIndex, usize>,
+    lowlink: HashMap<VertexIndex, usize>,
+    stack: Vec<VertexIndex>,
+    on_stack: HashSet<VertexIndex>,
+    frames: Vec<Frame>,
+    components: Vec<Vec<VertexIndex>>,
+}
+
+impl<'a, V: Hash + Eq + Clone> SccJob<'a, V> {
+    fn enter(&mut self, vertex: VertexIndex) {
+        self.index.insert(vertex, self.counter);
+        self.lowlink.insert(vertex, self.counter);
+        self.counter += 1;
+        self.stack.push(vertex);
+        self.on_stack.insert(vertex);
+        self.frames.push(Frame {
+            vertex,
+            succs: self.graph.nodes[vertex].posset.iter().cloned().collect(),
+            next: 0,
+        });
+    }
+
+    fn advance(&mut self) {
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => {
+                while let Some(root) = self.roots.pop() {
+                    if !self.index.contains_key(&root) {
+                        self.enter(root);
+                        return;
+                    }
+                }
+                return;
+            }
+        };
+
+        let vertex = frame.vertex;
+        if let Some(&dst) = frame.succs.get(frame.next) {
+            frame.next += 1;
+            if !self.index.contains_key(&dst) {
+                self.enter(dst);
+            } else if self.on_stack.contains(&dst) {
+                let low = self.lowlink[&vertex].min(self.index[&dst]);
+                self.lowlink.insert(vertex, low);
+            }
+            return;
+        }
+
+        self.frames.pop();
+        let low = self.lowlink[&vertex];
+        if let Some(parent) = self.frames.last() {
+            let parent_low = self.lowlink[&parent.vertex].min(low);
+            self.lowlink.insert(parent.vertex, parent_low);
+        }
+        if low == self.index[&vertex] {
+            let mut component = Vec::new();
+            loop {
+                let member = self.stack.pop().unwrap();
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == vertex {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+impl<'a, V: Hash + Eq + Clone> Job for SccJob<'a, V> {
+    type Output = Vec<Vec<VertexIndex>>;
+
+    fn step(&mut self, budget: usize) -> bool {
+        for _ in 0..budget {
+            if self.is_done() {
+                break;
+            }
+            self.advance();
+        }
+        self.is_done()
+    }
+
+    #[inline]
+    fn is_done(&self) -> bool {
+        self.frames.is_empty() && self.roots.is_empty()
+    }
+
+    #[inline]
+    fn progress(&self) -> Progress {
+        let retired = self.index.len() - self.stack.len();
+        Progress {
+            done: retired,
+            total: self.graph.nodes.len(),
+        }
+    }
+
+    fn finish(mut self) -> Self::Output {
+        while !self.step(usize::MAX) {}
+        self.components
+    }
+}
+
+/// Resumable transitive closure, computing the set of vertices reachable from
+/// every vertex. One unit of work is one vertex dequeued by a search.
+pub struct ClosureJob<'a, V: Hash + Eq + Clone> {
+    graph: &'a Graph<V>,
+    pending: Vec<VertexIndex>,
+    current: Option<(VertexIndex, HashSet<VertexIndex>, VecDeque<VertexIndex>)>,
+    closure: HashMap<VertexIndex, HashSet<VertexIndex>>,
+}
+
+impl<'a, V: Hash + Eq + Clone> Job for ClosureJob<'a, V> {
+    type Output = HashMap<VertexIndex, HashSet<VertexIndex>>;
+
+    fn step(&mut self, budget: usize) -> bool {
+        let mut spent = 0;
+        while spent < budget && !self.is_done() {
+            let (source, reached, queue) = match self.current.as_mut() {
+                Some(current) => current,
+                None => {
+                    let source = self.pending.pop().unwrap();
+                    let queue = vec![source].into_iter().collect();
+                    self.current.get_or_insert((source, HashSet::new(), queue))
+                }
+            };
+            match queue.pop_front() {
+                Some(vertex) => {
+                    spent += 1;
+                    for &dst in self.graph.nodes[vertex].posset.iter() {
+                        if reached.insert(dst) {
+                            queue.push_back(dst);
+                        }
+                    }
+                }
+                None => {
+                    let source = *source;
+                    let (_, reached, _) = self.current.take().unwrap();
+                    self.closure.insert(source, reached);
+                }
+            }
+        }
+        self.is_done()
+    }
+
+    #[inline]
+    fn is_done(&self) -> bool {
+        self.current.is_none() && self.pending.is_empty()
+    }
+
+    #[inline]
+    fn progress(&self) -> Progress {
+        Progress {
+            done: self.closure.len(),
+            total: self.graph.nodes.len(),
+        }
+    }
+
+    fn finish(mut self) -> Self::Output {
+        while !self.step(usize::MAX) {}
+        self.closure
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    pub fn scc_job(&self) -> SccJob<'_, V> {
+        SccJob {
+            graph: self,
+            roots: self.nodes.iter().map(|(index, _)| index).collect(),
+            counter: 0,
+            index: HashMap::new(),
+            lowlink: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            frames: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn closure_job(&self) -> ClosureJob<'_, V> {
+        ClosureJob {
+            graph: self,
+            pending: self.nodes.iter().map(|(index, _)| index).collect(),
+            current: None,
+            closure: HashMap::new(),
+        }
+    }
+
+    /// Strongly connected components in the order Tarjan's algorithm emits
+    /// them, which is a reverse topological order of the condensation.
+    #[inline]
+    pub fn strongly_connected_components(&self) -> Vec<Vec<VertexIndex>> {
+        self.scc_job().finish()
+    }
+
+    #[inline]
+    pub fn transitive_closure(
+        &self,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<HashMap<VertexIndex, HashSet<VertexIndex>>, Cancelled> {
+        self.closure_job().finish_with(cancel, |_| ())
+    }
+
+    #[inline]
+    pub fn transitive_closure_with_progress<F: FnMut(Progress)>(
+        &self,
+        cancel: Option<&AtomicBool>,
+        progress: F,
+    ) -> Result<HashMap<VertexIndex, HashSet<VertexIndex>>, Cancelled> {
+        self.closure_job().finish_with(cancel, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn budgeted_jobs() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, a);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(d, c);
+
+        let mut job = graph.scc_job();
+        let mut steps = 0;
+        while !job.step(2) {
+            steps += 1;
+        }
+        assert!(steps > 1);
+        let mut components: Vec<Vec<VertexIndex>> = job
+            .finish()
+            .into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+        assert_eq!(components, vec![vec![a, b], vec![c, d]]);
+        let order = graph.strongly_connected_components();
+        assert_eq!(order.len(), 2);
+        assert!(order[0].contains(&c) && order[1].contains(&a));
+
+        let mut job = graph.closure_job();
+        assert!(!job.step(1));
+        let closure = job.finish();
+        assert_eq!(closure[&a], vec![a, b, c, d].into_iter().collect());
+        assert_eq!(closure[&c], vec![c, d].into_iter().collect());
+
+        let cancel = AtomicBool::new(true);
+        assert_eq!(graph.transitive_closure(Some(&cancel)), Err(Cancelled));
+        cancel.store(false, Ordering::Relaxed);
+        assert_eq!(graph.transitive_closure(Some(&cancel)).unwrap(), closure);
+
+        let mut reports = Vec::new();
+        graph
+            .transitive_closure_with_progress(None, |p| reports.push(p))
+            .unwrap();
+        assert_eq!(reports.last(), Some(&Progress { done: 4, total: 4 }));
+
+        let mut job = graph.scc_job();
+        assert_eq!(job.progress(), Progress { done: 0, total: 4 });
+        while !job.step(1) {}
+        assert_eq!(job.progress(), Progress { done: 4, total: 4 });
+    }
+}
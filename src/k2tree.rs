@@ -0,0 +1,296 @@
+use crate::{Graph, VertexIndex};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const K: usize = 2;
+
+#[derive(Clone, Debug, Default)]
+struct RankBits {
+    words: Vec<u64>,
+    ranks: Vec<usize>,
+    len: usize,
+}
+
+impl RankBits {
+    fn from_bits(bits: &[bool]) -> Self {
+        let mut words = vec![0u64; bits.len().div_ceil(64)];
+        for (index, _) in bits.iter().enumerate().filter(|(_, &bit)| bit) {
+            words[index / 64] |= 1 << (index % 64);
+        }
+        let mut ranks = Vec::with_capacity(words.len());
+        let mut total = 0;
+        for word in words.iter() {
+            ranks.push(total);
+            total += word.count_ones() as usize;
+        }
+        RankBits {
+            words,
+            ranks,
+            len: bits.len(),
+        }
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Number of set bits in `0..=index`.
+    #[inline]
+    fn rank(&self, index: usize) -> usize {
+        let (word, bit) = (index / 64, index % 64);
+        let mask = if bit == 63 {
+            u64::MAX
+        } else {
+            (1 << (bit + 1)) - 1
+        };
+        self.ranks[word] + (self.words[word] & mask).count_ones() as usize
+    }
+}
+
+/// Read-only k²-tree encoding of a graph's adjacency matrix. Highly regular
+/// graphs collapse into very few tree nodes, at the cost of slower queries.
+#[derive(Clone, Debug)]
+pub struct CompressedGraph<V: Hash + Eq + Clone> {
+    size: usize,
+    tree: RankBits,
+    leaves: RankBits,
+    index: Vec<VertexIndex>,
+    dense: HashMap<VertexIndex, usize>,
+    labels: Vec<Vec<V>>,
+    aliases: HashMap<V, Vec<VertexIndex>>,
+}
+
+fn build(levels: &mut Vec<Vec<bool>>, cells: &[(usize, usize)], size: usize, depth: usize) {
+    let half = size / K;
+    if levels.len() <= depth {
+        levels.push(Vec::new());
+    }
+    for row in 0..K {
+        for col in 0..K {
+            let quadrant: Vec<(usize, usize)> = cells
+                .iter()
+                .filter(|&&(r, c)| r / half == row && c / half == col)
+                .map(|&(r, c)| (r % half, c % half))
+                .collect();
+            levels[depth].push(!quadrant.is_empty());
+            if half > 1 && !quadrant.is_empty() {
+                build(levels, &quadrant, half, depth + 1);
+            }
+        }
+    }
+}
+
+impl<V: Hash + Eq + Clone> CompressedGraph<V> {
+    #[inline]
+    fn bit(&self, pos: usize) -> bool {
+        if pos < self.tree.len {
+            self.tree.get(pos)
+        } else {
+            self.leaves.get(pos - self.tree.len)
+        }
+    }
+
+    #[inline]
+    fn children(&self, pos: Option<usize>) -> usize {
+        pos.map_or(0, |pos| self.tree.rank(pos) * K * K)
+    }
+
+    fn direct(
+        &self,
+        size: usize,
+        row: usize,
+        col: usize,
+        pos: Option<usize>,
+        out: &mut Vec<usize>,
+    ) {
+        if let Some(pos) = pos {
+            if !self.bit(pos) {
+                return;
+            }
+            if pos >= self.tree.len {
+                out.push(col);
+                return;
+            }
+        }
+        let half = size / K;
+        let base = self.children(pos) + K * (row / half);
+        for j in 0..K {
+            self.direct(half, row % half, col + half * j, Some(base + j), out);
+        }
+    }
+
+    fn reverse(
+        &self,
+        size: usize,
+        row: usize,
+        col: usize,
+        pos: Option<usize>,
+        out: &mut Vec<usize>,
+    ) {
+        if let Some(pos) = pos {
+            if !self.bit(pos) {
+                return;
+            }
+            if pos >= self.tree.len {
+                out.push(row);
+                return;
+            }
+        }
+        let half = size / K;
+        let base = self.children(pos) + col / half;
+        for j in 0..K {
+            self.reverse(half, row + half * j, col % half, Some(base + j * K), out);
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    #[inline]
+    pub fn size_in_bits(&self) -> usize {
+        self.tree.len + self.leaves.len
+    }
+
+    pub fn contains_edge(&self, src: VertexIndex, dst: VertexIndex) -> bool {
+        let (mut row, mut col) = match (self.dense.get(&src), self.dense.get(&dst)) {
+            (Some(&row), Some(&col)) => (row, col),
+            _ => return false,
+        };
+        let mut size = self.size;
+        let mut pos = None;
+        loop {
+            let half = size / K;
+            let next = self.children(pos) + K * (row / half) + col / half;
+            if !self.bit(next) {
+                return false;
+            }
+            if next >= self.tree.len {
+                return true;
+            }
+            row %= half;
+            col %= half;
+            size = half;
+            pos = Some(next);
+        }
+    }
+
+    pub fn posset(&self, vertex: VertexIndex) -> Option<Vec<VertexIndex>> {
+        let &row = self.dense.get(&vertex)?;
+        let mut out = Vec::new();
+        self.direct(self.size, row, 0, None, &mut out);
+        Some(out.into_iter().map(|col| self.index[col]).collect())
+    }
+
+    pub fn preset(&self, vertex: VertexIndex) -> Option<Vec<VertexIndex>> {
+        let &col = self.dense.get(&vertex)?;
+        let mut out = Vec::new();
+        self.reverse(self.size, 0, col, None, &mut out);
+        Some(out.into_iter().map(|row| self.index[row]).collect())
+    }
+
+    #[inline]
+    pub fn labels(&self, vertex: VertexIndex) -> Option<&[V]> {
+        self.dense
+            .get(&vertex)
+            .map(|&dense| self.labels[dense].as_slice())
+    }
+
+    #[inline]
+    pub fn get<W>(&self, label: &W) -> Option<&[VertexIndex]>
+    where
+        V: Borrow<W>,
+        W: Eq + Hash + ?Sized,
+    {
+        self.aliases.get(label).map(|set| set.as_slice())
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    pub fn compress(&self) -> CompressedGraph<V> {
+        let index: Vec<VertexIndex> = self.nodes.iter().map(|(index, _)| index).collect();
+        let dense: HashMap<VertexIndex, usize> = index
+            .iter()
+            .enumerate()
+            .map(|(dense, &index)| (index, dense))
+            .collect();
+        let cells: Vec<(usize, usize)> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .map(|(src, dst)| (dense[&src], dense[&dst]))
+            .collect();
+
+        let mut size = K;
+        while size < index.len() {
+            size *= K;
+        }
+        let mut levels = Vec::new();
+        build(&mut levels, &cells, size, 0);
+        let leaves = levels.pop().unwrap();
+        let tree: Vec<bool> = levels.into_iter().flatten().collect();
+
+        let labels = index
+            .iter()
+            .map(|&v| self.nodes[v].aliases.iter().cloned().collect())
+            .collect();
+        let aliases = self
+            .aliases
+            .iter()
+            .map(|(label, set)| (label.clone(), set.iter().cloned().collect()))
+            .collect();
+
+        CompressedGraph {
+            size,
+            tree: RankBits::from_bits(&tree),
+            leaves: RankBits::from_bits(&leaves),
+            index,
+            dense,
+            labels,
+            aliases,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn compressed_queries() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..11).map(|i| graph.insert(i)).collect();
+        for pair in vertices.windows(2) {
+            graph.connect(pair[0], pair[1]);
+        }
+        graph.connect(vertices[10], vertices[0]);
+        graph.connect(vertices[3], vertices[7]);
+        graph.remove(vertices[5]);
+
+        let compressed = graph.compress();
+        assert_eq!(compressed.len(), 10);
+        for &src in vertices.iter() {
+            for &dst in vertices.iter() {
+                let expected = graph.posset(src).is_some_and(|mut p| p.any(|v| v == dst));
+                assert_eq!(compressed.contains_edge(src, dst), expected);
+            }
+            let pos: Option<HashSet<VertexIndex>> =
+                compressed.posset(src).map(|p| p.into_iter().collect());
+            let pre: Option<HashSet<VertexIndex>> =
+                compressed.preset(src).map(|p| p.into_iter().collect());
+            assert_eq!(pos, graph.posset(src).map(|p| p.collect()));
+            assert_eq!(pre, graph.preset(src).map(|p| p.collect()));
+        }
+        assert_eq!(compressed.get(&3), Some(&[vertices[3]][..]));
+        assert_eq!(compressed.labels(vertices[4]), Some(&[4][..]));
+        assert_eq!(compressed.labels(vertices[5]), None);
+    }
+}
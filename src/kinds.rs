@@ -0,0 +1,159 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::hash::Hash;
+
+/// Tag telling apart edges of different nature kept in the same graph, such
+/// as control and data dependences. Edges are of kind `EdgeKind::DEFAULT`
+/// until tagged, and only tagged edges take up space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeKind(pub u8);
+
+impl EdgeKind {
+    pub const DEFAULT: EdgeKind = EdgeKind(0);
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Connects `src` to `dst` and tags the edge with `kind`, replacing the
+    /// kind of an existing edge.
+    pub fn connect_with_kind(
+        &mut self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        kind: EdgeKind,
+    ) -> Option<EdgeIndex> {
+        let edge = self.connect(src, dst)?;
+        self.set_edge_kind(edge, kind);
+        Some(edge)
+    }
+
+    /// Tags an existing edge with `kind`. Returns `false` if it is missing.
+    pub fn set_edge_kind(&mut self, edge: EdgeIndex, kind: EdgeKind) -> bool {
+        let (src, dst) = edge;
+        if !self
+            .nodes
+            .get(src)
+            .is_some_and(|node| node.posset.contains(&dst))
+        {
+            return false;
+        }
+        if kind == EdgeKind::DEFAULT {
+            self.edge_kinds.remove(&edge);
+        } else {
+            self.edge_kinds.insert(edge, kind);
+        }
+        true
+    }
+
+    #[inline]
+    pub fn edge_kind(&self, edge: EdgeIndex) -> Option<EdgeKind> {
+        let (src, dst) = edge;
+        if self.nodes.get(src)?.posset.contains(&dst) {
+            Some(self.edge_kinds.get(&edge).cloned().unwrap_or_default())
+        } else {
+            None
+        }
+    }
+
+    /// Successors of `vertex` along edges of `kind`.
+    pub fn successors_by_kind(
+        &self,
+        vertex: VertexIndex,
+        kind: EdgeKind,
+    ) -> Option<impl Iterator<Item = VertexIndex> + '_> {
+        let node = self.nodes.get(vertex)?;
+        Some(node.posset.iter().cloned().filter(move |&dst| {
+            self.edge_kinds
+                .get(&(vertex, dst))
+                .cloned()
+                .unwrap_or_default()
+                == kind
+        }))
+    }
+
+    /// Predecessors of `vertex` along edges of `kind`.
+    pub fn predecessors_by_kind(
+        &self,
+        vertex: VertexIndex,
+        kind: EdgeKind,
+    ) -> Option<impl Iterator<Item = VertexIndex> + '_> {
+        let node = self.nodes.get(vertex)?;
+        Some(node.preset.iter().cloned().filter(move |&src| {
+            self.edge_kinds
+                .get(&(src, vertex))
+                .cloned()
+                .unwrap_or_default()
+                == kind
+        }))
+    }
+
+    /// Moves the kinds of edges touching `members` onto the vertex `id` that
+    /// replaces them. Edges collapsing into one keep the highest kind.
+    pub(crate) fn merge_edge_kinds(&mut self, members: &[VertexIndex], id: VertexIndex) {
+        let moved: Vec<(EdgeIndex, EdgeKind)> = self
+            .edge_kinds
+            .iter()
+            .filter(|((src, dst), _)| members.contains(src) || members.contains(dst))
+            .map(|(&edge, &kind)| (edge, kind))
+            .collect();
+        let rename = |v| if members.contains(&v) { id } else { v };
+        for (edge, _) in moved.iter() {
+            self.edge_kinds.remove(edge);
+        }
+        for ((src, dst), kind) in moved {
+            let slot = self
+                .edge_kinds
+                .entry((rename(src), rename(dst)))
+                .or_insert(kind);
+            *slot = kind.max(*slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const DATA: EdgeKind = EdgeKind(1);
+
+    #[test]
+    fn edge_kinds() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect_with_kind(a, c, DATA);
+        graph.connect_with_kind(b, c, DATA);
+
+        assert_eq!(graph.edge_kind((a, b)), Some(EdgeKind::DEFAULT));
+        assert_eq!(graph.edge_kind((b, a)), None);
+        assert_eq!(
+            graph
+                .successors_by_kind(a, DATA)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![c]
+        );
+        assert_eq!(
+            graph
+                .successors_by_kind(a, EdgeKind::DEFAULT)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![b]
+        );
+        assert_eq!(graph.predecessors_by_kind(c, DATA).unwrap().count(), 2);
+
+        assert_eq!(graph.flip_edge((a, c)), Some((c, a)));
+        assert_eq!(graph.edge_kind((c, a)), Some(DATA));
+        graph.disconnect((c, a));
+        graph.connect(c, a);
+        assert_eq!(graph.edge_kind((c, a)), Some(EdgeKind::DEFAULT));
+
+        let bc = graph.merge_vertices(vec![b, c]);
+        assert_eq!(graph.edge_kind((bc, bc)), Some(DATA));
+        assert_eq!(graph.edge_kind((a, bc)), Some(EdgeKind::DEFAULT));
+        assert!(graph.set_edge_kind((a, bc), DATA));
+        assert!(!graph.set_edge_kind((a, a), DATA));
+        graph.remove(a);
+        assert_eq!(graph.edge_kinds.len(), 1);
+    }
+}
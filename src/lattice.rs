@@ -0,0 +1,159 @@
+use crate::{Graph, VertexIndex};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+/// Why a join or meet does not exist for a pair of vertices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoundError {
+    MissingVertex(VertexIndex),
+    /// The graph has a cycle, so reachability is not a partial order.
+    Cyclic,
+    /// The vertices have no common bound at all.
+    NoBound,
+    /// Several incomparable minimal (or maximal) common bounds exist.
+    Ambiguous(Vec<VertexIndex>),
+}
+
+impl fmt::Display for BoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundError::MissingVertex(vertex) => write!(f, "vertex {} does not exist", vertex),
+            BoundError::Cyclic => write!(f, "graph is cyclic"),
+            BoundError::NoBound => write!(f, "vertices have no common bound"),
+            BoundError::Ambiguous(candidates) => {
+                write!(f, "no unique bound, candidates are {:?}", candidates)
+            }
+        }
+    }
+}
+
+impl Error for BoundError {}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    fn bound(
+        &self,
+        one: VertexIndex,
+        other: VertexIndex,
+        upper: bool,
+    ) -> Result<VertexIndex, BoundError> {
+        for &vertex in [one, other].iter() {
+            if !self.nodes.contains(vertex) {
+                return Err(BoundError::MissingVertex(vertex));
+            }
+        }
+        if self.topological_order().is_none() {
+            return Err(BoundError::Cyclic);
+        }
+
+        let forward = |vertex: VertexIndex| {
+            let node = &self.nodes[vertex];
+            if upper {
+                &node.posset
+            } else {
+                &node.preset
+            }
+        };
+        let backward = |vertex: VertexIndex| {
+            let node = &self.nodes[vertex];
+            if upper {
+                &node.preset
+            } else {
+                &node.posset
+            }
+        };
+        let closure = |start: VertexIndex| {
+            let mut seen: HashSet<VertexIndex> = vec![start].into_iter().collect();
+            let mut stack = vec![start];
+            while let Some(vertex) = stack.pop() {
+                for &next in forward(vertex).iter() {
+                    if seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            seen
+        };
+
+        let common: HashSet<VertexIndex> = closure(one)
+            .intersection(&closure(other))
+            .cloned()
+            .collect();
+        let mut extremes: Vec<VertexIndex> = common
+            .iter()
+            .filter(|&&vertex| backward(vertex).is_disjoint(&common))
+            .cloned()
+            .collect();
+        match extremes.len() {
+            0 => Err(BoundError::NoBound),
+            1 => Ok(extremes[0]),
+            _ => {
+                extremes.sort_unstable();
+                Err(BoundError::Ambiguous(extremes))
+            }
+        }
+    }
+
+    /// Join of `one` and `other` in the reachability order, where a vertex is
+    /// below every vertex it reaches.
+    #[inline]
+    pub fn least_upper_bound(
+        &self,
+        one: VertexIndex,
+        other: VertexIndex,
+    ) -> Result<VertexIndex, BoundError> {
+        self.bound(one, other, true)
+    }
+
+    /// Meet of `one` and `other` in the reachability order.
+    #[inline]
+    pub fn greatest_lower_bound(
+        &self,
+        one: VertexIndex,
+        other: VertexIndex,
+    ) -> Result<VertexIndex, BoundError> {
+        self.bound(one, other, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn lattice_bounds() {
+        let mut graph = Graph::new();
+        let bottom = graph.insert("bottom");
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let top = graph.insert("top");
+        graph.connect(bottom, a);
+        graph.connect(bottom, b);
+        graph.connect(a, c);
+        graph.connect(b, c);
+        graph.connect(c, top);
+
+        assert_eq!(graph.least_upper_bound(a, b), Ok(c));
+        assert_eq!(graph.least_upper_bound(a, c), Ok(c));
+        assert_eq!(graph.greatest_lower_bound(a, b), Ok(bottom));
+        assert_eq!(graph.greatest_lower_bound(top, a), Ok(a));
+
+        let d = graph.insert("d");
+        graph.connect(a, d);
+        graph.connect(b, d);
+        assert_eq!(
+            graph.least_upper_bound(a, b),
+            Err(BoundError::Ambiguous(vec![c, d]))
+        );
+        assert_eq!(graph.least_upper_bound(top, d), Err(BoundError::NoBound));
+        assert_eq!(
+            graph.least_upper_bound(a, 42),
+            Err(BoundError::MissingVertex(42))
+        );
+
+        graph.connect(top, bottom);
+        assert_eq!(graph.greatest_lower_bound(a, b), Err(BoundError::Cyclic));
+    }
+}
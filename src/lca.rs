@@ -0,0 +1,103 @@
+use crate::{BitSet, Graph, VertexIndex};
+use std::hash::Hash;
+
+/// Answers lowest common ancestor queries on a snapshot of a DAG. Every
+/// vertex stores its ancestor set as a bitset over topological positions,
+/// so memory grows quadratically with the number of vertices while a query
+/// only scans the common ancestors of its arguments.
+#[derive(Clone, Debug)]
+pub struct LcaIndex {
+    position: Vec<usize>,
+    vertices: Vec<VertexIndex>,
+    ancestors: Vec<BitSet>,
+}
+
+impl LcaIndex {
+    /// Every lowest common ancestor of `one` and `other`: common ancestors,
+    /// counting each vertex as its own ancestor, with no common ancestor
+    /// below them. Sorted by index and empty when nothing is shared. `None`
+    /// if either vertex was not in the snapshot.
+    pub fn lca(&self, one: VertexIndex, other: VertexIndex) -> Option<Vec<VertexIndex>> {
+        let one = *self.position.get(one).filter(|&&p| p != usize::MAX)?;
+        let other = *self.position.get(other).filter(|&&p| p != usize::MAX)?;
+        let mut common = self.ancestors[one].clone();
+        common.intersect_with(&self.ancestors[other]);
+
+        // Descendants come later in topological order, so walking backwards
+        // meets each lowest ancestor before anything above it.
+        let mut covered = BitSet::default();
+        let mut lowest = Vec::new();
+        for pos in common.iter().rev() {
+            if !covered.contains(pos) {
+                lowest.push(self.vertices[pos]);
+                covered.union_with(&self.ancestors[pos]);
+            }
+        }
+        lowest.sort_unstable();
+        Some(lowest)
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Builds an `LcaIndex`, or `None` if the graph is cyclic.
+    pub fn lca_index(&self) -> Option<LcaIndex> {
+        let _span = trace_span!("lca_index", vertices = self.nodes.len());
+        let order = self.topological_order()?;
+        let mut position = vec![usize::MAX; self.nodes.capacity()];
+        for (pos, &vertex) in order.iter().enumerate() {
+            position[vertex] = pos;
+        }
+
+        let mut ancestors: Vec<BitSet> = Vec::with_capacity(order.len());
+        for (pos, &vertex) in order.iter().enumerate() {
+            let mut set = BitSet::default();
+            set.grow(order.len());
+            set.insert(pos);
+            for &pred in self.nodes[vertex].preset.iter() {
+                set.union_with(&ancestors[position[pred]]);
+            }
+            ancestors.push(set);
+        }
+
+        Some(LcaIndex {
+            position,
+            vertices: order,
+            ancestors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn lowest_common_ancestors() {
+        let mut graph = Graph::new();
+        let root = graph.insert("root");
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        let lone = graph.insert("lone");
+        graph.connect(root, a);
+        graph.connect(root, b);
+        graph.connect(a, c);
+        graph.connect(b, c);
+        graph.connect(a, d);
+        graph.connect(b, d);
+        graph.connect(d, e);
+
+        let index = graph.lca_index().unwrap();
+        assert_eq!(index.lca(c, d), Some(vec![a, b]));
+        assert_eq!(index.lca(c, e), Some(vec![a, b]));
+        assert_eq!(index.lca(d, e), Some(vec![d]));
+        assert_eq!(index.lca(a, b), Some(vec![root]));
+        assert_eq!(index.lca(e, lone), Some(vec![]));
+        assert_eq!(index.lca(e, 42), None);
+
+        graph.connect(e, root);
+        assert!(graph.lca_index().is_none());
+    }
+}
@@ -2,8 +2,99 @@ use slab::Slab;
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+#[macro_use]
+mod trace;
+mod automorphism;
+mod bitset;
+mod bridges;
+mod chordal;
+mod coloring;
+mod csr;
+mod cycles;
+mod diffusion;
+mod dominators;
+pub mod fixtures;
+mod flow;
+mod incremental;
+mod invariants;
+mod isomorphism;
 mod iterators;
+mod jobs;
+#[cfg(feature = "compression")]
+mod k2tree;
+mod kinds;
+mod lattice;
+mod layout;
+mod lca;
+mod merge;
+mod metrics;
+#[cfg(feature = "testing")]
+mod naive;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod paths;
+mod poset;
+#[cfg(feature = "prefix")]
+mod prefix;
+mod reachability;
+mod region;
+#[cfg(feature = "registry")]
+mod registry;
+mod resolve;
+mod robustness;
+mod sampling;
+mod schedule;
+mod search;
+mod separators;
+mod series_parallel;
+mod shape;
+mod simplify;
+mod symbols;
+mod transform;
+mod traversal;
+mod treewidth;
+mod union;
+mod unionfind;
+mod weights;
+mod workspace;
+use bitset::BitSet;
+use incremental::Maintained;
+#[cfg(feature = "prefix")]
+use prefix::PrefixIndex;
+use unionfind::UnionFind;
 use iterators::{LabelIter, VertexIter};
+pub use csr::{CsrHeader, CsrLabels, GpuCsr, CSR_LAYOUT_VERSION};
+pub use diffusion::{SpreadModel, SpreadRun, SpreadSummary};
+pub use dominators::{Dominators, DominatorsIter};
+pub use flow::MaxFlow;
+pub use incremental::{DegreeStream, DegreeTally};
+pub use jobs::{Cancelled, ClosureJob, Job, Progress, SccJob};
+#[cfg(feature = "compression")]
+pub use k2tree::CompressedGraph;
+pub use kinds::EdgeKind;
+pub use lattice::BoundError;
+pub use layout::CrossingHeuristic;
+pub use lca::LcaIndex;
+pub use merge::MergePreview;
+pub use metrics::Distance;
+#[cfg(feature = "testing")]
+pub use naive::NaiveGraph;
+pub use paths::{Path, PathError, ShortestPaths};
+pub use reachability::ReachabilityIndex;
+pub use region::RegionEditor;
+#[cfg(feature = "registry")]
+pub use registry::{GraphHandle, Registry};
+pub use resolve::{AliasPolicy, Candidate, ResolveError};
+pub use robustness::{RemovalCurve, RemovalOrder, RobustnessMetric};
+pub use sampling::CappedBuilder;
+pub use search::SearchMode;
+pub use series_parallel::SpTree;
+pub use symbols::{NameIter, SymbolTable};
+pub use transform::{PassReport, PassStats, Pipeline, TransformError};
+pub use traversal::{ordered_worklist, TraversalContext, Worklist};
+pub use treewidth::{EliminationHeuristic, TreeDecomposition};
+pub use unionfind::ComponentGraph;
+pub use workspace::{GraphId, VertexRef, Workspace};
 
 pub type VertexIndex = usize;
 pub type EdgeIndex = (VertexIndex, VertexIndex);
@@ -13,6 +104,8 @@ struct Vertex<V: Hash + Eq + Clone> {
     pub preset: HashSet<VertexIndex>,
     pub posset: HashSet<VertexIndex>,
     pub aliases: HashSet<V>,
+    /// Order of insertion, increasing even when indices are reused.
+    pub stamp: u64,
 }
 
 impl<V: Hash + Eq + Clone> Vertex<V> {
@@ -21,6 +114,7 @@ impl<V: Hash + Eq + Clone> Vertex<V> {
             preset: HashSet::new(),
             posset: HashSet::new(),
             aliases: HashSet::new(),
+            stamp: 0,
         }
     }
 
@@ -33,56 +127,147 @@ impl<V: Hash + Eq + Clone> Vertex<V> {
     }
 }
 
+/// How indices freed by vertex removal are handed out again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum IndexAllocation {
+    /// Reuse the most recently freed index first, keeping storage compact.
+    #[default]
+    ReuseFirst,
+    /// Always hand out a fresh, strictly increasing index so that a stale
+    /// index can never alias a newer vertex. Freed slots are never reclaimed
+    /// and each insertion skips over all of them, so heavy churn gets slower.
+    NeverReuse,
+}
+
+#[derive(Clone)]
 pub struct Graph<V: Hash + Eq + Clone> {
     nodes: Slab<Vertex<V>>,
     trunks: HashSet<VertexIndex>,
     leaves: HashSet<VertexIndex>,
     aliases: HashMap<V, HashSet<VertexIndex>>,
+    label_weights: HashMap<VertexIndex, HashMap<V, f64>>,
+    flags: Vec<BitSet>,
+    pinned: HashSet<VertexIndex>,
+    protected: HashSet<EdgeIndex>,
+    edge_kinds: HashMap<EdgeIndex, EdgeKind>,
+    #[cfg(feature = "prefix")]
+    prefixes: Option<PrefixIndex<V>>,
+    components: Option<UnionFind>,
+    maintained: Maintained,
+    allocation: IndexAllocation,
+    next_index: VertexIndex,
+    next_stamp: u64,
+}
+
+impl<V: Eq + Hash + Clone> Default for Graph<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<V: Eq + Hash + Clone> Graph<V> {
     #[inline]
     pub fn new() -> Self {
+        Self::with_index_allocation(IndexAllocation::default())
+    }
+
+    #[inline]
+    pub fn with_index_allocation(allocation: IndexAllocation) -> Self {
         Graph {
             nodes: Slab::new(),
             trunks: HashSet::new(),
             leaves: HashSet::new(),
             aliases: HashMap::new(),
+            label_weights: HashMap::new(),
+            flags: Vec::new(),
+            pinned: HashSet::new(),
+            protected: HashSet::new(),
+            edge_kinds: HashMap::new(),
+            #[cfg(feature = "prefix")]
+            prefixes: None,
+            components: None,
+            maintained: Maintained::default(),
+            allocation,
+            next_index: 0,
+            next_stamp: 0,
         }
     }
 
     #[inline]
-    pub fn insert(&mut self, label: V) -> VertexIndex {
-        let node = Vertex::new();
+    pub fn index_allocation(&self) -> IndexAllocation {
+        self.allocation
+    }
+
+    /// Stores `node` under an index chosen by the allocation strategy. In
+    /// never-reuse mode the freed slots are briefly occupied so the slab
+    /// places `node` past every index handed out so far.
+    fn allocate(&mut self, mut node: Vertex<V>) -> VertexIndex {
+        node.stamp = self.next_stamp;
+        self.next_stamp += 1;
+        let mut holes = Vec::new();
+        if self.allocation == IndexAllocation::NeverReuse {
+            while self.nodes.vacant_key() < self.next_index {
+                holes.push(self.nodes.insert(Vertex::new()));
+            }
+        }
         let index = self.nodes.insert(node);
+        for hole in holes.into_iter().rev() {
+            self.nodes.remove(hole);
+        }
+        self.next_index = self.next_index.max(index + 1);
+        index
+    }
+
+    fn insert_unlabeled(&mut self) -> VertexIndex {
+        let index = self.allocate(Vertex::new());
         self.trunks.insert(index);
         self.leaves.insert(index);
+        if let Some(sets) = self.components.as_mut() {
+            sets.make_set(index);
+        }
+        self.maintain_insert(index);
+        trace_event!(vertex = index, "insert");
+        index
+    }
+
+    #[inline]
+    pub fn insert(&mut self, label: V) -> VertexIndex {
+        let index = self.insert_unlabeled();
         self.append_label(index, label);
+        self.debug_check_invariants();
         index
     }
 
     fn remove_vertex_node(&mut self, vertex: VertexIndex) -> Vertex<V> {
         let node = self.nodes.get(vertex).unwrap();
         let posset: Vec<VertexIndex> = node.posset.iter().cloned().collect();
-        let preset: Vec<VertexIndex> = node.posset.iter().cloned().collect();
+        let preset: Vec<VertexIndex> = node.preset.iter().cloned().collect();
 
         for dst in posset {
-            self.disconnect((vertex, dst));
+            self.protected.remove(&(vertex, dst));
+            self.unlink((vertex, dst));
         }
 
         for src in preset {
-            self.disconnect((src, vertex));
+            self.protected.remove(&(src, vertex));
+            self.unlink((src, vertex));
         }
 
         self.trunks.remove(&vertex);
         self.leaves.remove(&vertex);
+        self.forget_flags(vertex);
+        self.label_weights.remove(&vertex);
 
         let node = self.nodes.remove(vertex);
+        self.maintain_remove(vertex);
         for id in node.aliases.iter() {
             let set = self.aliases.get_mut(id).unwrap();
             set.remove(&vertex);
             if set.is_empty() {
                 self.aliases.remove(id);
+                #[cfg(feature = "prefix")]
+                self.unindex_label(id);
             }
         }
 
@@ -91,14 +276,97 @@ impl<V: Eq + Hash + Clone> Graph<V> {
 
     #[inline]
     pub fn remove(&mut self, vertex: VertexIndex) -> bool {
-        if self.nodes.contains(vertex) {
+        if self.nodes.contains(vertex) && !self.pinned.contains(&vertex) {
+            trace_event!(vertex, "remove");
             self.remove_vertex_node(vertex);
+            self.debug_check_invariants();
             true
         } else {
             false
         }
     }
 
+    pub fn retain_vertices<F>(&mut self, mut keep: F) -> usize
+    where
+        F: FnMut(VertexIndex) -> bool,
+    {
+        let doomed: Vec<VertexIndex> = self
+            .nodes
+            .iter()
+            .map(|(index, _)| index)
+            .filter(|index| !self.pinned.contains(index))
+            .filter(|&index| !keep(index))
+            .collect();
+
+        for &vertex in doomed.iter() {
+            self.remove_vertex_node(vertex);
+        }
+        self.debug_check_invariants();
+        doomed.len()
+    }
+
+    pub fn retain_edges<F>(&mut self, mut keep: F) -> usize
+    where
+        F: FnMut(EdgeIndex) -> bool,
+    {
+        let doomed: Vec<EdgeIndex> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .filter(|edge| !self.protected.contains(edge))
+            .filter(|&edge| !keep(edge))
+            .collect();
+
+        for &edge in doomed.iter() {
+            self.unlink(edge);
+        }
+        self.debug_check_invariants();
+        doomed.len()
+    }
+
+    #[inline]
+    pub fn protect_edge(&mut self, edge: EdgeIndex) -> bool {
+        let (src, dst) = edge;
+        self.nodes
+            .get(src)
+            .is_some_and(|node| node.posset.contains(&dst))
+            && self.protected.insert(edge)
+    }
+
+    #[inline]
+    pub fn unprotect_edge(&mut self, edge: EdgeIndex) -> bool {
+        self.protected.remove(&edge)
+    }
+
+    #[inline]
+    pub fn is_protected(&self, edge: EdgeIndex) -> Option<bool> {
+        let (src, dst) = edge;
+        if self.nodes.get(src)?.posset.contains(&dst) {
+            Some(self.protected.contains(&edge))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn pin_vertex(&mut self, vertex: VertexIndex) -> bool {
+        self.nodes.contains(vertex) && self.pinned.insert(vertex)
+    }
+
+    #[inline]
+    pub fn unpin_vertex(&mut self, vertex: VertexIndex) -> bool {
+        self.pinned.remove(&vertex)
+    }
+
+    #[inline]
+    pub fn is_pinned(&self, vertex: VertexIndex) -> Option<bool> {
+        if self.nodes.contains(vertex) {
+            Some(self.pinned.contains(&vertex))
+        } else {
+            None
+        }
+    }
+
     #[inline]
     pub fn posset<'a>(&'a self, vertex: VertexIndex) -> Option<VertexIter<'a>> {
         self.nodes
@@ -124,7 +392,7 @@ impl<V: Eq + Hash + Clone> Graph<V> {
     }
 
     #[inline]
-    pub fn get<'a, 'b, W>(&'a self, label: &'b W) -> Option<VertexIter<'a>>
+    pub fn get<'a, W>(&'a self, label: &W) -> Option<VertexIter<'a>>
     where
         V: Borrow<W>,
         W: Eq + Hash + ?Sized,
@@ -148,33 +416,68 @@ impl<V: Eq + Hash + Clone> Graph<V> {
 
     #[inline]
     pub fn append_label(&mut self, vertex: VertexIndex, label: V) -> bool {
-        let set = self.aliases.entry(label.clone()).or_default();
-
         match self.nodes.get_mut(vertex) {
             None => false,
             Some(node) => {
                 node.aliases.insert(label.clone());
-                set.insert(vertex);
+                #[cfg(feature = "prefix")]
+                self.index_label(&label);
+                self.aliases.entry(label).or_default().insert(vertex);
+                self.debug_check_invariants();
                 true
             }
         }
     }
 
+    /// Attaches many labels at once, grouping the pairs by label so each
+    /// alias set is looked up and grown once. Pairs naming a missing vertex
+    /// are skipped. Returns how many pairs were attached.
+    pub fn append_labels_bulk<I>(&mut self, pairs: I) -> usize
+    where
+        I: IntoIterator<Item = (VertexIndex, V)>,
+    {
+        let mut grouped: HashMap<V, Vec<VertexIndex>> = HashMap::new();
+        for (vertex, label) in pairs {
+            if self.nodes.contains(vertex) {
+                grouped.entry(label).or_default().push(vertex);
+            }
+        }
+
+        let mut attached = 0;
+        for (label, vertices) in grouped {
+            attached += vertices.len();
+            for &vertex in vertices.iter() {
+                self.nodes[vertex].aliases.insert(label.clone());
+            }
+            #[cfg(feature = "prefix")]
+            self.index_label(&label);
+            let set = self.aliases.entry(label).or_default();
+            set.reserve(vertices.len());
+            set.extend(vertices);
+        }
+        self.debug_check_invariants();
+        attached
+    }
+
     pub fn remove_vertex_label(&mut self, label: &V, vertex: VertexIndex) -> bool {
         let node = match self.nodes.get_mut(vertex) {
             None => return false,
             Some(node) => node,
         };
         node.aliases.remove(label);
+        self.forget_label_weight(vertex, label);
 
         let set = match self.aliases.get_mut(label) {
             None => return false,
             Some(set) => set,
         };
         set.remove(&vertex);
-        if set.len() == 0 {
+        if set.is_empty() {
             self.aliases.remove(label);
+            #[cfg(feature = "prefix")]
+            self.unindex_label(label);
         }
+        self.debug_check_invariants();
 
         true
     }
@@ -185,15 +488,64 @@ impl<V: Eq + Hash + Clone> Graph<V> {
             return None;
         }
 
-        self.nodes.get_mut(src).unwrap().posset.insert(dst);
+        let fresh = self.nodes.get_mut(src).unwrap().posset.insert(dst);
         self.nodes.get_mut(dst).unwrap().preset.insert(src);
         self.trunks.remove(&dst);
         self.leaves.remove(&src);
+        if let Some(sets) = self.components.as_mut() {
+            sets.union(src, dst);
+        }
+        if fresh {
+            self.maintain_connect(src, dst);
+        }
+        trace_event!(src, dst, "connect");
+        self.debug_check_invariants();
 
         Some((src, dst))
     }
 
+    #[inline]
     pub fn disconnect(&mut self, edge: EdgeIndex) -> bool {
+        let removed = !self.protected.contains(&edge) && self.unlink(edge);
+        self.debug_check_invariants();
+        removed
+    }
+
+    /// Reverses `edge`, returning the reversed edge. If the reverse already
+    /// exists the two collapse into it. Returns `None` if the edge is missing
+    /// or protected; a self-loop is left as is.
+    pub fn flip_edge(&mut self, edge: EdgeIndex) -> Option<EdgeIndex> {
+        let (src, dst) = edge;
+        if self.protected.contains(&edge) || !self.nodes.get(src)?.posset.contains(&dst) {
+            return None;
+        }
+        if src == dst {
+            return Some(edge);
+        }
+
+        Some(self.reverse(edge))
+    }
+
+    /// Replaces `edge` by its reverse, carrying over its protection and kind
+    /// unless the reverse already exists, in which case it keeps its own
+    /// kind and becomes protected if either was.
+    pub(crate) fn reverse(&mut self, edge: EdgeIndex) -> EdgeIndex {
+        let (src, dst) = edge;
+        let existed = self.nodes[dst].posset.contains(&src);
+        let protected = self.protected.remove(&edge);
+        let kind = self.edge_kinds.get(&edge).cloned();
+        self.unlink(edge);
+        self.connect(dst, src);
+        if protected {
+            self.protected.insert((dst, src));
+        }
+        if let (Some(kind), false) = (kind, existed) {
+            self.edge_kinds.insert((dst, src), kind);
+        }
+        (dst, src)
+    }
+
+    fn unlink(&mut self, edge: EdgeIndex) -> bool {
         let (src, dst) = edge;
         if !(self.nodes.contains(src) && self.nodes.contains(dst)) {
             return false;
@@ -203,6 +555,7 @@ impl<V: Eq + Hash + Clone> Graph<V> {
         if !src_node.posset.remove(&dst) {
             return false;
         }
+        self.edge_kinds.remove(&edge);
         if src_node.posset.is_empty() {
             self.leaves.insert(src);
         }
@@ -214,6 +567,11 @@ impl<V: Eq + Hash + Clone> Graph<V> {
         if dst_node.preset.is_empty() {
             self.trunks.insert(dst);
         }
+        if let Some(sets) = self.components.as_mut() {
+            sets.dirty = true;
+        }
+        self.maintain_disconnect(src, dst);
+        trace_event!(src, dst, "disconnect");
 
         true
     }
@@ -228,7 +586,29 @@ impl<V: Eq + Hash + Clone> Graph<V> {
         VertexIter::new(self.leaves.iter())
     }
 
-    pub fn merge_vertices<'a, I>(&mut self, vertices: I) -> VertexIndex
+    /// Inserts a vertex labeled `label` with an edge to every current trunk,
+    /// making it the only trunk. Undo with `remove`.
+    pub fn add_supersource(&mut self, label: V) -> VertexIndex {
+        let trunks = self.trunks().sorted();
+        let source = self.insert(label);
+        for trunk in trunks {
+            self.connect(source, trunk);
+        }
+        source
+    }
+
+    /// Inserts a vertex labeled `label` with an edge from every current leaf,
+    /// making it the only leaf. Undo with `remove`.
+    pub fn add_supersink(&mut self, label: V) -> VertexIndex {
+        let leaves = self.leaves().sorted();
+        let sink = self.insert(label);
+        for leaf in leaves {
+            self.connect(leaf, sink);
+        }
+        sink
+    }
+
+    pub fn merge_vertices<I>(&mut self, vertices: I) -> VertexIndex
     where
         I: IntoIterator<Item = VertexIndex>,
     {
@@ -236,26 +616,34 @@ impl<V: Eq + Hash + Clone> Graph<V> {
         let mut preset = HashSet::new();
         let mut aliases = HashSet::new();
         let mut reflexive = false;
+        let mut pinned = false;
+        let members: Vec<VertexIndex> = vertices.into_iter().collect();
+        let _span = trace_span!("merge_vertices");
+        let weights = self.merged_label_weights(&members);
 
-        for vertex in vertices {
+        for &vertex in members.iter() {
             let node = self.nodes.remove(vertex);
+            self.trunks.remove(&vertex);
+            self.leaves.remove(&vertex);
+            self.forget_flags(vertex);
+            pinned |= self.pinned.remove(&vertex);
 
             for id in node.posset {
-                if id != vertex {
+                if members.contains(&id) {
+                    reflexive = true;
+                } else {
                     posset.insert(id);
                     let other = self.nodes.get_mut(id).unwrap();
                     other.preset.remove(&vertex);
-                } else {
-                    reflexive = true;
                 }
             }
             for id in node.preset {
-                if id != vertex {
+                if members.contains(&id) {
+                    reflexive = true;
+                } else {
                     preset.insert(id);
                     let other = self.nodes.get_mut(id).unwrap();
                     other.posset.remove(&vertex);
-                } else {
-                    reflexive = true;
                 }
             }
             for alias in node.aliases {
@@ -265,23 +653,46 @@ impl<V: Eq + Hash + Clone> Graph<V> {
             }
         }
 
-        let id = self.nodes.insert(Vertex::new());
+        let id = self.allocate(Vertex::new());
+        if let Some(sets) = self.components.as_mut() {
+            sets.make_set(id);
+            sets.dirty = true;
+        }
+        self.maintain_merge(&members, id);
+        if pinned {
+            self.pinned.insert(id);
+        }
+
+        let moved: Vec<EdgeIndex> = self
+            .protected
+            .iter()
+            .filter(|(src, dst)| members.contains(src) || members.contains(dst))
+            .cloned()
+            .collect();
+        for (src, dst) in moved {
+            self.protected.remove(&(src, dst));
+            let rename = |v| if members.contains(&v) { id } else { v };
+            self.protected.insert((rename(src), rename(dst)));
+        }
+        self.merge_edge_kinds(&members, id);
 
         if reflexive {
             posset.insert(id);
             preset.insert(id);
         }
 
-        if !posset.is_empty() {
-            self.leaves.remove(&id);
+        if posset.is_empty() {
+            self.leaves.insert(id);
+        } else {
             for &dst in posset.iter() {
                 self.trunks.remove(&dst);
                 self.nodes.get_mut(dst).unwrap().preset.insert(id);
             }
         };
 
-        if !preset.is_empty() {
-            self.trunks.remove(&id);
+        if preset.is_empty() {
+            self.trunks.insert(id);
+        } else {
             for &src in preset.iter() {
                 self.leaves.remove(&src);
                 self.nodes.get_mut(src).unwrap().posset.insert(id);
@@ -296,10 +707,73 @@ impl<V: Eq + Hash + Clone> Graph<V> {
         node.posset = posset;
         node.preset = preset;
         node.aliases = aliases;
+        if !weights.is_empty() {
+            self.label_weights.insert(id, weights);
+        }
+        self.debug_check_invariants();
 
         id
     }
 
+    #[inline]
+    pub fn set_flag(&mut self, vertex: VertexIndex, slot: usize) -> bool {
+        if !self.nodes.contains(vertex) {
+            return false;
+        }
+
+        if slot >= self.flags.len() {
+            self.flags.resize_with(slot + 1, BitSet::new);
+        }
+        let bits = &mut self.flags[slot];
+        bits.grow(self.nodes.capacity());
+        bits.insert(vertex);
+        true
+    }
+
+    #[inline]
+    pub fn unset_flag(&mut self, vertex: VertexIndex, slot: usize) -> bool {
+        self.nodes.contains(vertex)
+            && self
+                .flags
+                .get_mut(slot)
+                .is_some_and(|bits| bits.remove(vertex))
+    }
+
+    #[inline]
+    pub fn flag(&self, vertex: VertexIndex, slot: usize) -> Option<bool> {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+
+        Some(self.flags.get(slot).is_some_and(|bits| bits.contains(vertex)))
+    }
+
+    #[inline]
+    pub fn clear_flags(&mut self, slot: usize) {
+        if let Some(bits) = self.flags.get_mut(slot) {
+            bits.clear();
+        }
+    }
+
+    fn forget_flags(&mut self, vertex: VertexIndex) {
+        for bits in self.flags.iter_mut() {
+            bits.remove(vertex);
+        }
+    }
+
+    /// Neighbors of `vertex` with edge directions ignored, excluding itself.
+    #[inline]
+    pub(crate) fn undirected_neighbors(
+        &self,
+        vertex: VertexIndex,
+    ) -> impl Iterator<Item = VertexIndex> + '_ {
+        let node = &self.nodes[vertex];
+        node.posset
+            .union(&node.preset)
+            .cloned()
+            .filter(move |&other| other != vertex)
+    }
+
     #[inline]
     pub fn are_vertices_parallel(&self, one: VertexIndex, other: VertexIndex) -> Option<bool> {
         let one = self.nodes.get(one)?;
@@ -307,6 +781,59 @@ impl<V: Eq + Hash + Clone> Graph<V> {
 
         Some(one.is_parallel(other))
     }
+
+    /// Partitions every vertex into classes of mutually parallel vertices in
+    /// one pass. Parallelism is not transitive, so the classes are built
+    /// greedily in index order: each vertex joins the first class whose
+    /// members are all parallel to it, or starts a new one. Only vertices
+    /// sharing a neighbor with it are compared. Classes are sorted, as are
+    /// their members.
+    pub fn parallel_classes(&self) -> Vec<Vec<VertexIndex>> {
+        let mut classes: Vec<Vec<VertexIndex>> = Vec::new();
+        let mut class_of: HashMap<VertexIndex, usize> = HashMap::new();
+        let mut isolated: Option<usize> = None;
+        for (vertex, node) in self.nodes.iter() {
+            let siblings: HashSet<VertexIndex> = if !node.preset.is_empty() {
+                node.preset
+                    .iter()
+                    .flat_map(|&src| self.nodes[src].posset.iter().cloned())
+                    .collect()
+            } else if !node.posset.is_empty() {
+                node.posset
+                    .iter()
+                    .flat_map(|&dst| self.nodes[dst].preset.iter().cloned())
+                    .collect()
+            } else {
+                let id = *isolated.get_or_insert_with(|| {
+                    classes.push(Vec::new());
+                    classes.len() - 1
+                });
+                classes[id].push(vertex);
+                continue;
+            };
+
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for sibling in siblings {
+                if let Some(&id) = class_of.get(&sibling) {
+                    if node.is_parallel(&self.nodes[sibling]) {
+                        *counts.entry(id).or_insert(0) += 1;
+                    }
+                }
+            }
+            let id = counts
+                .into_iter()
+                .filter(|&(id, count)| count == classes[id].len())
+                .map(|(id, _)| id)
+                .min()
+                .unwrap_or_else(|| {
+                    classes.push(Vec::new());
+                    classes.len() - 1
+                });
+            classes[id].push(vertex);
+            class_of.insert(vertex, id);
+        }
+        classes
+    }
 }
 
 #[cfg(test)]
@@ -360,6 +887,29 @@ mod tests {
         assert_eq!(graph.are_vertices_parallel(a, g), Some(false));
         assert_eq!(graph.are_vertices_parallel(i, h), Some(false));
         assert_eq!(graph.are_vertices_parallel(h, i), Some(false));
+        assert_eq!(
+            graph.parallel_classes(),
+            vec![vec![a, b], vec![c, d], vec![e, f], vec![g], vec![h], vec![i]]
+        );
+
+        let j = graph.insert("j");
+        let k = graph.insert("k");
+        graph.connect(c, j);
+        assert_eq!(graph.parallel_classes()[2], vec![e, f, j]);
+        assert_eq!(graph.parallel_classes().last(), Some(&vec![k]));
+    }
+
+    #[test]
+    fn sorted_iterators() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..20).map(|i| graph.insert(i % 3)).collect();
+        for &vertex in vertices[1..].iter().rev() {
+            graph.connect(vertices[0], vertex);
+        }
+        assert_eq!(graph.posset(vertices[0]).unwrap().sorted(), vertices[1..]);
+        assert_eq!(graph.trunks().sorted(), vec![vertices[0]]);
+        assert_eq!(graph.leaves().sorted(), vertices[1..]);
+        assert_eq!(graph.get(&1).unwrap().sorted(), vec![1, 4, 7, 10, 13, 16, 19]);
     }
 
     #[test]
@@ -614,4 +1164,211 @@ mod tests {
         assert_eq!(c_pre, vec![b, d].into_iter().collect());
         assert_eq!(d_pre, vec![b].into_iter().collect());
     }
+
+    #[test]
+    fn merge_updates_terminals() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let x = graph.insert("x");
+        let y = graph.insert("y");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+
+        let inner = graph.merge_vertices(vec![b, c]);
+        let inner_pos: HashSet<VertexIndex> = graph.posset(inner).unwrap().collect();
+        let inner_pre: HashSet<VertexIndex> = graph.preset(inner).unwrap().collect();
+        assert_eq!(inner_pos, vec![inner, d].into_iter().collect());
+        assert_eq!(inner_pre, vec![inner, a].into_iter().collect());
+
+        let lone = graph.merge_vertices(vec![x, y]);
+        let trunks: HashSet<VertexIndex> = graph.trunks().collect();
+        let leaves: HashSet<VertexIndex> = graph.leaves().collect();
+        assert_eq!(trunks, vec![a, lone].into_iter().collect());
+        assert_eq!(leaves, vec![d, lone].into_iter().collect());
+    }
+
+    #[test]
+    fn append_label_to_missing_vertex() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        assert!(!graph.append_label(a + 1, "b"));
+        assert!(graph.get("b").is_none());
+        assert_eq!(graph.aliases.len(), 1);
+    }
+
+    #[test]
+    fn remove_connected_vertex() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+
+        assert!(graph.remove(b));
+        assert_eq!(graph.posset(a).unwrap().count(), 0);
+        assert_eq!(graph.preset(c).unwrap().count(), 0);
+        assert_eq!(graph.leaves().collect::<HashSet<_>>(), vec![a, c].into_iter().collect());
+        assert_eq!(graph.trunks().collect::<HashSet<_>>(), vec![a, c].into_iter().collect());
+    }
+
+    #[test]
+    fn vertex_flags() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+
+        assert_eq!(graph.flag(a, 0), Some(false));
+        assert!(graph.set_flag(a, 0));
+        assert!(graph.set_flag(b, 3));
+        assert_eq!(graph.flag(a, 0), Some(true));
+        assert_eq!(graph.flag(b, 0), Some(false));
+        assert_eq!(graph.flag(b, 3), Some(true));
+
+        graph.clear_flags(0);
+        assert_eq!(graph.flag(a, 0), Some(false));
+        assert_eq!(graph.flag(b, 3), Some(true));
+
+        graph.remove(b);
+        assert_eq!(graph.flag(b, 3), None);
+        assert!(!graph.set_flag(b, 3));
+        let c = graph.insert("c");
+        assert_eq!(graph.flag(c, 3), Some(false));
+    }
+
+    #[test]
+    fn pinned_vertices() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+
+        assert!(graph.pin_vertex(b));
+        assert!(!graph.pin_vertex(b));
+        assert_eq!(graph.is_pinned(b), Some(true));
+        assert!(!graph.remove(b));
+        assert_eq!(graph.retain_vertices(|_| false), 2);
+        assert_eq!(graph.is_pinned(b), Some(true));
+        assert_eq!(graph.is_pinned(a), None);
+
+        let d = graph.insert("d");
+        let bd = graph.merge_vertices(vec![b, d]);
+        assert_eq!(graph.is_pinned(bd), Some(true));
+        assert!(graph.unpin_vertex(bd));
+        assert!(graph.remove(bd));
+    }
+
+    #[test]
+    fn index_allocation() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        graph.insert("b");
+        graph.remove(a);
+        assert_eq!(graph.insert("c"), a);
+
+        let mut graph = Graph::with_index_allocation(IndexAllocation::NeverReuse);
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.remove(a);
+        graph.remove(c);
+        let d = graph.insert("d");
+        assert!(d > c);
+        assert!(!graph.remove(c));
+        let merged = graph.merge_vertices(vec![b, d]);
+        assert!(merged > d);
+        assert_eq!(graph.count_labeled(&"a"), None);
+        assert_eq!(graph.index_allocation(), IndexAllocation::NeverReuse);
+    }
+
+    #[test]
+    fn protected_edges() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+
+        assert!(graph.protect_edge((a, c)));
+        assert!(!graph.protect_edge((c, a)));
+        assert_eq!(graph.is_protected((a, c)), Some(true));
+        assert_eq!(graph.is_protected((a, b)), Some(false));
+        assert_eq!(graph.is_protected((c, a)), None);
+
+        assert!(!graph.disconnect((a, c)));
+        assert_eq!(graph.retain_edges(|_| false), 2);
+        assert_eq!(graph.outdegree(a), Some(1));
+
+        let ab = graph.merge_vertices(vec![a, b]);
+        assert_eq!(graph.is_protected((ab, c)), Some(true));
+        assert!(graph.unprotect_edge((ab, c)));
+        assert!(graph.disconnect((ab, c)));
+    }
+
+    #[test]
+    fn super_terminals() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, c);
+        graph.connect(b, c);
+
+        let source = graph.add_supersource("source");
+        let sink = graph.add_supersink("sink");
+        assert_eq!(graph.trunks().sorted(), vec![source]);
+        assert_eq!(graph.leaves().sorted(), vec![sink]);
+        assert_eq!(graph.posset(source).unwrap().sorted(), vec![a, b, d]);
+        assert_eq!(graph.preset(sink).unwrap().sorted(), vec![c, d]);
+
+        graph.remove(source);
+        graph.remove(sink);
+        assert_eq!(graph.trunks().sorted(), vec![a, b, d]);
+        assert_eq!(graph.leaves().sorted(), vec![c, d]);
+    }
+
+    #[test]
+    fn bulk_labels() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let pairs = vec![(a, "x"), (b, "x"), (42, "x"), (b, "y"), (a, "a"), (b, "x")];
+        assert_eq!(graph.append_labels_bulk(pairs), 5);
+        assert_eq!(graph.get(&"x").unwrap().sorted(), vec![a, b]);
+        assert_eq!(graph.count_labeled(&"a"), Some(1));
+        assert_eq!(graph.labels(b).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn flip_edges() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, b);
+
+        assert_eq!(graph.trunks().sorted(), vec![a]);
+        assert_eq!(graph.flip_edge((a, b)), Some((b, a)));
+        assert_eq!(graph.trunks().count(), 0);
+        assert_eq!(graph.leaves().sorted(), vec![a]);
+        assert_eq!(graph.flip_edge((b, c)), Some((c, b)));
+        assert_eq!(graph.outdegree(b), Some(1));
+        assert_eq!(graph.flip_edge((b, c)), None);
+
+        graph.protect_edge((b, a));
+        assert_eq!(graph.flip_edge((b, a)), None);
+        graph.connect(c, c);
+        assert_eq!(graph.flip_edge((c, c)), Some((c, c)));
+    }
 }
@@ -0,0 +1,104 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// What `merge_vertices` would produce, computed without touching the graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergePreview<V: Eq + Hash> {
+    /// Predecessors of the merged vertex outside the merged set.
+    pub preset: HashSet<VertexIndex>,
+    /// Successors of the merged vertex outside the merged set.
+    pub posset: HashSet<VertexIndex>,
+    pub labels: HashSet<V>,
+    /// Whether the merged vertex gets a self-loop, which happens as soon as
+    /// any edge joins two members or a member to itself.
+    pub self_loop: bool,
+    /// The edges among members that collapse into the self-loop, sorted.
+    pub internal_edges: Vec<EdgeIndex>,
+    /// Whether the merged vertex inherits a pin.
+    pub pinned: bool,
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Describes the result of merging `vertices` without merging them.
+    /// Returns `None` if any of them is missing or listed twice, cases in
+    /// which `merge_vertices` would panic.
+    pub fn preview_merge<I>(&self, vertices: I) -> Option<MergePreview<V>>
+    where
+        I: IntoIterator<Item = VertexIndex>,
+    {
+        let mut members = HashSet::new();
+        for vertex in vertices {
+            if !self.nodes.contains(vertex) || !members.insert(vertex) {
+                return None;
+            }
+        }
+
+        let mut preview = MergePreview {
+            preset: HashSet::new(),
+            posset: HashSet::new(),
+            labels: HashSet::new(),
+            self_loop: false,
+            internal_edges: Vec::new(),
+            pinned: false,
+        };
+        for &vertex in members.iter() {
+            let node = &self.nodes[vertex];
+            for &dst in node.posset.iter() {
+                if members.contains(&dst) {
+                    preview.internal_edges.push((vertex, dst));
+                } else {
+                    preview.posset.insert(dst);
+                }
+            }
+            preview.preset.extend(
+                node.preset
+                    .iter()
+                    .filter(|src| !members.contains(src))
+                    .cloned(),
+            );
+            preview.labels.extend(node.aliases.iter().cloned());
+            preview.pinned |= self.pinned.contains(&vertex);
+        }
+        preview.internal_edges.sort_unstable();
+        preview.self_loop = !preview.internal_edges.is_empty();
+        Some(preview)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn preview_merge() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(d, a);
+        graph.pin_vertex(b);
+
+        let preview = graph.preview_merge(vec![a, b]).unwrap();
+        assert_eq!(preview.posset, vec![c].into_iter().collect());
+        assert_eq!(preview.preset, vec![d].into_iter().collect());
+        assert_eq!(preview.labels, vec!["a", "b"].into_iter().collect());
+        assert_eq!(preview.internal_edges, vec![(a, b)]);
+        assert!(preview.self_loop && preview.pinned);
+
+        let mut after = graph.clone();
+        let merged = after.merge_vertices(vec![a, b]);
+        let posset: HashSet<VertexIndex> = after.posset(merged).unwrap().collect();
+        assert_eq!(posset, vec![c, merged].into_iter().collect());
+        assert_eq!(after.is_pinned(merged), Some(true));
+
+        let preview = graph.preview_merge(vec![c, d]).unwrap();
+        assert!(!preview.self_loop && !preview.pinned);
+        assert!(graph.preview_merge(vec![a, a]).is_none());
+        assert!(graph.preview_merge(vec![a, 42]).is_none());
+        assert_eq!(graph.nodes.len(), 4);
+    }
+}
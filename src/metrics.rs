@@ -0,0 +1,335 @@
+use crate::{EdgeIndex, Graph, Vertex, VertexIndex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::vec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Distance {
+    Shortest,
+    Longest,
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    fn layer_distances(
+        &self,
+        sources: &HashSet<VertexIndex>,
+        forward: bool,
+        metric: Distance,
+    ) -> Option<HashMap<VertexIndex, usize>> {
+        let next = |node: &Vertex<V>| -> Vec<VertexIndex> {
+            if forward {
+                node.posset.iter().cloned().collect()
+            } else {
+                node.preset.iter().cloned().collect()
+            }
+        };
+        let mut distance: HashMap<VertexIndex, usize> =
+            sources.iter().map(|&vertex| (vertex, 0)).collect();
+        let mut queue: VecDeque<VertexIndex> = sources.iter().cloned().collect();
+
+        match metric {
+            Distance::Shortest => {
+                while let Some(vertex) = queue.pop_front() {
+                    let depth = distance[&vertex] + 1;
+                    for dst in next(&self.nodes[vertex]) {
+                        distance.entry(dst).or_insert_with(|| {
+                            queue.push_back(dst);
+                            depth
+                        });
+                    }
+                }
+            }
+            Distance::Longest => {
+                let mut pending: HashMap<VertexIndex, usize> = HashMap::new();
+                let mut visited = 0;
+                while let Some(vertex) = queue.pop_front() {
+                    visited += 1;
+                    let depth = distance[&vertex] + 1;
+                    for dst in next(&self.nodes[vertex]) {
+                        let best = distance.entry(dst).or_insert(depth);
+                        *best = depth.max(*best);
+                        let node = &self.nodes[dst];
+                        let count = pending.entry(dst).or_insert(if forward {
+                            node.preset.len()
+                        } else {
+                            node.posset.len()
+                        });
+                        *count -= 1;
+                        if *count == 0 {
+                            queue.push_back(dst);
+                        }
+                    }
+                }
+                if visited != self.nodes.len() {
+                    return None;
+                }
+            }
+        }
+
+        Some(distance)
+    }
+
+    /// Distance of every vertex from the nearest (or farthest) trunk. Vertices
+    /// not reachable from any trunk are omitted; `Distance::Longest` returns
+    /// `None` on cyclic graphs.
+    #[inline]
+    pub fn depth_from_trunks(&self, metric: Distance) -> Option<HashMap<VertexIndex, usize>> {
+        self.layer_distances(&self.trunks, true, metric)
+    }
+
+    /// Distance of every vertex to the nearest (or farthest) leaf, following
+    /// the same conventions as `depth_from_trunks`.
+    #[inline]
+    pub fn height_to_leaves(&self, metric: Distance) -> Option<HashMap<VertexIndex, usize>> {
+        self.layer_distances(&self.leaves, false, metric)
+    }
+
+    /// Number of shortest directed paths between ordered pairs of vertices
+    /// crossing each edge, paths tied for shortest sharing their pair's unit.
+    /// Computed with Brandes' algorithm in O(VE).
+    pub fn edge_betweenness(&self) -> HashMap<EdgeIndex, f64> {
+        let _span = trace_span!("edge_betweenness", vertices = self.nodes.len());
+        let capacity = self.nodes.capacity();
+        let mut betweenness: HashMap<EdgeIndex, f64> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| ((src, dst), 0.0)))
+            .collect();
+        let mut sigma = vec![0.0; capacity];
+        let mut depth = vec![usize::MAX; capacity];
+        let mut delta = vec![0.0; capacity];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for (source, _) in self.nodes.iter() {
+            for &vertex in order.iter() {
+                sigma[vertex] = 0.0;
+                depth[vertex] = usize::MAX;
+                delta[vertex] = 0.0;
+            }
+            order.clear();
+            sigma[source] = 1.0;
+            depth[source] = 0;
+            queue.push_back(source);
+            while let Some(vertex) = queue.pop_front() {
+                order.push(vertex);
+                for &dst in self.nodes[vertex].posset.iter() {
+                    if depth[dst] == usize::MAX {
+                        depth[dst] = depth[vertex] + 1;
+                        queue.push_back(dst);
+                    }
+                    if depth[dst] == depth[vertex] + 1 {
+                        sigma[dst] += sigma[vertex];
+                    }
+                }
+            }
+            for &vertex in order.iter().rev() {
+                for &pred in self.nodes[vertex].preset.iter() {
+                    if depth[pred] != usize::MAX && depth[pred] + 1 == depth[vertex] {
+                        let share = sigma[pred] / sigma[vertex] * (1.0 + delta[vertex]);
+                        *betweenness.get_mut(&(pred, vertex)).unwrap() += share;
+                        delta[pred] += share;
+                    }
+                }
+            }
+        }
+
+        betweenness
+    }
+
+    /// Links among the undirected neighbors of `vertex` and how many
+    /// neighbors it has.
+    fn neighbor_links(&self, vertex: VertexIndex) -> (usize, usize) {
+        let neighbors: HashSet<VertexIndex> = self.undirected_neighbors(vertex).collect();
+        let links = neighbors
+            .iter()
+            .map(|&one| {
+                self.undirected_neighbors(one)
+                    .filter(|other| *other > one && neighbors.contains(other))
+                    .count()
+            })
+            .sum();
+        (links, neighbors.len())
+    }
+
+    /// Fraction of pairs of neighbors of `vertex` that are themselves
+    /// adjacent, over the underlying undirected simple graph. Zero for
+    /// vertices with fewer than two neighbors.
+    pub fn local_clustering(&self, vertex: VertexIndex) -> Option<f64> {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+        let (links, degree) = self.neighbor_links(vertex);
+        if degree < 2 {
+            return Some(0.0);
+        }
+        Some(2.0 * links as f64 / (degree * (degree - 1)) as f64)
+    }
+
+    /// Mean `local_clustering` over all vertices, zero for an empty graph.
+    pub fn average_clustering(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .nodes
+            .iter()
+            .map(|(vertex, _)| self.local_clustering(vertex).unwrap())
+            .sum();
+        total / self.nodes.len() as f64
+    }
+
+    /// Global clustering coefficient, or transitivity: the fraction of
+    /// connected triples of the underlying undirected simple graph that
+    /// close into triangles. Zero if there are no such triples.
+    pub fn global_clustering(&self) -> f64 {
+        let (closed, triples) = self
+            .nodes
+            .iter()
+            .fold((0, 0), |(closed, triples), (vertex, _)| {
+                let (links, degree) = self.neighbor_links(vertex);
+                (
+                    closed + links,
+                    triples + degree * degree.saturating_sub(1) / 2,
+                )
+            });
+        if triples == 0 {
+            0.0
+        } else {
+            closed as f64 / triples as f64
+        }
+    }
+
+    /// Sum of `weight` over the incoming edges of `vertex`, its weighted
+    /// indegree.
+    pub fn vertex_in_strength<F>(&self, vertex: VertexIndex, weight: F) -> Option<f64>
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        let node = self.nodes.get(vertex)?;
+        Some(node.preset.iter().map(|&src| weight((src, vertex))).sum())
+    }
+
+    /// Sum of `weight` over the outgoing edges of `vertex`, its weighted
+    /// outdegree.
+    pub fn vertex_out_strength<F>(&self, vertex: VertexIndex, weight: F) -> Option<f64>
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        let node = self.nodes.get(vertex)?;
+        Some(node.posset.iter().map(|&dst| weight((vertex, dst))).sum())
+    }
+
+    fn strongest(
+        &self,
+        k: usize,
+        strength: impl Fn(VertexIndex) -> f64,
+    ) -> vec::IntoIter<(VertexIndex, f64)> {
+        let mut ranked: Vec<(VertexIndex, f64)> = self
+            .nodes
+            .iter()
+            .map(|(vertex, _)| (vertex, strength(vertex)))
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(k);
+        ranked.into_iter()
+    }
+
+    /// The `k` vertices with the highest `vertex_in_strength` and their
+    /// strengths, strongest first and the lowest index winning ties.
+    pub fn top_in_strength<F>(&self, k: usize, weight: F) -> vec::IntoIter<(VertexIndex, f64)>
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        self.strongest(k, |vertex| {
+            self.vertex_in_strength(vertex, &weight).unwrap()
+        })
+    }
+
+    /// The `k` vertices with the highest `vertex_out_strength`, like
+    /// `top_in_strength`.
+    pub fn top_out_strength<F>(&self, k: usize, weight: F) -> vec::IntoIter<(VertexIndex, f64)>
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        self.strongest(k, |vertex| {
+            self.vertex_out_strength(vertex, &weight).unwrap()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn depths_and_heights() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+        graph.connect(c, d);
+
+        let shortest = graph.depth_from_trunks(Distance::Shortest).unwrap();
+        let longest = graph.depth_from_trunks(Distance::Longest).unwrap();
+        assert_eq!(shortest[&d], 2);
+        assert_eq!(longest[&d], 3);
+        assert_eq!(longest[&a], 0);
+
+        let shortest = graph.height_to_leaves(Distance::Shortest).unwrap();
+        let longest = graph.height_to_leaves(Distance::Longest).unwrap();
+        assert_eq!(shortest[&a], 2);
+        assert_eq!(longest[&a], 3);
+        assert_eq!(longest[&c], 1);
+
+        let e = graph.insert("e");
+        graph.connect(d, e);
+        graph.connect(e, d);
+        assert_eq!(graph.depth_from_trunks(Distance::Longest), None);
+        assert_eq!(graph.depth_from_trunks(Distance::Shortest).unwrap()[&e], 3);
+    }
+
+    #[test]
+    fn clustering() {
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..5).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [(0, 1), (1, 2), (2, 0), (0, 2), (2, 3), (3, 4), (4, 4)].iter() {
+            graph.connect(v[src], v[dst]);
+        }
+
+        assert_eq!(graph.local_clustering(v[0]), Some(1.0));
+        assert_eq!(graph.local_clustering(v[2]), Some(1.0 / 3.0));
+        assert_eq!(graph.local_clustering(v[4]), Some(0.0));
+        assert_eq!(graph.local_clustering(42), None);
+        assert_eq!(graph.average_clustering(), (2.0 + 1.0 / 3.0) / 5.0);
+        // One triangle closes three of the six connected triples.
+        assert_eq!(graph.global_clustering(), 0.5);
+        assert_eq!(Graph::<u8>::new().global_clustering(), 0.0);
+    }
+
+    #[test]
+    fn strength() {
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [(0, 1), (0, 2), (1, 2), (3, 2), (2, 2)].iter() {
+            graph.connect(v[src], v[dst]);
+        }
+        let weight = |(src, dst): EdgeIndex| (src + dst) as f64 / 2.0;
+
+        assert_eq!(graph.vertex_in_strength(v[2], weight), Some(7.0));
+        assert_eq!(graph.vertex_out_strength(v[0], weight), Some(1.5));
+        assert_eq!(graph.vertex_out_strength(v[1], weight), Some(1.5));
+        assert_eq!(graph.vertex_in_strength(v[3], weight), Some(0.0));
+        assert_eq!(graph.vertex_in_strength(42, weight), None);
+        assert_eq!(
+            graph.top_out_strength(3, weight).collect::<Vec<_>>(),
+            vec![(v[3], 2.5), (v[2], 2.0), (v[0], 1.5)]
+        );
+        assert_eq!(graph.top_in_strength(1, weight).next(), Some((v[2], 7.0)));
+        assert_eq!(graph.top_in_strength(10, weight).len(), 4);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::hash::Hash;
+use std::vec;
+
+/// Reference implementation of the core `Graph` API kept deliberately
+/// simple: an ordered edge set and a label list per vertex, with every query
+/// answered by a linear scan. Meant for differential testing of code that
+/// extends the crate, never for real workloads.
+///
+/// Indices are handed out like `Graph::new` does, reusing the most recently
+/// freed one first, so the same sequence of calls yields the same indices.
+/// Edge protection, pins and flags are not modelled.
+#[derive(Clone, Debug)]
+pub struct NaiveGraph<V: Eq + Hash + Clone> {
+    labels: BTreeMap<VertexIndex, Vec<V>>,
+    edges: BTreeSet<EdgeIndex>,
+    free: Vec<VertexIndex>,
+    next_index: VertexIndex,
+}
+
+impl<V: Eq + Hash + Clone> Default for NaiveGraph<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Eq + Hash + Clone> NaiveGraph<V> {
+    #[inline]
+    pub fn new() -> Self {
+        NaiveGraph {
+            labels: BTreeMap::new(),
+            edges: BTreeSet::new(),
+            free: Vec::new(),
+            next_index: 0,
+        }
+    }
+
+    fn allocate(&mut self, labels: Vec<V>) -> VertexIndex {
+        let vertex = self.free.pop().unwrap_or_else(|| {
+            self.next_index += 1;
+            self.next_index - 1
+        });
+        self.labels.insert(vertex, labels);
+        vertex
+    }
+
+    #[inline]
+    pub fn insert(&mut self, label: V) -> VertexIndex {
+        self.allocate(vec![label])
+    }
+
+    pub fn remove(&mut self, vertex: VertexIndex) -> bool {
+        if self.labels.remove(&vertex).is_none() {
+            return false;
+        }
+        self.edges
+            .retain(|&(src, dst)| src != vertex && dst != vertex);
+        self.free.push(vertex);
+        true
+    }
+
+    #[inline]
+    pub fn contains(&self, vertex: VertexIndex) -> bool {
+        self.labels.contains_key(&vertex)
+    }
+
+    /// All vertices in increasing index order.
+    #[inline]
+    pub fn vertices(&self) -> Vec<VertexIndex> {
+        self.labels.keys().cloned().collect()
+    }
+
+    pub fn posset(&self, vertex: VertexIndex) -> Option<vec::IntoIter<VertexIndex>> {
+        if !self.contains(vertex) {
+            return None;
+        }
+        let succs: Vec<VertexIndex> = self
+            .edges
+            .iter()
+            .filter(|&&(src, _)| src == vertex)
+            .map(|&(_, dst)| dst)
+            .collect();
+        Some(succs.into_iter())
+    }
+
+    pub fn preset(&self, vertex: VertexIndex) -> Option<vec::IntoIter<VertexIndex>> {
+        if !self.contains(vertex) {
+            return None;
+        }
+        let preds: Vec<VertexIndex> = self
+            .edges
+            .iter()
+            .filter(|&&(_, dst)| dst == vertex)
+            .map(|&(src, _)| src)
+            .collect();
+        Some(preds.into_iter())
+    }
+
+    #[inline]
+    pub fn indegree(&self, vertex: VertexIndex) -> Option<usize> {
+        self.preset(vertex).map(|preds| preds.len())
+    }
+
+    #[inline]
+    pub fn outdegree(&self, vertex: VertexIndex) -> Option<usize> {
+        self.posset(vertex).map(|succs| succs.len())
+    }
+
+    pub fn get<W>(&self, label: &W) -> Option<vec::IntoIter<VertexIndex>>
+    where
+        V: Borrow<W>,
+        W: Eq + Hash + ?Sized,
+    {
+        let vertices: Vec<VertexIndex> = self
+            .labels
+            .iter()
+            .filter(|(_, labels)| labels.iter().any(|other| other.borrow() == label))
+            .map(|(&vertex, _)| vertex)
+            .collect();
+        if vertices.is_empty() {
+            None
+        } else {
+            Some(vertices.into_iter())
+        }
+    }
+
+    #[inline]
+    pub fn labels(&self, vertex: VertexIndex) -> Option<std::slice::Iter<'_, V>> {
+        self.labels.get(&vertex).map(|labels| labels.iter())
+    }
+
+    #[inline]
+    pub fn count_labeled<W: Borrow<V>>(&self, label: &W) -> Option<usize> {
+        self.get(label.borrow()).map(|vertices| vertices.len())
+    }
+
+    pub fn append_label(&mut self, vertex: VertexIndex, label: V) -> bool {
+        match self.labels.get_mut(&vertex) {
+            None => false,
+            Some(labels) => {
+                if !labels.contains(&label) {
+                    labels.push(label);
+                }
+                true
+            }
+        }
+    }
+
+    /// Mirrors `Graph::remove_vertex_label`, which reports `false` when no
+    /// vertex at all carries `label`.
+    pub fn remove_vertex_label(&mut self, label: &V, vertex: VertexIndex) -> bool {
+        if !self.contains(vertex) || self.get(label).is_none() {
+            return false;
+        }
+        self.labels
+            .get_mut(&vertex)
+            .unwrap()
+            .retain(|other| other != label);
+        true
+    }
+
+    #[inline]
+    pub fn connect(&mut self, src: VertexIndex, dst: VertexIndex) -> Option<EdgeIndex> {
+        if !(self.contains(src) && self.contains(dst)) {
+            return None;
+        }
+        self.edges.insert((src, dst));
+        Some((src, dst))
+    }
+
+    #[inline]
+    pub fn disconnect(&mut self, edge: EdgeIndex) -> bool {
+        self.edges.remove(&edge)
+    }
+
+    pub fn trunks(&self) -> vec::IntoIter<VertexIndex> {
+        let trunks: Vec<VertexIndex> = self
+            .labels
+            .keys()
+            .filter(|&&vertex| !self.edges.iter().any(|&(_, dst)| dst == vertex))
+            .cloned()
+            .collect();
+        trunks.into_iter()
+    }
+
+    pub fn leaves(&self) -> vec::IntoIter<VertexIndex> {
+        let leaves: Vec<VertexIndex> = self
+            .labels
+            .keys()
+            .filter(|&&vertex| !self.edges.iter().any(|&(src, _)| src == vertex))
+            .cloned()
+            .collect();
+        leaves.into_iter()
+    }
+
+    /// Replaces `vertices` by a single vertex carrying the union of their
+    /// labels and edges; edges among them become a self-loop. Panics if any
+    /// of them does not exist.
+    pub fn merge_vertices<I>(&mut self, vertices: I) -> VertexIndex
+    where
+        I: IntoIterator<Item = VertexIndex>,
+    {
+        let members: Vec<VertexIndex> = vertices.into_iter().collect();
+        let edges: Vec<EdgeIndex> = self
+            .edges
+            .iter()
+            .filter(|(src, dst)| members.contains(src) || members.contains(dst))
+            .cloned()
+            .collect();
+        let mut labels = Vec::new();
+        for &vertex in members.iter() {
+            for label in self.labels[&vertex].iter() {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+            self.remove(vertex);
+        }
+
+        let id = self.allocate(labels);
+        let rename = |vertex| {
+            if members.contains(&vertex) {
+                id
+            } else {
+                vertex
+            }
+        };
+        for (src, dst) in edges {
+            self.edges.insert((rename(src), rename(dst)));
+        }
+        id
+    }
+
+    /// Compares every vertex, edge and label against `graph`, describing the
+    /// first difference found.
+    pub fn compare(&self, graph: &Graph<V>) -> Result<(), String> {
+        let mut vertices: Vec<VertexIndex> = graph.nodes.iter().map(|(vertex, _)| vertex).collect();
+        vertices.sort_unstable();
+        if vertices != self.vertices() {
+            return Err(format!(
+                "vertices differ: expected {:?}, found {:?}",
+                self.vertices(),
+                vertices
+            ));
+        }
+
+        for (&vertex, labels) in self.labels.iter() {
+            let expected: BTreeSet<VertexIndex> = self.posset(vertex).unwrap().collect();
+            let found: BTreeSet<VertexIndex> = graph.posset(vertex).unwrap().collect();
+            if expected != found {
+                return Err(format!(
+                    "successors of {} differ: expected {:?}, found {:?}",
+                    vertex, expected, found
+                ));
+            }
+            let expected: BTreeSet<VertexIndex> = self.preset(vertex).unwrap().collect();
+            let found: BTreeSet<VertexIndex> = graph.preset(vertex).unwrap().collect();
+            if expected != found {
+                return Err(format!(
+                    "predecessors of {} differ: expected {:?}, found {:?}",
+                    vertex, expected, found
+                ));
+            }
+            let expected: HashSet<&V> = labels.iter().collect();
+            let found: HashSet<&V> = graph.labels(vertex).unwrap().collect();
+            if expected != found {
+                return Err(format!("labels of {} differ", vertex));
+            }
+        }
+
+        let expected: BTreeSet<VertexIndex> = self.trunks().collect();
+        let found: BTreeSet<VertexIndex> = graph.trunks().collect();
+        if expected != found {
+            return Err(format!(
+                "trunks differ: expected {:?}, found {:?}",
+                expected, found
+            ));
+        }
+        let expected: BTreeSet<VertexIndex> = self.leaves().collect();
+        let found: BTreeSet<VertexIndex> = graph.leaves().collect();
+        if expected != found {
+            return Err(format!(
+                "leaves differ: expected {:?}, found {:?}",
+                expected, found
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn naive_agrees() {
+        let mut graph = Graph::new();
+        let mut naive = NaiveGraph::new();
+        for label in ["a", "b", "c", "d"] {
+            assert_eq!(graph.insert(label), naive.insert(label));
+        }
+        for &(src, dst) in [(0, 1), (1, 2), (2, 0), (2, 3)].iter() {
+            assert_eq!(graph.connect(src, dst), naive.connect(src, dst));
+        }
+        assert_eq!(graph.append_label(3, "a"), naive.append_label(3, "a"));
+        assert_eq!(naive.count_labeled(&"a"), Some(2));
+        assert_eq!(naive.compare(&graph), Ok(()));
+
+        assert_eq!(graph.remove(1), naive.remove(1));
+        assert_eq!(graph.disconnect((2, 0)), naive.disconnect((2, 0)));
+        assert_eq!(
+            graph.remove_vertex_label(&"b", 3),
+            naive.remove_vertex_label(&"b", 3)
+        );
+        assert_eq!(graph.insert("e"), naive.insert("e"));
+        assert_eq!(
+            graph.merge_vertices(vec![0, 2]),
+            naive.merge_vertices(vec![0, 2])
+        );
+        assert_eq!(naive.compare(&graph), Ok(()));
+
+        graph.connect(3, 3);
+        assert!(naive
+            .compare(&graph)
+            .unwrap_err()
+            .contains("successors of 3"));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use rayon::prelude::*;
+use rayon::Scope;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::sync::Mutex;
+
+/// Number of label maps built independently by `par_from_edge_list`.
+const SHARDS: usize = 64;
+/// Fewest edges handled by one task of `par_from_edge_list`.
+const MIN_CHUNK: usize = 1024;
+
+impl<V: Eq + Hash + Clone + Sync> Graph<V> {
+    /// Builds a graph from `(src, dst)` label pairs, hashing the labels in
+    /// parallel: each label goes to one of several shards by hash, every
+    /// shard is deduplicated on its own, and the edges are resolved against
+    /// the shards in parallel. Only vertex creation and linking run
+    /// sequentially. Vertices are numbered in order of first appearance,
+    /// as if inserted one edge at a time.
+    pub fn par_from_edge_list(edges: &[(V, V)]) -> Self {
+        let _span = trace_span!("par_from_edge_list", edges = edges.len());
+        let state = RandomState::new();
+        let shard_of = |label: &V| state.hash_one(label) as usize % SHARDS;
+        let chunk_len = (edges.len() / (4 * rayon::current_num_threads())).max(MIN_CHUNK);
+
+        let chunks: Vec<Vec<HashMap<&V, usize>>> = edges
+            .par_chunks(chunk_len)
+            .enumerate()
+            .map(|(chunk, part)| {
+                let mut shards = vec![HashMap::new(); SHARDS];
+                for (i, (src, dst)) in part.iter().enumerate() {
+                    let pos = 2 * (chunk * chunk_len + i);
+                    shards[shard_of(src)].entry(src).or_insert(pos);
+                    shards[shard_of(dst)].entry(dst).or_insert(pos + 1);
+                }
+                shards
+            })
+            .collect();
+        let mut shards: Vec<HashMap<&V, usize>> = (0..SHARDS)
+            .into_par_iter()
+            .map(|shard| {
+                let mut merged = HashMap::new();
+                for chunk in chunks.iter() {
+                    for (&label, &pos) in chunk[shard].iter() {
+                        merged.entry(label).or_insert(pos);
+                    }
+                }
+                merged
+            })
+            .collect();
+        drop(chunks);
+
+        let mut firsts: Vec<(usize, &V)> = shards
+            .iter()
+            .flat_map(|shard| shard.iter().map(|(&label, &pos)| (pos, label)))
+            .collect();
+        firsts.par_sort_unstable_by_key(|&(pos, _)| pos);
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = firsts
+            .iter()
+            .map(|&(_, label)| graph.insert(label.clone()))
+            .collect();
+        shards.par_iter_mut().for_each(|shard| {
+            for pos in shard.values_mut() {
+                let rank = firsts.binary_search_by_key(pos, |&(first, _)| first);
+                *pos = vertices[rank.unwrap()];
+            }
+        });
+
+        let links: Vec<EdgeIndex> = edges
+            .par_iter()
+            .map(|(src, dst)| (shards[shard_of(src)][src], shards[shard_of(dst)][dst]))
+            .collect();
+        for (src, dst) in links {
+            graph.connect(src, dst);
+        }
+        graph
+    }
+
+    /// Strongly connected components computed in parallel with the
+    /// forward-backward algorithm: the component of a pivot is the
+    /// intersection of what it reaches and what reaches it, and the three
+    /// remaining parts are independent subproblems. Vertices without edges
+    /// inside their part are trimmed off as singletons first, which keeps
+    /// long chains from producing deep chains of subproblems.
+    ///
+    /// Unlike `strongly_connected_components`, the result is not in
+    /// topological order: components are sorted by their smallest vertex and
+    /// their members by index.
+    pub fn par_strongly_connected_components(&self) -> Vec<Vec<VertexIndex>> {
+        let _span = trace_span!(
+            "par_strongly_connected_components",
+            vertices = self.nodes.len()
+        );
+        let found = Mutex::new(Vec::new());
+        let all: HashSet<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        rayon::scope(|scope| self.forward_backward(scope, all, &found));
+
+        let mut components = found.into_inner().unwrap();
+        for component in components.iter_mut() {
+            component.sort_unstable();
+        }
+        components.sort_unstable_by_key(|component| component[0]);
+        components
+    }
+
+    fn forward_backward<'s>(
+        &'s self,
+        scope: &Scope<'s>,
+        mut part: HashSet<VertexIndex>,
+        found: &'s Mutex<Vec<Vec<VertexIndex>>>,
+    ) {
+        let trimmed = self.trim(&mut part);
+        if !trimmed.is_empty() {
+            found
+                .lock()
+                .unwrap()
+                .extend(trimmed.into_iter().map(|vertex| vec![vertex]));
+        }
+        let pivot = match part.iter().min() {
+            Some(&pivot) => pivot,
+            None => return,
+        };
+
+        let (forward, backward) = rayon::join(
+            || self.reach_within(pivot, &part, true),
+            || self.reach_within(pivot, &part, false),
+        );
+        let component: Vec<VertexIndex> = forward.intersection(&backward).cloned().collect();
+        for vertex in component.iter() {
+            part.remove(vertex);
+        }
+        let forward: HashSet<VertexIndex> =
+            forward.into_iter().filter(|v| part.contains(v)).collect();
+        let backward: HashSet<VertexIndex> =
+            backward.into_iter().filter(|v| part.contains(v)).collect();
+        part.retain(|vertex| !forward.contains(vertex) && !backward.contains(vertex));
+        found.lock().unwrap().push(component);
+
+        for rest in [forward, backward, part] {
+            if !rest.is_empty() {
+                scope.spawn(move |scope| self.forward_backward(scope, rest, found));
+            }
+        }
+    }
+
+    /// Repeatedly removes from `part` the vertices with no predecessor or
+    /// no successor inside it, returning them. Each is its own component.
+    fn trim(&self, part: &mut HashSet<VertexIndex>) -> Vec<VertexIndex> {
+        let inside = |set: &HashSet<VertexIndex>, part: &HashSet<VertexIndex>| {
+            set.iter().filter(|v| part.contains(v)).count()
+        };
+        let mut indegree: HashMap<VertexIndex, usize> = HashMap::with_capacity(part.len());
+        let mut outdegree: HashMap<VertexIndex, usize> = HashMap::with_capacity(part.len());
+        let mut queue = Vec::new();
+        for &vertex in part.iter() {
+            let node = &self.nodes[vertex];
+            let (ins, outs) = (inside(&node.preset, part), inside(&node.posset, part));
+            indegree.insert(vertex, ins);
+            outdegree.insert(vertex, outs);
+            if ins == 0 || outs == 0 {
+                queue.push(vertex);
+            }
+        }
+
+        let mut trimmed = Vec::new();
+        while let Some(vertex) = queue.pop() {
+            if !part.remove(&vertex) {
+                continue;
+            }
+            trimmed.push(vertex);
+            let node = &self.nodes[vertex];
+            for (set, degree) in [
+                (&node.posset, &mut indegree),
+                (&node.preset, &mut outdegree),
+            ] {
+                for next in set.iter().filter(|next| part.contains(next)) {
+                    let count = degree.get_mut(next).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        queue.push(*next);
+                    }
+                }
+            }
+        }
+        trimmed
+    }
+
+    fn reach_within(
+        &self,
+        start: VertexIndex,
+        part: &HashSet<VertexIndex>,
+        forward: bool,
+    ) -> HashSet<VertexIndex> {
+        let mut reached = HashSet::new();
+        reached.insert(start);
+        let mut stack = vec![start];
+        while let Some(vertex) = stack.pop() {
+            let node = &self.nodes[vertex];
+            let next = if forward { &node.posset } else { &node.preset };
+            for &dst in next.iter() {
+                if part.contains(&dst) && reached.insert(dst) {
+                    stack.push(dst);
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn par_strongly_connected_components() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..200).map(|i| graph.insert(i)).collect();
+        // Rings of ten joined into a chain, with a tail of plain vertices.
+        for ring in vertices[..150].chunks(10) {
+            for (i, &vertex) in ring.iter().enumerate() {
+                graph.connect(vertex, ring[(i + 1) % ring.len()]);
+            }
+        }
+        for pair in vertices.windows(2).step_by(7) {
+            graph.connect(pair[0], pair[1]);
+        }
+        graph.connect(vertices[199], vertices[199]);
+
+        let mut expected: Vec<Vec<VertexIndex>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|mut component| {
+                component.sort_unstable();
+                component
+            })
+            .collect();
+        expected.sort_unstable_by_key(|component| component[0]);
+        assert_eq!(graph.par_strongly_connected_components(), expected);
+        assert!(Graph::<u8>::new()
+            .par_strongly_connected_components()
+            .is_empty());
+    }
+
+    #[test]
+    fn par_from_edge_list() {
+        let edges: Vec<(String, String)> = (0..2100)
+            .map(|i| (format!("n{}", i % 389), format!("n{}", (i * 31) % 401)))
+            .collect();
+        let graph = Graph::par_from_edge_list(&edges);
+
+        let mut expected = Graph::new();
+        let mut index = HashMap::new();
+        for (src, dst) in edges.iter() {
+            let mut vertex = |label: &String| {
+                *index
+                    .entry(label.clone())
+                    .or_insert_with(|| expected.insert(label.clone()))
+            };
+            let (src, dst) = (vertex(src), vertex(dst));
+            expected.connect(src, dst);
+        }
+        assert_eq!(graph.nodes.len(), expected.nodes.len());
+        assert_eq!(
+            graph.degree_stats().edges(),
+            expected.degree_stats().edges()
+        );
+        for (label, &vertex) in index.iter() {
+            assert_eq!(graph.get(label).unwrap().collect::<Vec<_>>(), vec![vertex]);
+            let mut succs: Vec<VertexIndex> = graph.posset(vertex).unwrap().collect();
+            let mut others: Vec<VertexIndex> = expected.posset(vertex).unwrap().collect();
+            succs.sort_unstable();
+            others.sort_unstable();
+            assert_eq!(succs, others);
+        }
+        assert!(Graph::<u8>::par_from_edge_list(&[]).nodes.is_empty());
+    }
+}
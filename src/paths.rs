@@ -0,0 +1,878 @@
+use crate::jobs::{is_cancelled, Cancelled, CANCEL_INTERVAL};
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::Sum;
+use std::sync::atomic::AtomicBool;
+
+/// A path through the graph, stored as its sequence of vertices with each
+/// consecutive pair joined by an edge. Path queries return it instead of a
+/// bare vertex list, so that ends, edges and costs need not be recomputed
+/// by hand.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Path {
+    vertices: Vec<VertexIndex>,
+}
+
+impl Path {
+    /// Wraps `vertices` known to follow edges.
+    #[inline]
+    pub(crate) fn from_vertices(vertices: Vec<VertexIndex>) -> Self {
+        Path { vertices }
+    }
+
+    /// `vertices` as a path of `graph`, or `None` if the list is empty or
+    /// two consecutive vertices are not joined by an edge.
+    pub fn new<V: Eq + Hash + Clone>(graph: &Graph<V>, vertices: Vec<VertexIndex>) -> Option<Self> {
+        let path = Path { vertices };
+        if path.is_empty() || !path.is_valid(graph) {
+            return None;
+        }
+        Some(path)
+    }
+
+    /// Whether every vertex and edge of the path still exists in `graph`,
+    /// e.g. after edits made since it was computed.
+    pub fn is_valid<V: Eq + Hash + Clone>(&self, graph: &Graph<V>) -> bool {
+        self.vertices
+            .iter()
+            .all(|&vertex| graph.nodes.contains(vertex))
+            && self
+                .edges()
+                .all(|(src, dst)| graph.nodes[src].posset.contains(&dst))
+    }
+
+    #[inline]
+    pub fn vertices(&self) -> &[VertexIndex] {
+        &self.vertices
+    }
+
+    #[inline]
+    pub fn into_vertices(self) -> Vec<VertexIndex> {
+        self.vertices
+    }
+
+    /// The edges along the path, in order.
+    #[inline]
+    pub fn edges(&self) -> impl Iterator<Item = EdgeIndex> + '_ {
+        self.vertices.windows(2).map(|pair| (pair[0], pair[1]))
+    }
+
+    #[inline]
+    pub fn source(&self) -> Option<VertexIndex> {
+        self.vertices.first().cloned()
+    }
+
+    #[inline]
+    pub fn target(&self) -> Option<VertexIndex> {
+        self.vertices.last().cloned()
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Number of edges, the length of an unweighted path.
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.vertices.len().saturating_sub(1)
+    }
+
+    /// Whether the path has no vertices at all, as the critical path of an
+    /// empty graph.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, vertex: VertexIndex) -> bool {
+        self.vertices.contains(&vertex)
+    }
+
+    /// Sum of `weight` over the edges of the path.
+    #[inline]
+    pub fn cost<W, F>(&self, weight: F) -> W
+    where
+        W: Sum<W>,
+        F: Fn(EdgeIndex) -> W,
+    {
+        self.edges().map(weight).sum()
+    }
+
+    /// This path followed by `other`, or `None` unless `other` starts where
+    /// this one ends. The shared vertex appears once.
+    pub fn concat(&self, other: &Path) -> Option<Path> {
+        if self.target()? != other.source()? {
+            return None;
+        }
+        let mut vertices = self.vertices.clone();
+        vertices.extend_from_slice(&other.vertices[1..]);
+        Some(Path { vertices })
+    }
+}
+
+impl From<Path> for Vec<VertexIndex> {
+    #[inline]
+    fn from(path: Path) -> Self {
+        path.vertices
+    }
+}
+
+/// Distances and shortest-path tree from a single source.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<W> {
+    source: VertexIndex,
+    distance: HashMap<VertexIndex, W>,
+    predecessor: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl<W: Copy> ShortestPaths<W> {
+    #[inline]
+    pub fn source(&self) -> VertexIndex {
+        self.source
+    }
+
+    /// Distance from the source, or `None` if `vertex` is unreachable.
+    #[inline]
+    pub fn distance(&self, vertex: VertexIndex) -> Option<W> {
+        self.distance.get(&vertex).cloned()
+    }
+
+    #[inline]
+    pub fn predecessor(&self, vertex: VertexIndex) -> Option<VertexIndex> {
+        self.predecessor.get(&vertex).cloned()
+    }
+
+    #[inline]
+    pub fn distances(&self) -> &HashMap<VertexIndex, W> {
+        &self.distance
+    }
+
+    /// Vertices on a shortest path from the source to `vertex`, both ends
+    /// included.
+    pub fn path_to(&self, vertex: VertexIndex) -> Option<Path> {
+        if !self.distance.contains_key(&vertex) {
+            return None;
+        }
+        let mut path = vec![vertex];
+        while let Some(&prev) = self.predecessor.get(path.last().unwrap()) {
+            path.push(prev);
+        }
+        path.reverse();
+        Some(Path::from_vertices(path))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathError {
+    MissingVertex(VertexIndex),
+    /// Distances are unbounded because of negative cycles reachable from
+    /// the source; holds the vertices of at least one such cycle, not
+    /// necessarily all of them.
+    NegativeCycle(Vec<VertexIndex>),
+    /// Longest paths are only defined on acyclic graphs.
+    Cyclic,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::MissingVertex(vertex) => write!(f, "vertex {} does not exist", vertex),
+            PathError::NegativeCycle(vertices) => {
+                write!(f, "negative cycle through vertices {:?}", vertices)
+            }
+            PathError::Cyclic => write!(f, "graph is cyclic"),
+        }
+    }
+}
+
+impl Error for PathError {}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Every simple path from `src` to `dst`. The number of paths can grow
+    /// exponentially, so `cancel` is polled periodically to allow aborting.
+    pub fn all_simple_paths(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Vec<Path>, Cancelled> {
+        let _span = trace_span!("all_simple_paths", src, dst);
+        let mut paths = Vec::new();
+        if !(self.nodes.contains(src) && self.nodes.contains(dst)) {
+            return Ok(paths);
+        }
+
+        let mut path = vec![src];
+        let mut on_path: HashSet<VertexIndex> = path.iter().cloned().collect();
+        let mut stack = vec![self.nodes[src].posset.iter()];
+        let mut work = 0;
+        while let Some(succs) = stack.last_mut() {
+            work += 1;
+            if work % CANCEL_INTERVAL == 0 && is_cancelled(cancel) {
+                return Err(Cancelled);
+            }
+            if path.last() == Some(&dst) {
+                paths.push(Path::from_vertices(path.clone()));
+            } else if let Some(&next) = succs.find(|next| !on_path.contains(next)) {
+                path.push(next);
+                on_path.insert(next);
+                stack.push(self.nodes[next].posset.iter());
+                continue;
+            }
+            stack.pop();
+            on_path.remove(&path.pop().unwrap());
+        }
+
+        Ok(paths)
+    }
+
+    /// A path from `src` to `dst` with the fewest edges, found by
+    /// breadth-first search. `None` if either vertex is missing or `dst` is
+    /// unreachable.
+    #[inline]
+    pub fn shortest_path(&self, src: VertexIndex, dst: VertexIndex) -> Option<Path> {
+        self.shortest_path_filtered(src, dst, |_| true)
+    }
+
+    /// Like `shortest_path`, only following edges for which `filter` holds,
+    /// such as edges of a given kind.
+    pub fn shortest_path_filtered<F>(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        filter: F,
+    ) -> Option<Path>
+    where
+        F: Fn(EdgeIndex) -> bool,
+    {
+        if !(self.nodes.contains(src) && self.nodes.contains(dst)) {
+            return None;
+        }
+
+        let mut parent: HashMap<VertexIndex, VertexIndex> = HashMap::new();
+        let mut queue = VecDeque::new();
+        parent.insert(src, src);
+        queue.push_back(src);
+        while let Some(vertex) = queue.pop_front() {
+            if vertex == dst {
+                let mut path = vec![dst];
+                while *path.last().unwrap() != src {
+                    path.push(parent[path.last().unwrap()]);
+                }
+                path.reverse();
+                return Some(Path::from_vertices(path));
+            }
+            for &next in self.nodes[vertex].posset.iter() {
+                if !filter((vertex, next)) {
+                    continue;
+                }
+                if let Entry::Vacant(entry) = parent.entry(next) {
+                    entry.insert(vertex);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Single-source shortest paths with non-negative weights supplied by
+    /// `weight`, so weights can be kept outside the graph.
+    pub fn dijkstra<F>(&self, src: VertexIndex, weight: F) -> Option<ShortestPaths<u64>>
+    where
+        F: Fn(EdgeIndex) -> u64,
+    {
+        if !self.nodes.contains(src) {
+            return None;
+        }
+        let _span = trace_span!("dijkstra", src);
+        Some(self.dijkstra_avoiding(src, &weight, |_| false))
+    }
+
+    /// Like `dijkstra`, only following edges for which `filter` holds.
+    pub fn dijkstra_filtered<F, P>(
+        &self,
+        src: VertexIndex,
+        weight: F,
+        filter: P,
+    ) -> Option<ShortestPaths<u64>>
+    where
+        F: Fn(EdgeIndex) -> u64,
+        P: Fn(EdgeIndex) -> bool,
+    {
+        if !self.nodes.contains(src) {
+            return None;
+        }
+        let _span = trace_span!("dijkstra_filtered", src);
+        Some(self.dijkstra_avoiding(src, &weight, |edge| !filter(edge)))
+    }
+
+    /// Dijkstra's algorithm ignoring every edge for which `avoid` holds.
+    fn dijkstra_avoiding<F, A>(&self, src: VertexIndex, weight: &F, avoid: A) -> ShortestPaths<u64>
+    where
+        F: Fn(EdgeIndex) -> u64,
+        A: Fn(EdgeIndex) -> bool,
+    {
+        let mut distance: HashMap<VertexIndex, u64> = HashMap::new();
+        let mut predecessor = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        distance.insert(src, 0);
+        heap.push(Reverse((0, src)));
+        while let Some(Reverse((dist, vertex))) = heap.pop() {
+            if dist > distance[&vertex] {
+                continue;
+            }
+            for &next in self.nodes[vertex].posset.iter() {
+                if avoid((vertex, next)) {
+                    continue;
+                }
+                let candidate = dist.saturating_add(weight((vertex, next)));
+                if distance.get(&next).is_none_or(|&known| candidate < known) {
+                    distance.insert(next, candidate);
+                    predecessor.insert(next, vertex);
+                    heap.push(Reverse((candidate, next)));
+                }
+            }
+        }
+
+        ShortestPaths {
+            source: src,
+            distance,
+            predecessor,
+        }
+    }
+
+    /// Up to `k` loop-free paths from `src` to `dst` with their costs, in
+    /// increasing cost order, using Yen's algorithm over non-negative
+    /// weights supplied by `weight`.
+    pub fn k_shortest_paths<F>(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        k: usize,
+        weight: F,
+    ) -> Option<Vec<(u64, Path)>>
+    where
+        F: Fn(EdgeIndex) -> u64,
+    {
+        if !(self.nodes.contains(src) && self.nodes.contains(dst)) {
+            return None;
+        }
+        let _span = trace_span!("k_shortest_paths", src, dst, k);
+        let cost = |path: &[VertexIndex]| {
+            path.windows(2).fold(0u64, |sum, pair| {
+                sum.saturating_add(weight((pair[0], pair[1])))
+            })
+        };
+
+        let mut found: Vec<(u64, Vec<VertexIndex>)> = Vec::new();
+        let first = self.dijkstra_avoiding(src, &weight, |_| false);
+        match first.path_to(dst) {
+            Some(path) if k > 0 => found.push((first.distance[&dst], path.into_vertices())),
+            _ => return Some(Vec::new()),
+        }
+
+        let mut candidates = BinaryHeap::new();
+        let mut seen: HashSet<Vec<VertexIndex>> = HashSet::new();
+        seen.insert(found[0].1.clone());
+        while found.len() < k {
+            let last = found.last().unwrap().1.clone();
+            for spur in 0..last.len() - 1 {
+                let root = &last[..=spur];
+                let blocked: HashSet<EdgeIndex> = found
+                    .iter()
+                    .filter(|(_, path)| path.len() > spur + 1 && path[..=spur] == *root)
+                    .map(|(_, path)| (path[spur], path[spur + 1]))
+                    .collect();
+                let avoid =
+                    |edge: EdgeIndex| blocked.contains(&edge) || root[..spur].contains(&edge.1);
+                let tail = self.dijkstra_avoiding(last[spur], &weight, avoid);
+                if let Some(tail) = tail.path_to(dst) {
+                    let mut path = root[..spur].to_vec();
+                    path.extend(tail.into_vertices());
+                    if seen.insert(path.clone()) {
+                        candidates.push(Reverse((cost(&path), path)));
+                    }
+                }
+            }
+            match candidates.pop() {
+                Some(Reverse(candidate)) => found.push(candidate),
+                None => break,
+            }
+        }
+
+        Some(
+            found
+                .into_iter()
+                .map(|(cost, path)| (cost, Path::from_vertices(path)))
+                .collect(),
+        )
+    }
+
+    /// Single-source shortest paths with signed weights supplied by
+    /// `weight`. Fails with the vertices on at least one negative cycle
+    /// reachable from `src` if there is any.
+    pub fn bellman_ford<F>(
+        &self,
+        src: VertexIndex,
+        weight: F,
+    ) -> Result<ShortestPaths<i64>, PathError>
+    where
+        F: Fn(EdgeIndex) -> i64,
+    {
+        if !self.nodes.contains(src) {
+            return Err(PathError::MissingVertex(src));
+        }
+        let _span = trace_span!("bellman_ford", src);
+
+        let mut distance: HashMap<VertexIndex, i64> = HashMap::new();
+        let mut predecessor = HashMap::new();
+        distance.insert(src, 0);
+        let mut relax = |distance: &mut HashMap<VertexIndex, i64>,
+                         updated: &mut Vec<VertexIndex>| {
+            for (vertex, node) in self.nodes.iter() {
+                let dist = match distance.get(&vertex) {
+                    Some(&dist) => dist,
+                    None => continue,
+                };
+                for &next in node.posset.iter() {
+                    let candidate = dist.saturating_add(weight((vertex, next)));
+                    if distance.get(&next).is_none_or(|&known| candidate < known) {
+                        distance.insert(next, candidate);
+                        predecessor.insert(next, vertex);
+                        updated.push(next);
+                    }
+                }
+            }
+        };
+
+        let mut updated = Vec::new();
+        for _ in 1..self.nodes.len().max(1) {
+            updated.clear();
+            relax(&mut distance, &mut updated);
+            if updated.is_empty() {
+                break;
+            }
+        }
+        updated.clear();
+        relax(&mut distance, &mut updated);
+        if updated.is_empty() {
+            return Ok(ShortestPaths {
+                source: src,
+                distance,
+                predecessor,
+            });
+        }
+
+        // Walking back far enough from a vertex that still relaxes lands on
+        // a negative cycle in the predecessor graph.
+        let mut cyclic = HashSet::new();
+        for &vertex in updated.iter() {
+            let mut at = vertex;
+            for _ in 0..self.nodes.len() {
+                at = predecessor[&at];
+            }
+            if cyclic.contains(&at) {
+                continue;
+            }
+            let start = at;
+            loop {
+                cyclic.insert(at);
+                at = predecessor[&at];
+                if at == start {
+                    break;
+                }
+            }
+        }
+        let mut vertices: Vec<VertexIndex> = cyclic.into_iter().collect();
+        vertices.sort_unstable();
+        Err(PathError::NegativeCycle(vertices))
+    }
+
+    /// Longest path from `src` to `dst` under `weight` with its length,
+    /// found by dynamic programming over a topological order. `Ok(None)` if
+    /// `dst` is unreachable.
+    pub fn longest_path<F>(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        weight: F,
+    ) -> Result<Option<(i64, Path)>, PathError>
+    where
+        F: Fn(EdgeIndex) -> i64,
+    {
+        for &vertex in [src, dst].iter() {
+            if !self.nodes.contains(vertex) {
+                return Err(PathError::MissingVertex(vertex));
+            }
+        }
+        let paths = self.longest_paths_from(Some(src), &weight)?;
+        Ok(paths
+            .distance(dst)
+            .map(|length| (length, paths.path_to(dst).unwrap())))
+    }
+
+    /// The longest path anywhere in the graph, such as the critical path of
+    /// a dependency graph whose edges are weighted by task duration. A single
+    /// vertex of length 0 when every weight is negative; empty if the graph
+    /// is.
+    pub fn critical_path<F>(&self, weight: F) -> Result<(i64, Path), PathError>
+    where
+        F: Fn(EdgeIndex) -> i64,
+    {
+        let paths = self.longest_paths_from(None, &weight)?;
+        Ok(paths
+            .distances()
+            .iter()
+            .max_by_key(|&(&vertex, &length)| (length, std::cmp::Reverse(vertex)))
+            .map(|(&vertex, &length)| (length, paths.path_to(vertex).unwrap()))
+            .unwrap_or((0, Path::default())))
+    }
+
+    /// Longest distances from `src`, or from whichever vertex gives the
+    /// longest path into each vertex when `src` is `None`.
+    fn longest_paths_from<F>(
+        &self,
+        src: Option<VertexIndex>,
+        weight: &F,
+    ) -> Result<ShortestPaths<i64>, PathError>
+    where
+        F: Fn(EdgeIndex) -> i64,
+    {
+        let order = self.topological_order().ok_or(PathError::Cyclic)?;
+        let _span = trace_span!("longest_paths", vertices = order.len());
+        let mut distance: HashMap<VertexIndex, i64> = HashMap::new();
+        let mut predecessor = HashMap::new();
+        match src {
+            Some(src) => {
+                distance.insert(src, 0);
+            }
+            None => distance.extend(order.iter().map(|&vertex| (vertex, 0))),
+        }
+        for &vertex in order.iter() {
+            let dist = match distance.get(&vertex) {
+                Some(&dist) => dist,
+                None => continue,
+            };
+            for &next in self.nodes[vertex].posset.iter() {
+                let candidate = dist.saturating_add(weight((vertex, next)));
+                if distance.get(&next).is_none_or(|&known| candidate > known) {
+                    distance.insert(next, candidate);
+                    predecessor.insert(next, vertex);
+                }
+            }
+        }
+
+        Ok(ShortestPaths {
+            source: src.unwrap_or_else(|| order.first().cloned().unwrap_or(0)),
+            distance,
+            predecessor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn simple_paths() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, c);
+        graph.connect(c, b);
+        graph.connect(b, d);
+        graph.connect(c, d);
+        graph.connect(d, a);
+
+        let mut paths: Vec<Vec<VertexIndex>> = graph
+            .all_simple_paths(a, d, None)
+            .unwrap()
+            .into_iter()
+            .map(Vec::from)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                vec![a, b, c, d],
+                vec![a, b, d],
+                vec![a, c, b, d],
+                vec![a, c, d]
+            ]
+        );
+        assert_eq!(
+            graph.all_simple_paths(a, a, None),
+            Ok(vec![Path::from_vertices(vec![a])])
+        );
+        assert_eq!(graph.all_simple_paths(a, 42, None), Ok(vec![]));
+
+        let mut big = Graph::new();
+        let layers: Vec<Vec<VertexIndex>> = (0..12)
+            .map(|_| (0..4).map(|i| big.insert(i)).collect())
+            .collect();
+        for pair in layers.windows(2) {
+            for &src in pair[0].iter() {
+                for &dst in pair[1].iter() {
+                    big.connect(src, dst);
+                }
+            }
+        }
+        let cancel = AtomicBool::new(true);
+        assert_eq!(
+            big.all_simple_paths(layers[0][0], layers[11][0], Some(&cancel)),
+            Err(Cancelled)
+        );
+    }
+
+    #[test]
+    fn shortest_path() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(a, c);
+        graph.connect(d, a);
+
+        assert_eq!(
+            graph.shortest_path(a, d),
+            Some(Path::from_vertices(vec![a, c, d]))
+        );
+        assert_eq!(
+            graph.shortest_path(b, a),
+            Some(Path::from_vertices(vec![b, c, d, a]))
+        );
+        assert_eq!(
+            graph.shortest_path(c, c),
+            Some(Path::from_vertices(vec![c]))
+        );
+        graph.disconnect((d, a));
+        assert_eq!(graph.shortest_path(d, a), None);
+        assert_eq!(graph.shortest_path(a, 42), None);
+    }
+
+    #[test]
+    fn filtered_paths() {
+        const DATA: EdgeKind = EdgeKind(1);
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect_with_kind(a, b, DATA);
+        graph.connect_with_kind(b, c, DATA);
+        graph.connect_with_kind(c, d, DATA);
+        graph.connect(a, d);
+        graph.connect(b, a);
+
+        let data = |edge| graph.edge_kind(edge) == Some(DATA);
+        assert_eq!(
+            graph.shortest_path(a, d),
+            Some(Path::from_vertices(vec![a, d]))
+        );
+        assert_eq!(
+            graph.shortest_path_filtered(a, d, data),
+            Some(Path::from_vertices(vec![a, b, c, d]))
+        );
+        assert_eq!(graph.shortest_path_filtered(b, a, data), None);
+        assert_eq!(graph.is_reachable_filtered(b, a, data), Some(false));
+        assert_eq!(graph.is_reachable_filtered(a, d, data), Some(true));
+        assert_eq!(graph.is_reachable_filtered(a, 42, data), None);
+
+        let paths = graph.dijkstra_filtered(a, |_| 1, data).unwrap();
+        assert_eq!(paths.distance(d), Some(3));
+        assert_eq!(graph.dijkstra(a, |_| 1).unwrap().distance(d), Some(1));
+    }
+
+    #[test]
+    fn path() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+
+        let first = Path::new(&graph, vec![a, b]).unwrap();
+        let second = Path::new(&graph, vec![b, c]).unwrap();
+        let whole = first.concat(&second).unwrap();
+        assert_eq!(whole.vertices(), &[a, b, c][..]);
+        assert_eq!(whole.edges().collect::<Vec<_>>(), vec![(a, b), (b, c)]);
+        assert_eq!((whole.source(), whole.target()), (Some(a), Some(c)));
+        assert_eq!(whole.edge_count(), 2);
+        assert_eq!(whole.cost(|(src, _)| src as u64 + 1), 3);
+        assert!(second.concat(&first).is_none());
+        assert!(Path::new(&graph, vec![a, c]).is_none());
+        assert!(Path::new(&graph, vec![]).is_none());
+
+        graph.disconnect((b, c));
+        assert!(first.is_valid(&graph));
+        assert!(!whole.is_valid(&graph));
+    }
+
+    #[test]
+    fn dijkstra() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, d);
+        graph.connect(c, d);
+        graph.connect(d, a);
+
+        let weight = |(src, dst)| if (src, dst) == (a, b) { 10 } else { 3 };
+        let paths = graph.dijkstra(a, weight).unwrap();
+        assert_eq!(paths.source(), a);
+        assert_eq!(paths.distance(d), Some(6));
+        assert_eq!(paths.distance(b), Some(10));
+        assert_eq!(paths.distance(e), None);
+        assert_eq!(paths.path_to(d), Some(Path::from_vertices(vec![a, c, d])));
+        assert_eq!(paths.path_to(a), Some(Path::from_vertices(vec![a])));
+        assert_eq!(paths.predecessor(c), Some(a));
+        assert_eq!(paths.distances().len(), 4);
+        assert!(graph.dijkstra(42, weight).is_none());
+    }
+
+    #[test]
+    fn k_shortest_paths() {
+        let mut graph = Graph::new();
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        let f = graph.insert("f");
+        let g = graph.insert("g");
+        let h = graph.insert("h");
+        let weights: HashMap<EdgeIndex, u64> = [
+            ((c, d), 3),
+            ((c, e), 2),
+            ((d, f), 4),
+            ((e, d), 1),
+            ((e, f), 2),
+            ((e, g), 3),
+            ((f, g), 2),
+            ((f, h), 1),
+            ((g, h), 2),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        for &(src, dst) in weights.keys() {
+            graph.connect(src, dst);
+        }
+        graph.connect(h, c);
+
+        let weight = |edge| weights.get(&edge).cloned().unwrap_or(100);
+        let paths = graph.k_shortest_paths(c, h, 3, weight).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                (5, Path::from_vertices(vec![c, e, f, h])),
+                (7, Path::from_vertices(vec![c, e, g, h])),
+                (8, Path::from_vertices(vec![c, d, f, h])),
+            ]
+        );
+        let all = graph.k_shortest_paths(c, h, 10, weight).unwrap();
+        assert_eq!(all.len(), 7);
+        assert!(all.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!(
+            graph.k_shortest_paths(c, c, 3, weight),
+            Some(vec![(0, Path::from_vertices(vec![c]))])
+        );
+        assert_eq!(graph.k_shortest_paths(c, h, 0, weight), Some(vec![]));
+        assert_eq!(graph.k_shortest_paths(c, 42, 3, weight), None);
+    }
+
+    #[test]
+    fn longest_path() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, d);
+        graph.connect(c, d);
+        graph.connect(e, c);
+
+        let weight = |(src, _)| if src == c { 1 } else { 4 };
+        assert_eq!(
+            graph.longest_path(a, d, weight),
+            Ok(Some((8, Path::from_vertices(vec![a, b, d]))))
+        );
+        assert_eq!(graph.longest_path(b, e, weight), Ok(None));
+        let (length, path) = graph.critical_path(weight).unwrap();
+        assert_eq!((length, path.vertices()), (8, &[a, b, d][..]));
+        assert_eq!(path.cost(weight), 8);
+        assert_eq!(
+            graph.longest_path(a, 42, weight),
+            Err(PathError::MissingVertex(42))
+        );
+
+        graph.connect(d, a);
+        assert_eq!(graph.longest_path(a, d, weight), Err(PathError::Cyclic));
+        assert_eq!(
+            Graph::<u8>::new().critical_path(|_| 1),
+            Ok((0, Path::default()))
+        );
+    }
+
+    #[test]
+    fn bellman_ford() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(c, b);
+        graph.connect(b, d);
+
+        let weight = |edge| match edge {
+            (src, _) if src == d => -1,
+            (src, dst) if (src, dst) == (c, b) => -4,
+            _ => 2,
+        };
+        let paths = graph.bellman_ford(a, weight).unwrap();
+        assert_eq!(paths.distance(b), Some(-2));
+        assert_eq!(
+            paths.path_to(d),
+            Some(Path::from_vertices(vec![a, c, b, d]))
+        );
+
+        graph.connect(d, c);
+        assert_eq!(
+            graph.bellman_ford(a, weight).unwrap_err(),
+            PathError::NegativeCycle(vec![b, c, d])
+        );
+        assert_eq!(graph.bellman_ford(b, |_| 1).unwrap().distance(a), None);
+        assert_eq!(
+            graph.bellman_ford(42, weight).unwrap_err(),
+            PathError::MissingVertex(42)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{EdgeIndex, Graph, Job, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Maximum matching between the "left" and "right" copies of the elements of
+/// a strict partial order, with an edge from `u` to `v` whenever `u < v`.
+struct Comparability {
+    elements: Vec<VertexIndex>,
+    greater: HashMap<VertexIndex, HashSet<VertexIndex>>,
+    right_of: HashMap<VertexIndex, VertexIndex>,
+    left_of: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl Comparability {
+    fn new<V: Eq + Hash + Clone>(graph: &Graph<V>) -> Option<Self> {
+        graph.topological_order()?;
+        let greater = graph.closure_job().finish();
+        let mut matching = Comparability {
+            elements: graph.nodes.iter().map(|(vertex, _)| vertex).collect(),
+            greater,
+            right_of: HashMap::new(),
+            left_of: HashMap::new(),
+        };
+        for &vertex in matching.elements.clone().iter() {
+            let mut seen = HashSet::new();
+            matching.augment(vertex, &mut seen);
+        }
+        Some(matching)
+    }
+
+    /// Kuhn's augmenting-path search from `root`, with an explicit stack of
+    /// left elements so that long alternating paths cannot overflow the call
+    /// stack. `via` holds the right element each frame moved through.
+    fn augment(&mut self, root: VertexIndex, seen: &mut HashSet<VertexIndex>) -> bool {
+        let candidates = |left: VertexIndex| -> Vec<VertexIndex> {
+            self.greater[&left].iter().cloned().collect()
+        };
+        let mut frames = vec![(root, candidates(root).into_iter())];
+        let mut via: Vec<VertexIndex> = Vec::new();
+        while let Some((_, rights)) = frames.last_mut() {
+            let right = match rights.next() {
+                Some(right) => right,
+                None => {
+                    frames.pop();
+                    via.pop();
+                    continue;
+                }
+            };
+            if !seen.insert(right) {
+                continue;
+            }
+            via.push(right);
+            match self.left_of.get(&right) {
+                Some(&other) => frames.push((other, candidates(other).into_iter())),
+                None => {
+                    for (&(left, _), &right) in frames.iter().zip(via.iter()) {
+                        self.right_of.insert(left, right);
+                        self.left_of.insert(right, left);
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn chains(&self) -> Vec<Vec<VertexIndex>> {
+        self.elements
+            .iter()
+            .filter(|vertex| !self.left_of.contains_key(vertex))
+            .map(|&head| {
+                let mut chain = vec![head];
+                while let Some(&next) = self.right_of.get(chain.last().unwrap()) {
+                    chain.push(next);
+                }
+                chain
+            })
+            .collect()
+    }
+
+    /// Maximum antichain from the minimum vertex cover given by König's
+    /// theorem: elements whose left copy is reachable from an unmatched left
+    /// vertex by an alternating path while their right copy is not.
+    fn antichain(&self) -> Vec<VertexIndex> {
+        let mut left_seen: HashSet<VertexIndex> = self
+            .elements
+            .iter()
+            .filter(|vertex| !self.right_of.contains_key(vertex))
+            .cloned()
+            .collect();
+        let mut right_seen = HashSet::new();
+        let mut stack: Vec<VertexIndex> = left_seen.iter().cloned().collect();
+        while let Some(left) = stack.pop() {
+            for &right in self.greater[&left].iter() {
+                if self.right_of.get(&left) == Some(&right) || !right_seen.insert(right) {
+                    continue;
+                }
+                if let Some(&next) = self.left_of.get(&right) {
+                    if left_seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        self.elements
+            .iter()
+            .filter(|vertex| left_seen.contains(vertex) && !right_seen.contains(vertex))
+            .cloned()
+            .collect()
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Hasse diagram of the reachability order: the edges not implied by a
+    /// longer path. `None` if the graph is cyclic.
+    pub fn covering_relation(&self) -> Option<Vec<EdgeIndex>> {
+        self.topological_order()?;
+        let redundant: HashSet<EdgeIndex> = self.redundant_edges().into_iter().collect();
+        Some(
+            self.nodes
+                .iter()
+                .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+                .filter(|edge| !redundant.contains(edge))
+                .collect(),
+        )
+    }
+
+    /// Minimum partition of the vertices into chains of mutually reachable
+    /// vertices (Dilworth), each ordered along the reachability order. `None`
+    /// if the graph is cyclic.
+    #[inline]
+    pub fn chain_decomposition(&self) -> Option<Vec<Vec<VertexIndex>>> {
+        Comparability::new(self).map(|matching| matching.chains())
+    }
+
+    /// A maximum-size set of pairwise unreachable vertices. Its size equals
+    /// the number of chains in `chain_decomposition`. `None` if the graph is
+    /// cyclic.
+    #[inline]
+    pub fn maximal_antichain(&self) -> Option<Vec<VertexIndex>> {
+        Comparability::new(self).map(|matching| matching.antichain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn partial_order() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(a, d);
+        graph.connect(b, e);
+        graph.connect(c, e);
+        graph.connect(a, e);
+
+        let covers: HashSet<EdgeIndex> = graph.covering_relation().unwrap().into_iter().collect();
+        assert_eq!(
+            covers,
+            vec![(a, b), (a, c), (a, d), (b, e), (c, e)]
+                .into_iter()
+                .collect()
+        );
+
+        let chains = graph.chain_decomposition().unwrap();
+        assert_eq!(chains.len(), 3);
+        assert_eq!(chains.iter().map(|chain| chain.len()).sum::<usize>(), 5);
+        let mut antichain = graph.maximal_antichain().unwrap();
+        antichain.sort();
+        assert_eq!(antichain, vec![b, c, d]);
+
+        graph.connect(e, a);
+        assert_eq!(graph.covering_relation(), None);
+        assert_eq!(graph.maximal_antichain(), None);
+    }
+}
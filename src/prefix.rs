@@ -0,0 +1,125 @@
+use crate::{Graph, VertexIndex};
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::hash::Hash;
+use std::ops::Bound;
+
+/// Sorted copy of the label strings, kept current by every label edit so
+/// that all labels sharing a prefix form one contiguous range.
+#[derive(Clone)]
+pub(crate) struct PrefixIndex<V> {
+    key: fn(&V) -> &str,
+    labels: BTreeSet<String>,
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Called when `label` may have gained its first vertex.
+    pub(crate) fn index_label(&mut self, label: &V) {
+        if let Some(index) = self.prefixes.as_mut() {
+            let key = (index.key)(label);
+            if !index.labels.contains(key) {
+                index.labels.insert(key.to_string());
+            }
+        }
+    }
+
+    /// Called when `label` lost its last vertex.
+    pub(crate) fn unindex_label(&mut self, label: &V) {
+        if let Some(index) = self.prefixes.as_mut() {
+            let key = (index.key)(label);
+            index.labels.remove(key);
+        }
+    }
+}
+
+impl<V: Eq + Hash + Clone + Borrow<str>> Graph<V> {
+    /// Starts maintaining a sorted index of the labels, turning prefix
+    /// queries from a scan over every label into a range lookup. Each label
+    /// added or dropped then costs O(log n).
+    pub fn track_label_prefixes(&mut self) {
+        if self.prefixes.is_none() {
+            self.prefixes = Some(PrefixIndex {
+                key: <V as Borrow<str>>::borrow,
+                labels: self
+                    .aliases
+                    .keys()
+                    .map(|label| label.borrow().to_string())
+                    .collect(),
+            });
+        }
+    }
+
+    #[inline]
+    pub fn untrack_label_prefixes(&mut self) {
+        self.prefixes = None;
+    }
+
+    /// Labels starting with `prefix`, sorted. Scans every label unless
+    /// `track_label_prefixes` was called.
+    pub fn labels_with_prefix<'a>(&'a self, prefix: &str) -> Vec<&'a str> {
+        match self.prefixes.as_ref() {
+            Some(index) => index
+                .labels
+                .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+                .take_while(|label| label.starts_with(prefix))
+                .map(|label| label.as_str())
+                .collect(),
+            None => {
+                let mut labels: Vec<&str> = self
+                    .aliases
+                    .keys()
+                    .map(|label| label.borrow())
+                    .filter(|label| label.starts_with(prefix))
+                    .collect();
+                labels.sort_unstable();
+                labels
+            }
+        }
+    }
+
+    /// Vertices carrying a label that starts with `prefix`, such as every
+    /// instance under `"top.cpu."`, sorted by index.
+    pub fn vertices_with_label_prefix(&self, prefix: &str) -> Vec<VertexIndex> {
+        let mut vertices: Vec<VertexIndex> = self
+            .labels_with_prefix(prefix)
+            .into_iter()
+            .flat_map(|label| self.aliases[label].iter().cloned())
+            .collect();
+        vertices.sort_unstable();
+        vertices.dedup();
+        vertices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn label_prefixes() {
+        let mut graph: Graph<String> = Graph::new();
+        let alu = graph.insert("top.cpu.alu".to_string());
+        let fpu = graph.insert("top.cpu.fpu".to_string());
+        let mem = graph.insert("top.mem".to_string());
+        graph.append_label(alu, "top.cpu.alu0".to_string());
+        let unindexed = graph.vertices_with_label_prefix("top.cpu.");
+
+        graph.track_label_prefixes();
+        assert_eq!(graph.vertices_with_label_prefix("top.cpu."), unindexed);
+        assert_eq!(unindexed, vec![alu, fpu]);
+        assert_eq!(
+            graph.labels_with_prefix("top.cpu.alu"),
+            vec!["top.cpu.alu", "top.cpu.alu0"]
+        );
+
+        let bus = graph.insert("top.cpu.bus".to_string());
+        graph.remove(fpu);
+        graph.remove_vertex_label(&"top.cpu.alu".to_string(), alu);
+        assert_eq!(graph.vertices_with_label_prefix("top.cpu."), vec![alu, bus]);
+        assert_eq!(graph.labels_with_prefix("top.cpu.a"), vec!["top.cpu.alu0"]);
+        let merged = graph.merge_vertices(vec![bus, mem]);
+        assert_eq!(graph.vertices_with_label_prefix("top.m"), vec![merged]);
+        assert_eq!(graph.vertices_with_label_prefix("top."), vec![alu, merged]);
+        assert!(graph.vertices_with_label_prefix("bottom").is_empty());
+    }
+}
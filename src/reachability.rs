@@ -0,0 +1,143 @@
+use crate::{Graph, VertexIndex};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+const UNREACHED: u32 = u32::MAX;
+
+/// Static reachability index over a snapshot of a graph. Strongly connected
+/// components are condensed and the resulting DAG is covered by chains; each
+/// component stores, per chain, the first position it reaches. Queries take
+/// constant time and memory grows with components times chains.
+#[derive(Clone, Debug)]
+pub struct ReachabilityIndex {
+    component: Vec<usize>,
+    chain: Vec<usize>,
+    position: Vec<u32>,
+    chains: usize,
+    table: Vec<u32>,
+}
+
+impl ReachabilityIndex {
+    /// Whether `dst` is reachable from `src` in the indexed snapshot. A
+    /// vertex always reaches itself. `None` if either vertex was not in the
+    /// snapshot.
+    #[inline]
+    pub fn reaches(&self, src: VertexIndex, dst: VertexIndex) -> Option<bool> {
+        let from = *self.component.get(src).filter(|&&c| c != usize::MAX)?;
+        let to = *self.component.get(dst).filter(|&&c| c != usize::MAX)?;
+        Some(from == to || self.table[from * self.chains + self.chain[to]] <= self.position[to])
+    }
+
+    /// Number of chains in the cover of the condensation.
+    #[inline]
+    pub fn chains(&self) -> usize {
+        self.chains
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    pub fn reachability_index(&self) -> ReachabilityIndex {
+        let _span = trace_span!("reachability_index", vertices = self.nodes.len());
+        // Tarjan emits components sinks first, so every condensed edge goes
+        // from a higher component id to a lower one.
+        let components = self.strongly_connected_components();
+        let mut component = vec![usize::MAX; self.nodes.capacity()];
+        for (id, members) in components.iter().enumerate() {
+            for &vertex in members.iter() {
+                component[vertex] = id;
+            }
+        }
+        let succs: Vec<HashSet<usize>> = components
+            .iter()
+            .enumerate()
+            .map(|(id, members)| {
+                members
+                    .iter()
+                    .flat_map(|&vertex| self.nodes[vertex].posset.iter())
+                    .map(|&dst| component[dst])
+                    .filter(|&other| other != id)
+                    .collect()
+            })
+            .collect();
+
+        // Greedy chain cover in topological order: extend the chain of a
+        // predecessor whose tail it is, otherwise open a new chain.
+        let mut chain = vec![0; components.len()];
+        let mut position = vec![0; components.len()];
+        let mut tails: Vec<usize> = Vec::new();
+        let mut tail_of: Vec<Option<usize>> = vec![None; components.len()];
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); components.len()];
+        for (id, next) in succs.iter().enumerate() {
+            for &dst in next.iter() {
+                preds[dst].push(id);
+            }
+        }
+        for id in (0..components.len()).rev() {
+            match preds[id].iter().find_map(|&pred| tail_of[pred].take()) {
+                Some(k) => {
+                    chain[id] = k;
+                    position[id] = position[tails[k]] + 1;
+                    tails[k] = id;
+                }
+                None => {
+                    chain[id] = tails.len();
+                    tails.push(id);
+                }
+            }
+            tail_of[id] = Some(chain[id]);
+        }
+
+        let chains = tails.len();
+        let mut table = vec![UNREACHED; components.len() * chains];
+        for id in 0..components.len() {
+            let row = id * chains;
+            table[row + chain[id]] = position[id];
+            for &dst in succs[id].iter() {
+                for k in 0..chains {
+                    table[row + k] = table[row + k].min(table[dst * chains + k]);
+                }
+            }
+        }
+
+        ReachabilityIndex {
+            component,
+            chain,
+            position,
+            chains,
+            table,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn reachability_index() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..8).map(|i| graph.insert(i)).collect();
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (1, 5),
+            (5, 4),
+            (6, 5),
+        ];
+        for &(src, dst) in edges.iter() {
+            graph.connect(vertices[src], vertices[dst]);
+        }
+
+        let index = graph.reachability_index();
+        assert!(index.chains() >= 2);
+        for &src in vertices.iter() {
+            for &dst in vertices.iter() {
+                assert_eq!(index.reaches(src, dst), graph.is_reachable(src, dst));
+            }
+        }
+        assert_eq!(index.reaches(vertices[0], 42), None);
+    }
+}
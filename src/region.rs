@@ -0,0 +1,218 @@
+use crate::{EdgeIndex, Graph, IndexAllocation, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Isolated copy of a vertex region that can be edited freely and then either
+/// spliced back into the source graph or discarded. Edits are made on the
+/// copy returned by `graph_mut`; the source graph is untouched until
+/// `commit`, so the result can be validated first.
+pub struct RegionEditor<'a, V: Hash + Eq + Clone> {
+    source: &'a mut Graph<V>,
+    region: Graph<V>,
+    origin: HashMap<VertexIndex, VertexIndex>,
+    local: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl<'a, V: Hash + Eq + Clone> RegionEditor<'a, V> {
+    #[inline]
+    pub fn graph(&self) -> &Graph<V> {
+        &self.region
+    }
+
+    #[inline]
+    pub fn graph_mut(&mut self) -> &mut Graph<V> {
+        &mut self.region
+    }
+
+    /// Index in the editor of the source vertex `vertex`.
+    #[inline]
+    pub fn local(&self, vertex: VertexIndex) -> Option<VertexIndex> {
+        self.local
+            .get(&vertex)
+            .cloned()
+            .filter(|&index| self.region.nodes.contains(index))
+    }
+
+    /// Drops every edit and leaves the source graph as it was.
+    #[inline]
+    pub fn discard(self) {}
+
+    /// Replaces the region in the source graph with the edited copy. Vertices
+    /// that survive the edit keep their source index, flags and edges to the
+    /// rest of the graph; removed ones are dropped and new ones are inserted.
+    /// Returns the source index of every vertex in the editor.
+    pub fn commit(self) -> HashMap<VertexIndex, VertexIndex> {
+        let RegionEditor {
+            source,
+            region,
+            origin,
+            local,
+        } = self;
+        let _span = trace_span!("commit_region", vertices = region.nodes.len());
+
+        for (&vertex, &index) in local.iter() {
+            if !region.nodes.contains(index) {
+                source.pinned.remove(&vertex);
+                source.remove_vertex_node(vertex);
+            }
+        }
+
+        let placed: HashMap<VertexIndex, VertexIndex> = region
+            .nodes
+            .iter()
+            .map(|(index, _)| match origin.get(&index) {
+                Some(&vertex) => (index, vertex),
+                None => (index, source.insert_unlabeled()),
+            })
+            .collect();
+        let members: HashSet<VertexIndex> = placed.values().cloned().collect();
+
+        for (&index, &vertex) in placed.iter() {
+            let node = &region.nodes[index];
+            let stale: Vec<V> = source.nodes[vertex]
+                .aliases
+                .difference(&node.aliases)
+                .cloned()
+                .collect();
+            for label in stale.iter() {
+                source.remove_vertex_label(label, vertex);
+            }
+            for label in node.aliases.iter() {
+                source.append_label(vertex, label.clone());
+            }
+
+            let internal: Vec<EdgeIndex> = source.nodes[vertex]
+                .posset
+                .iter()
+                .filter(|dst| members.contains(dst))
+                .map(|&dst| (vertex, dst))
+                .collect();
+            for edge in internal {
+                source.protected.remove(&edge);
+                source.unlink(edge);
+            }
+
+            if region.pinned.contains(&index) {
+                source.pinned.insert(vertex);
+            } else {
+                source.pinned.remove(&vertex);
+            }
+        }
+
+        for (&index, &src) in placed.iter() {
+            for dst in region.nodes[index].posset.iter() {
+                source.connect(src, placed[dst]);
+                if region.protected.contains(&(index, *dst)) {
+                    source.protected.insert((src, placed[dst]));
+                }
+                if let Some(&kind) = region.edge_kinds.get(&(index, *dst)) {
+                    source.edge_kinds.insert((src, placed[dst]), kind);
+                }
+            }
+        }
+        source.debug_check_invariants();
+
+        placed
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Opens an editor over the subgraph induced by `vertices`, carrying over
+    /// labels, pins, protected edges and edge kinds. Returns `None` if any
+    /// vertex is missing.
+    pub fn edit_region<I>(&mut self, vertices: I) -> Option<RegionEditor<'_, V>>
+    where
+        I: IntoIterator<Item = VertexIndex>,
+    {
+        let members: HashSet<VertexIndex> = vertices.into_iter().collect();
+        if !members.iter().all(|&vertex| self.nodes.contains(vertex)) {
+            return None;
+        }
+
+        // Local indices are never reused so removed vertices stay detectable.
+        let mut region = Graph::with_index_allocation(IndexAllocation::NeverReuse);
+        let mut local = HashMap::new();
+        for &vertex in members.iter() {
+            let index = region.insert_unlabeled();
+            for label in self.nodes[vertex].aliases.iter() {
+                region.append_label(index, label.clone());
+            }
+            if self.pinned.contains(&vertex) {
+                region.pinned.insert(index);
+            }
+            local.insert(vertex, index);
+        }
+        for &src in members.iter() {
+            for dst in self.nodes[src]
+                .posset
+                .iter()
+                .filter(|dst| members.contains(dst))
+            {
+                let edge = (local[&src], local[dst]);
+                region.connect(edge.0, edge.1);
+                if self.protected.contains(&(src, *dst)) {
+                    region.protected.insert(edge);
+                }
+                if let Some(&kind) = self.edge_kinds.get(&(src, *dst)) {
+                    region.edge_kinds.insert(edge, kind);
+                }
+            }
+        }
+
+        Some(RegionEditor {
+            source: self,
+            region,
+            origin: local
+                .iter()
+                .map(|(&vertex, &index)| (index, vertex))
+                .collect(),
+            local,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn edit_region() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.set_flag(b, 0);
+
+        let mut editor = graph.edit_region(vec![b, c]).unwrap();
+        let (lb, lc) = (editor.local(b).unwrap(), editor.local(c).unwrap());
+        editor.graph_mut().remove(lc);
+        let e = editor.graph_mut().insert("e");
+        editor.graph_mut().connect(lb, e);
+        editor.graph_mut().append_label(lb, "b2");
+        assert_eq!(editor.local(c), None);
+        editor.discard();
+        assert_eq!(graph.posset(b).unwrap().collect::<Vec<_>>(), vec![c]);
+
+        let mut editor = graph.edit_region(vec![b, c]).unwrap();
+        let (lb, lc) = (editor.local(b).unwrap(), editor.local(c).unwrap());
+        editor.graph_mut().remove(lc);
+        let e = editor.graph_mut().insert("e");
+        editor.graph_mut().connect(lb, e);
+        editor.graph_mut().append_label(lb, "b2");
+        let placed = editor.commit();
+
+        assert_eq!(placed[&lb], b);
+        let e = placed[&e];
+        assert_eq!(graph.posset(b).unwrap().collect::<Vec<_>>(), vec![e]);
+        assert_eq!(graph.preset(b).unwrap().collect::<Vec<_>>(), vec![a]);
+        assert_eq!(graph.get(&"c").map(|mut iter| iter.next()), None);
+        assert_eq!(graph.get(&"b2").unwrap().next(), Some(b));
+        assert_eq!(graph.flag(b, 0), Some(true));
+        assert_eq!(graph.indegree(d), Some(0));
+        assert!(graph.edit_region(vec![a, 42]).is_none());
+    }
+}
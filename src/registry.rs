@@ -0,0 +1,106 @@
+use crate::Graph;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+pub type GraphHandle<V> = Arc<RwLock<Graph<V>>>;
+
+/// Named graphs shared between subsystems. Handles are reference counted, so
+/// replacing a graph never invalidates handles other threads already hold.
+/// Place it in a `static` (e.g. behind `OnceLock`) for process-wide access.
+pub struct Registry<V: Hash + Eq + Clone> {
+    graphs: RwLock<HashMap<String, GraphHandle<V>>>,
+}
+
+impl<V: Hash + Eq + Clone> Default for Registry<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Hash + Eq + Clone> Registry<V> {
+    #[inline]
+    pub fn new() -> Self {
+        Registry {
+            graphs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registers `graph` under `name`, returning the handle it displaced.
+    #[inline]
+    pub fn insert(&self, name: &str, graph: Graph<V>) -> Option<GraphHandle<V>> {
+        self.swap(name, Arc::new(RwLock::new(graph)))
+    }
+
+    /// Atomically points `name` at `handle`, returning the previous handle.
+    #[inline]
+    pub fn swap(&self, name: &str, handle: GraphHandle<V>) -> Option<GraphHandle<V>> {
+        self.graphs
+            .write()
+            .unwrap()
+            .insert(name.to_string(), handle)
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<GraphHandle<V>> {
+        self.graphs.read().unwrap().get(name).cloned()
+    }
+
+    #[inline]
+    pub fn remove(&self, name: &str) -> Option<GraphHandle<V>> {
+        self.graphs.write().unwrap().remove(name)
+    }
+
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.graphs.read().unwrap().contains_key(name)
+    }
+
+    #[inline]
+    pub fn names(&self) -> Vec<String> {
+        self.graphs.read().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn shared_registry() {
+        let registry = Arc::new(Registry::new());
+        let mut graph = Graph::new();
+        graph.insert("a");
+        assert!(registry.insert("netlist", graph).is_none());
+
+        let worker = {
+            let registry = registry.clone();
+            thread::spawn(move || {
+                let handle = registry.get("netlist").unwrap();
+                handle.write().unwrap().insert("b");
+            })
+        };
+        worker.join().unwrap();
+
+        let handle = registry.get("netlist").unwrap();
+        assert_eq!(handle.read().unwrap().count_labeled(&"b"), Some(1));
+
+        let old = registry.insert("netlist", Graph::new()).unwrap();
+        assert!(Arc::ptr_eq(&old, &handle));
+        assert_eq!(
+            registry
+                .get("netlist")
+                .unwrap()
+                .read()
+                .unwrap()
+                .count_labeled(&"a"),
+            None
+        );
+        assert_eq!(registry.names(), vec!["netlist".to_string()]);
+        assert!(registry.remove("netlist").is_some());
+        assert!(!registry.contains("netlist"));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{Graph, VertexIndex};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+/// How to pick a single vertex when a label is carried by several.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliasPolicy {
+    /// Fail with every candidate unless exactly one vertex carries the label.
+    Unique,
+    /// The vertex with the most incident edges.
+    MostConnected,
+    /// The vertex inserted last, merged vertices counting as inserted when
+    /// merged. Insertion order is tracked apart from indices, so it holds
+    /// under every `IndexAllocation`.
+    MostRecent,
+    /// The vertex with the highest label weight, see `best_labeled`.
+    HighestWeight,
+}
+
+/// A vertex competing for a label, with enough context to report it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub vertex: VertexIndex,
+    pub indegree: usize,
+    pub outdegree: usize,
+    pub labels: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    /// No vertex carries the label.
+    Unknown,
+    /// Several vertices carry the label and the policy could not choose;
+    /// candidates are sorted by index.
+    Ambiguous(Vec<Candidate>),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Unknown => write!(f, "no vertex carries the label"),
+            ResolveError::Ambiguous(candidates) => {
+                write!(f, "label is ambiguous between vertices")?;
+                for candidate in candidates.iter() {
+                    write!(
+                        f,
+                        " {} (in {}, out {}, {} labels)",
+                        candidate.vertex, candidate.indegree, candidate.outdegree, candidate.labels
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Vertices carrying `label` with their degrees and label counts, sorted
+    /// by index.
+    pub fn label_candidates(&self, label: &V) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = self
+            .aliases
+            .get(label)
+            .into_iter()
+            .flatten()
+            .map(|&vertex| {
+                let node = &self.nodes[vertex];
+                Candidate {
+                    vertex,
+                    indegree: node.preset.len(),
+                    outdegree: node.posset.len(),
+                    labels: node.aliases.len(),
+                }
+            })
+            .collect();
+        candidates.sort_unstable_by_key(|candidate| candidate.vertex);
+        candidates
+    }
+
+    /// Resolves `label` to a single vertex according to `policy`. Ties under
+    /// `MostConnected` go to the lowest index.
+    pub fn resolve_label(
+        &self,
+        label: &V,
+        policy: AliasPolicy,
+    ) -> Result<VertexIndex, ResolveError> {
+        let candidates = self.label_candidates(label);
+        match (candidates.len(), policy) {
+            (0, _) => Err(ResolveError::Unknown),
+            (1, _) => Ok(candidates[0].vertex),
+            (_, AliasPolicy::Unique) => Err(ResolveError::Ambiguous(candidates)),
+            (_, AliasPolicy::MostConnected) => Ok(candidates
+                .iter()
+                .rev()
+                .max_by_key(|candidate| candidate.indegree + candidate.outdegree)
+                .unwrap()
+                .vertex),
+            (_, AliasPolicy::MostRecent) => Ok(candidates
+                .iter()
+                .max_by_key(|candidate| self.nodes[candidate.vertex].stamp)
+                .unwrap()
+                .vertex),
+            (_, AliasPolicy::HighestWeight) => Ok(self.best_labeled(label).unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn resolve_label() {
+        let mut graph = Graph::new();
+        let a = graph.insert("x");
+        let b = graph.insert("x");
+        let c = graph.insert("x");
+        let d = graph.insert("y");
+        graph.connect(b, d);
+        graph.connect(d, b);
+        graph.connect(c, d);
+        graph.append_vertex_label_weighted(a, "x", 5.0);
+
+        assert_eq!(graph.resolve_label(&"y", AliasPolicy::Unique), Ok(d));
+        assert_eq!(graph.resolve_label(&"x", AliasPolicy::MostConnected), Ok(b));
+        assert_eq!(graph.resolve_label(&"x", AliasPolicy::MostRecent), Ok(c));
+        assert_eq!(graph.resolve_label(&"x", AliasPolicy::HighestWeight), Ok(a));
+        assert_eq!(
+            graph.resolve_label(&"z", AliasPolicy::MostRecent),
+            Err(ResolveError::Unknown)
+        );
+
+        let err = graph.resolve_label(&"x", AliasPolicy::Unique).unwrap_err();
+        match &err {
+            ResolveError::Ambiguous(candidates) => {
+                assert_eq!(candidates.len(), 3);
+                assert_eq!(
+                    candidates[1],
+                    Candidate {
+                        vertex: b,
+                        indegree: 1,
+                        outdegree: 1,
+                        labels: 1
+                    }
+                );
+            }
+            _ => panic!("expected an ambiguity"),
+        }
+        assert!(err.to_string().contains("(in 0, out 1, 1 labels)"));
+    }
+
+    #[test]
+    fn most_recent_in_freed_slot() {
+        let mut graph = Graph::new();
+        let a = graph.insert("x");
+        graph.insert("x");
+        let c = graph.insert("x");
+        graph.remove(a);
+        let d = graph.insert("x");
+        assert_eq!(d, a);
+        assert_eq!(graph.resolve_label(&"x", AliasPolicy::MostRecent), Ok(d));
+
+        graph.remove(c);
+        assert_eq!(graph.resolve_label(&"x", AliasPolicy::MostRecent), Ok(d));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::unionfind::UnionFind;
+use crate::{BitSet, Graph, VertexIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+
+/// Order in which `simulate_removal` takes vertices out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemovalOrder {
+    /// Uniformly random failures.
+    Random,
+    /// Highest initial total degree first, the classic targeted attack.
+    Degree,
+    /// Highest total degree among the remaining vertices first, recomputed
+    /// after every removal.
+    AdaptiveDegree,
+    /// An explicit order, e.g. vertices sorted by some centrality. Missing
+    /// and repeated vertices are skipped; unlisted ones are never removed.
+    Given(Vec<VertexIndex>),
+}
+
+/// What `simulate_removal` measures after each removal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RobustnessMetric {
+    /// Size of the largest weakly connected component.
+    LargestComponent,
+    /// Number of ordered pairs of distinct vertices where the first reaches
+    /// the second. Costs a search from every vertex at every step.
+    ReachablePairs,
+}
+
+/// Result of `simulate_removal`: `values[i]` is the metric once the first
+/// `i` vertices of `order` are gone, so it has one more entry than `order`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemovalCurve {
+    pub order: Vec<VertexIndex>,
+    pub values: Vec<usize>,
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    fn removal_sequence<R: Rng + ?Sized>(
+        &self,
+        order: RemovalOrder,
+        rng: &mut R,
+    ) -> Vec<VertexIndex> {
+        let degree =
+            |vertex: VertexIndex| self.nodes[vertex].preset.len() + self.nodes[vertex].posset.len();
+        let mut vertices: Vec<VertexIndex> = self.nodes.iter().map(|(vertex, _)| vertex).collect();
+        match order {
+            RemovalOrder::Random => {
+                vertices.shuffle(rng);
+                vertices
+            }
+            RemovalOrder::Degree => {
+                vertices.sort_by_key(|&vertex| (Reverse(degree(vertex)), vertex));
+                vertices
+            }
+            RemovalOrder::AdaptiveDegree => {
+                let mut current: Vec<usize> = vec![0; self.nodes.capacity()];
+                let mut heap = BinaryHeap::new();
+                for &vertex in vertices.iter() {
+                    current[vertex] = degree(vertex);
+                    heap.push((current[vertex], Reverse(vertex)));
+                }
+                let mut removed = BitSet::default();
+                removed.grow(self.nodes.capacity());
+                let mut sequence = Vec::with_capacity(vertices.len());
+                while let Some((deg, Reverse(vertex))) = heap.pop() {
+                    if removed.contains(vertex) || deg != current[vertex] {
+                        continue;
+                    }
+                    removed.insert(vertex);
+                    sequence.push(vertex);
+                    let node = &self.nodes[vertex];
+                    for &other in node.preset.iter().chain(node.posset.iter()) {
+                        if !removed.contains(other) {
+                            current[other] -= 1;
+                            heap.push((current[other], Reverse(other)));
+                        }
+                    }
+                }
+                sequence
+            }
+            RemovalOrder::Given(given) => {
+                let mut seen = BitSet::default();
+                seen.grow(self.nodes.capacity());
+                given
+                    .into_iter()
+                    .filter(|&vertex| self.nodes.contains(vertex) && seen.insert(vertex))
+                    .collect()
+            }
+        }
+    }
+
+    /// Largest component sizes while removing `order`, obtained by adding the
+    /// vertices back in reverse into a union-find.
+    fn largest_component_curve(&self, order: &[VertexIndex]) -> Vec<usize> {
+        let mut present = BitSet::default();
+        present.grow(self.nodes.capacity());
+        let mut sets = UnionFind::default();
+        for (vertex, _) in self.nodes.iter() {
+            present.insert(vertex);
+            sets.make_set(vertex);
+        }
+        for &vertex in order.iter() {
+            present.remove(vertex);
+        }
+
+        let mut largest = 0;
+        for (vertex, node) in self
+            .nodes
+            .iter()
+            .filter(|&(vertex, _)| present.contains(vertex))
+        {
+            for &dst in node.posset.iter().filter(|&&dst| present.contains(dst)) {
+                sets.union(vertex, dst);
+            }
+        }
+        for (vertex, _) in self
+            .nodes
+            .iter()
+            .filter(|&(vertex, _)| present.contains(vertex))
+        {
+            largest = largest.max(sets.set_size(vertex));
+        }
+
+        let mut values = vec![largest];
+        for &vertex in order.iter().rev() {
+            present.insert(vertex);
+            for other in self
+                .undirected_neighbors(vertex)
+                .filter(|&other| present.contains(other))
+            {
+                sets.union(vertex, other);
+            }
+            largest = largest.max(sets.set_size(vertex));
+            values.push(largest);
+        }
+        values.reverse();
+        values
+    }
+
+    fn reachable_pairs(&self, present: &BitSet) -> usize {
+        let mut pairs = 0;
+        let mut seen = BitSet::default();
+        let mut stack = Vec::new();
+        for (src, _) in self
+            .nodes
+            .iter()
+            .filter(|&(vertex, _)| present.contains(vertex))
+        {
+            seen.clear();
+            seen.grow(self.nodes.capacity());
+            seen.insert(src);
+            stack.push(src);
+            while let Some(vertex) = stack.pop() {
+                for &dst in self.nodes[vertex].posset.iter() {
+                    if present.contains(dst) && seen.insert(dst) {
+                        pairs += 1;
+                        stack.push(dst);
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Removes vertices one at a time in the given `order`, without touching
+    /// the graph itself, and records how `metric` decays. `rng` is only used
+    /// by `RemovalOrder::Random`.
+    pub fn simulate_removal<R: Rng + ?Sized>(
+        &self,
+        order: RemovalOrder,
+        metric: RobustnessMetric,
+        rng: &mut R,
+    ) -> RemovalCurve {
+        let _span = trace_span!("simulate_removal", vertices = self.nodes.len());
+        let order = self.removal_sequence(order, rng);
+        let values = match metric {
+            RobustnessMetric::LargestComponent => self.largest_component_curve(&order),
+            RobustnessMetric::ReachablePairs => {
+                let mut present = BitSet::default();
+                present.grow(self.nodes.capacity());
+                for (vertex, _) in self.nodes.iter() {
+                    present.insert(vertex);
+                }
+                let mut values = vec![self.reachable_pairs(&present)];
+                for &vertex in order.iter() {
+                    present.remove(vertex);
+                    values.push(self.reachable_pairs(&present));
+                }
+                values
+            }
+        };
+
+        RemovalCurve { order, values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn simulate_removal() {
+        // A star whose hub also leads into a short chain.
+        let mut graph = Graph::new();
+        let hub = graph.insert("hub");
+        let spokes: Vec<VertexIndex> = (0..4).map(|_| graph.insert("spoke")).collect();
+        for &spoke in spokes.iter() {
+            graph.connect(hub, spoke);
+        }
+        let chain: Vec<VertexIndex> = (0..2).map(|_| graph.insert("chain")).collect();
+        graph.connect(spokes[0], chain[0]);
+        graph.connect(chain[0], chain[1]);
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let attack = graph.simulate_removal(
+            RemovalOrder::Degree,
+            RobustnessMetric::LargestComponent,
+            &mut rng,
+        );
+        assert_eq!(attack.order[0], hub);
+        assert_eq!(attack.values[..3], [7, 3, 2]);
+        assert_eq!(attack.values.len(), 8);
+        assert_eq!(*attack.values.last().unwrap(), 0);
+
+        let adaptive = graph.simulate_removal(
+            RemovalOrder::AdaptiveDegree,
+            RobustnessMetric::LargestComponent,
+            &mut rng,
+        );
+        assert_eq!(adaptive.order[..2], [hub, chain[0]]);
+
+        let random = graph.simulate_removal(
+            RemovalOrder::Random,
+            RobustnessMetric::LargestComponent,
+            &mut rng,
+        );
+        assert_eq!(random.order.len(), 7);
+        assert!(random.values.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        let given = graph.simulate_removal(
+            RemovalOrder::Given(vec![chain[1], 42, chain[1], hub]),
+            RobustnessMetric::ReachablePairs,
+            &mut rng,
+        );
+        assert_eq!(given.order, vec![chain[1], hub]);
+        assert_eq!(given.values, vec![9, 6, 1]);
+    }
+}
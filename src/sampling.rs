@@ -0,0 +1,415 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// Builds a graph from an edge stream of unbounded length while keeping at
+/// most `max_degree` successors and `max_degree` predecessors per vertex.
+///
+/// Every distinct edge gets a pseudo-random rank, and each vertex keeps the
+/// lowest-ranked edges on its out and in sides, a uniform sample of the
+/// distinct edges it was offered. Repeated edges share a rank and so count
+/// once. An edge survives only if it is sampled at both endpoints, so a
+/// vertex may end below the cap when its edges lost out at the other end.
+pub struct CappedBuilder<V: Hash + Eq + Clone> {
+    max_degree: usize,
+    salt: u64,
+    edges_seen: usize,
+    outs: HashMap<V, BTreeMap<u64, V>>,
+    ins: HashMap<V, BTreeMap<u64, V>>,
+}
+
+/// 64-bit FNV-1a followed by the SplitMix64 finalizer. Unlike the standard
+/// library hashers its output is fixed, so a seeded `CappedBuilder` draws the
+/// same sample on every toolchain.
+struct RankHasher(u64);
+
+impl RankHasher {
+    fn new(salt: u64) -> Self {
+        let mut hasher = RankHasher(0xcbf2_9ce4_8422_2325);
+        hasher.write(&salt.to_le_bytes());
+        hasher
+    }
+}
+
+impl Hasher for RankHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter() {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut z = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Inserts `vertex` under `rank` into `sample`, keeping the `cap` lowest
+/// ranks.
+fn keep_lowest<V>(sample: &mut BTreeMap<u64, V>, rank: u64, vertex: V, cap: usize) {
+    if sample.len() < cap || sample.keys().next_back().is_some_and(|&top| rank < top) {
+        sample.insert(rank, vertex);
+        if sample.len() > cap {
+            sample.pop_last();
+        }
+    }
+}
+
+impl<V: Hash + Eq + Clone> CappedBuilder<V> {
+    /// The rank salt is drawn from `rng` and ranks are hashed with a fixed
+    /// function, so a seeded generator makes the sample reproducible.
+    #[inline]
+    pub fn new<R: Rng>(max_degree: usize, mut rng: R) -> Self {
+        CappedBuilder {
+            max_degree,
+            salt: rng.gen(),
+            edges_seen: 0,
+            outs: HashMap::new(),
+            ins: HashMap::new(),
+        }
+    }
+
+    fn rank(&self, src: &V, dst: &V) -> u64 {
+        let mut hasher = RankHasher::new(self.salt);
+        (src, dst).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn push(&mut self, src: V, dst: V) {
+        self.edges_seen += 1;
+        let rank = self.rank(&src, &dst);
+        self.outs.entry(dst.clone()).or_default();
+        self.ins.entry(src.clone()).or_default();
+        let outs = self.outs.entry(src.clone()).or_default();
+        keep_lowest(outs, rank, dst.clone(), self.max_degree);
+        let ins = self.ins.entry(dst).or_default();
+        keep_lowest(ins, rank, src, self.max_degree);
+    }
+
+    #[inline]
+    pub fn edges_seen(&self) -> usize {
+        self.edges_seen
+    }
+
+    pub fn build(self) -> Graph<V> {
+        let mut graph = Graph::new();
+        let index: HashMap<V, _> = self
+            .outs
+            .keys()
+            .map(|label| (label.clone(), graph.insert(label.clone())))
+            .collect();
+        for (src, dsts) in self.outs.iter() {
+            for (rank, dst) in dsts.iter() {
+                if self.ins[dst].contains_key(rank) {
+                    graph.connect(index[src], index[dst]);
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<V: Hash + Eq + Clone> Extend<(V, V)> for CappedBuilder<V> {
+    fn extend<I: IntoIterator<Item = (V, V)>>(&mut self, iter: I) {
+        for (src, dst) in iter {
+            self.push(src, dst);
+        }
+    }
+}
+
+/// Relative error allowed in the squared norms of the random projection
+/// used to estimate effective resistances.
+const JL_DISTORTION: f64 = 0.5;
+
+/// `L x` for the Laplacian of the unit-weight multigraph `links`.
+fn laplacian_product(links: &[(usize, usize)], x: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; x.len()];
+    for &(u, v) in links.iter() {
+        let flow = x[u] - x[v];
+        product[u] += flow;
+        product[v] -= flow;
+    }
+    product
+}
+
+/// Solves `L x = b` with conjugate gradients. `b` must sum to zero over
+/// every connected component, as any combination of incidence rows does.
+fn solve_laplacian(links: &[(usize, usize)], b: &[f64]) -> Vec<f64> {
+    let dot = |x: &[f64], y: &[f64]| x.iter().zip(y.iter()).map(|(a, b)| a * b).sum::<f64>();
+    let mut x = vec![0.0; b.len()];
+    let mut residual = b.to_vec();
+    let mut direction = residual.clone();
+    let mut norm = dot(&residual, &residual);
+    let tolerance = norm * 1e-20;
+    for _ in 0..2 * b.len() + 10 {
+        if norm <= tolerance {
+            break;
+        }
+        let product = laplacian_product(links, &direction);
+        let curvature = dot(&direction, &product);
+        if curvature <= 0.0 {
+            break;
+        }
+        let step = norm / curvature;
+        for i in 0..x.len() {
+            x[i] += step * direction[i];
+            residual[i] -= step * product[i];
+        }
+        let next = dot(&residual, &residual);
+        for i in 0..x.len() {
+            direction[i] = residual[i] + next / norm * direction[i];
+        }
+        norm = next;
+    }
+    x
+}
+
+/// Effective resistance of every link, within `JL_DISTORTION` with high
+/// probability, as `|Z (e_u - e_v)|²` where `Z = Q B L⁺` for the incidence
+/// matrix `B` and a random ±1 projection `Q`.
+fn approximate_resistances<R: Rng + ?Sized>(
+    vertices: usize,
+    links: &[(usize, usize)],
+    rng: &mut R,
+) -> Vec<f64> {
+    let mut resistance = vec![0.0; links.len()];
+    if links.is_empty() {
+        return resistance;
+    }
+    let n = vertices.max(2) as f64;
+    let rows = (24.0 * n.ln() / (JL_DISTORTION * JL_DISTORTION)).ceil() as usize;
+    let scale = 1.0 / (rows as f64).sqrt();
+    for _ in 0..rows {
+        let mut projected = vec![0.0; vertices];
+        for &(u, v) in links.iter() {
+            let sign = if rng.gen_bool(0.5) { scale } else { -scale };
+            projected[u] += sign;
+            projected[v] -= sign;
+        }
+        let z = solve_laplacian(links, &projected);
+        for (r, &(u, v)) in resistance.iter_mut().zip(links.iter()) {
+            *r += (z[u] - z[v]) * (z[u] - z[v]);
+        }
+    }
+    resistance
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Spectral sparsifier after Spielman and Srivastava, treating every edge
+    /// as an undirected edge of weight 1. Each edge is kept independently
+    /// with probability proportional to its effective resistance, at least
+    /// `9 ln(n) R / epsilon²`, and reweighted by the inverse of that
+    /// probability; protected edges and self-loops are always kept with
+    /// weight 1. With high probability the reweighted Laplacian satisfies
+    /// `(1 - epsilon) L <= L' <= (1 + epsilon) L`, so the weight of every cut
+    /// is within a factor `1 ± epsilon` of the original one.
+    ///
+    /// Resistances are approximated within a factor of two by projecting the
+    /// edge-vertex incidence matrix onto `O(log n)` random ±1 vectors and
+    /// solving the resulting Laplacian systems with conjugate gradients.
+    pub fn sparsify<R: Rng + ?Sized>(
+        &self,
+        epsilon: f64,
+        rng: &mut R,
+    ) -> (Graph<V>, HashMap<EdgeIndex, f64>) {
+        let _span = trace_span!("sparsify", vertices = self.nodes.len());
+        let dense: HashMap<VertexIndex, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (vertex, _))| (vertex, i))
+            .collect();
+        let edges: Vec<EdgeIndex> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .collect();
+        let links: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|&&(src, dst)| src != dst)
+            .map(|&(src, dst)| (dense[&src], dense[&dst]))
+            .collect();
+        let resistance = approximate_resistances(dense.len(), &links, rng);
+
+        // Projection error of at most a half in squared norm: dividing by
+        // (1 - 1/2) keeps every estimate above the true resistance.
+        let n = (dense.len().max(2)) as f64;
+        let scale = 9.0 * n.ln() / (epsilon * epsilon) / (1.0 - JL_DISTORTION);
+        let mut sparse = self.clone();
+        let mut weights = HashMap::new();
+        let mut link = 0;
+        for &(src, dst) in edges.iter() {
+            let p = if src == dst {
+                1.0
+            } else {
+                link += 1;
+                if self.protected.contains(&(src, dst)) {
+                    1.0
+                } else {
+                    (scale * resistance[link - 1]).min(1.0)
+                }
+            };
+            if p >= 1.0 || rng.gen_bool(p) {
+                weights.insert((src, dst), 1.0 / p);
+            } else {
+                sparse.disconnect((src, dst));
+            }
+        }
+
+        (sparse, weights)
+    }
+
+    /// Randomizes the graph with up to `iterations` double-edge swaps,
+    /// replacing `(a, b)` and `(c, d)` by `(a, d)` and `(c, b)`. Every
+    /// in-degree and out-degree is preserved, which makes the result a null
+    /// model for the original. Swaps that would create a self-loop or a
+    /// duplicate edge, or touch a protected edge, are skipped. Returns the
+    /// number of swaps performed.
+    pub fn rewire<R: Rng + ?Sized>(&mut self, iterations: usize, rng: &mut R) -> usize {
+        let _span = trace_span!("rewire", iterations);
+        let mut edges: Vec<EdgeIndex> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .filter(|edge| !self.protected.contains(edge))
+            .collect();
+        edges.sort_unstable();
+        if edges.len() < 2 {
+            return 0;
+        }
+
+        let mut swaps = 0;
+        for _ in 0..iterations {
+            let (i, j) = (rng.gen_range(0..edges.len()), rng.gen_range(0..edges.len()));
+            let ((a, b), (c, d)) = (edges[i], edges[j]);
+            if a == c || b == d || a == d || c == b {
+                continue;
+            }
+            if self.nodes[a].posset.contains(&d) || self.nodes[c].posset.contains(&b) {
+                continue;
+            }
+            self.disconnect((a, b));
+            self.disconnect((c, d));
+            self.connect(a, d);
+            self.connect(c, b);
+            edges[i] = (a, d);
+            edges[j] = (c, b);
+            swaps += 1;
+        }
+
+        swaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn capped_builder() {
+        let mut builder = CappedBuilder::new(3, StdRng::seed_from_u64(11));
+        builder.extend((0..100).map(|i| ("hub".to_string(), i.to_string())));
+        builder.extend((0..100).map(|i| (i.to_string(), "hub".to_string())));
+        for _ in 0..10 {
+            builder.push("a".to_string(), "b".to_string());
+        }
+        builder.push("a".to_string(), "c".to_string());
+        assert_eq!(builder.edges_seen(), 211);
+
+        let graph = builder.build();
+        let hub = graph.get("hub").unwrap().next().unwrap();
+        let a = graph.get("a").unwrap().next().unwrap();
+        assert_eq!(graph.outdegree(hub), Some(3));
+        assert_eq!(graph.indegree(hub), Some(3));
+        // Ranks are hashed with a fixed function, so the sample is pinned.
+        let mut picked: Vec<&str> = graph
+            .posset(hub)
+            .unwrap()
+            .map(|v| graph.labels(v).unwrap().next().unwrap().as_str())
+            .collect();
+        picked.sort_unstable();
+        assert_eq!(picked, vec!["21", "64", "74"]);
+        assert_eq!(graph.outdegree(a), Some(2));
+        assert_eq!(graph.count_labeled(&"7".to_string()), Some(1));
+    }
+
+    #[test]
+    fn sparsify() {
+        // Two cliques joined by a single bridge, whose resistance of 1 makes
+        // it indispensable while clique edges are cheap to drop.
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..40).map(|i| graph.insert(i)).collect();
+        for half in vertices.chunks(20) {
+            for &src in half.iter() {
+                for &dst in half.iter().filter(|&&dst| dst > src) {
+                    graph.connect(src, dst);
+                }
+            }
+        }
+        let bridge = (vertices[0], vertices[20]);
+        graph.connect(bridge.0, bridge.1);
+
+        // Graphs this small only lose edges under a loose epsilon; tighter
+        // ones keep everything.
+        let mut rng = StdRng::seed_from_u64(7);
+        let (sparse, weights) = graph.sparsify(3.0, &mut rng);
+        let kept: usize = vertices.iter().map(|&v| sparse.outdegree(v).unwrap()).sum();
+        assert_eq!(kept, weights.len());
+        assert!(kept < 2 * 190);
+        assert_eq!(weights.get(&bridge), Some(&1.0));
+        for (&(src, dst), &weight) in weights.iter() {
+            assert!(sparse.posset(src).unwrap().any(|v| v == dst));
+            assert!(weight >= 1.0);
+        }
+        let total: f64 = weights.values().sum();
+        assert!((total - 381.0).abs() < 0.15 * 381.0);
+
+        let (dense, _) = graph.sparsify(0.01, &mut rng);
+        assert_eq!(dense.outdegree(vertices[0]), Some(20));
+    }
+
+    #[test]
+    fn rewire() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..30).map(|i| graph.insert(i)).collect();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..90 {
+            let src = vertices[rng.gen_range(0..30)];
+            let dst = vertices[rng.gen_range(0..30)];
+            if src != dst {
+                graph.connect(src, dst);
+            }
+        }
+        graph.protect_edge((vertices[0], graph.posset(vertices[0]).unwrap().sorted()[0]));
+        let degrees = |graph: &Graph<i32>| -> Vec<(usize, usize)> {
+            vertices
+                .iter()
+                .map(|&v| (graph.indegree(v).unwrap(), graph.outdegree(v).unwrap()))
+                .collect()
+        };
+        let protected: Vec<EdgeIndex> = vertices
+            .iter()
+            .flat_map(|&v| graph.posset(v).unwrap().map(move |dst| (v, dst)))
+            .filter(|&edge| graph.is_protected(edge) == Some(true))
+            .collect();
+
+        let before = graph.clone();
+        let swaps = graph.rewire(500, &mut rng);
+        assert!(swaps > 100);
+        assert_eq!(degrees(&graph), degrees(&before));
+        assert!(vertices
+            .iter()
+            .any(|&v| { graph.posset(v).unwrap().sorted() != before.posset(v).unwrap().sorted() }));
+        for &(src, dst) in protected.iter() {
+            assert_eq!(graph.is_protected((src, dst)), Some(true));
+        }
+        assert!(vertices
+            .iter()
+            .all(|&v| !graph.posset(v).unwrap().any(|dst| dst == v)));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{Graph, VertexIndex};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// List-schedules the DAG honoring precedence edges and the capacity of
+    /// each resource kind, prioritising vertices on the critical path.
+    ///
+    /// `usage(vertex, kind)` is the amount of resource `kind` held by `vertex`
+    /// for its whole `duration`. Returns the start time of every vertex, or
+    /// `None` if the graph is cyclic or some vertex needs more of a resource
+    /// than is available.
+    pub fn schedule<D, U>(
+        &self,
+        resources: &[u64],
+        duration: D,
+        usage: U,
+    ) -> Option<HashMap<VertexIndex, u64>>
+    where
+        D: Fn(VertexIndex) -> u64,
+        U: Fn(VertexIndex, usize) -> u64,
+    {
+        let _span = trace_span!("schedule", vertices = self.nodes.len());
+        let order = self.topological_order()?;
+        if order
+            .iter()
+            .any(|&v| (0..resources.len()).any(|kind| usage(v, kind) > resources[kind]))
+        {
+            return None;
+        }
+
+        let mut level: HashMap<VertexIndex, u64> = HashMap::with_capacity(order.len());
+        for &vertex in order.iter().rev() {
+            let tail = self.nodes[vertex]
+                .posset
+                .iter()
+                .map(|dst| level[dst])
+                .max()
+                .unwrap_or(0);
+            level.insert(vertex, duration(vertex) + tail);
+        }
+
+        let mut pending: HashMap<VertexIndex, usize> = self
+            .nodes
+            .iter()
+            .map(|(index, node)| (index, node.preset.len()))
+            .collect();
+        let mut ready: Vec<VertexIndex> = self.trunks.iter().cloned().collect();
+        let mut running = BinaryHeap::new();
+        let mut in_use = vec![0; resources.len()];
+        let mut start = HashMap::with_capacity(order.len());
+        let mut time = 0;
+
+        while start.len() < order.len() || !running.is_empty() {
+            ready.sort_by_key(|&v| (Reverse(level[&v]), v));
+            ready.retain(|&vertex| {
+                let fits = (0..resources.len())
+                    .all(|kind| in_use[kind] + usage(vertex, kind) <= resources[kind]);
+                if fits {
+                    for (kind, used) in in_use.iter_mut().enumerate() {
+                        *used += usage(vertex, kind);
+                    }
+                    start.insert(vertex, time);
+                    running.push(Reverse((time + duration(vertex), vertex)));
+                }
+                !fits
+            });
+
+            let Reverse((finish, _)) = *running.peek()?;
+            time = finish;
+            while let Some(&Reverse((finish, vertex))) = running.peek() {
+                if finish > time {
+                    break;
+                }
+                running.pop();
+                for (kind, used) in in_use.iter_mut().enumerate() {
+                    *used -= usage(vertex, kind);
+                }
+                for &dst in self.nodes[vertex].posset.iter() {
+                    let count = pending.get_mut(&dst).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(dst);
+                    }
+                }
+            }
+        }
+
+        Some(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn resource_constrained_schedule() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, d);
+        graph.connect(c, d);
+
+        let duration = |v| if v == b { 3 } else { 1 };
+        let start = graph.schedule(&[1], duration, |_, _| 1).unwrap();
+        assert_eq!(start[&a], 0);
+        assert_eq!(start[&b], 1);
+        assert_eq!(start[&c], 4);
+        assert_eq!(start[&d], 5);
+
+        let start = graph.schedule(&[2], duration, |_, _| 1).unwrap();
+        assert_eq!(start[&b], 1);
+        assert_eq!(start[&c], 1);
+        assert_eq!(start[&d], 4);
+
+        assert_eq!(graph.schedule(&[1], duration, |_, _| 2), None);
+        graph.connect(d, a);
+        assert_eq!(graph.schedule(&[2], duration, |_, _| 1), None);
+    }
+}
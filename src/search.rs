@@ -0,0 +1,203 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    /// Repeated depth-first passes with a growing cost threshold (IDA* without
+    /// a heuristic). Memory is linear in the path length.
+    IterativeDeepening,
+    /// Single depth-first pass pruning any path that cannot beat the best
+    /// solution found so far.
+    BranchAndBound,
+}
+
+struct Search<'a, V: Eq + Hash + Clone, G, C> {
+    graph: &'a Graph<V>,
+    goal: G,
+    cost: C,
+    limit: u64,
+    path: Vec<VertexIndex>,
+    on_path: HashSet<VertexIndex>,
+    best: Option<(Vec<VertexIndex>, u64)>,
+    exceeded: Option<u64>,
+}
+
+impl<'a, V, G, C> Search<'a, V, G, C>
+where
+    V: Eq + Hash + Clone,
+    G: Fn(VertexIndex) -> bool,
+    C: Fn(EdgeIndex) -> u64,
+{
+    /// Records `vertex` as a solution if it satisfies the goal, returning
+    /// whether it did.
+    fn reached(&mut self, vertex: VertexIndex, spent: u64) -> bool {
+        if !(self.goal)(vertex) {
+            return false;
+        }
+        self.best = Some((self.path.clone(), spent));
+        self.limit = spent.saturating_sub(1);
+        true
+    }
+
+    /// Depth-first pass from `start` within the current limit, with an
+    /// explicit frame stack so that deep graphs cannot overflow the call
+    /// stack. Returns whether the search should stop.
+    fn visit(&mut self, start: VertexIndex, exhaustive: bool) -> bool {
+        if self.reached(start, 0) {
+            return !exhaustive;
+        }
+
+        let graph = self.graph;
+        let mut frames = vec![(start, 0u64, graph.nodes[start].posset.iter())];
+        while let Some((vertex, spent, succs)) = frames.last_mut() {
+            let (vertex, spent) = (*vertex, *spent);
+            let dst = match succs.next() {
+                Some(&dst) => dst,
+                None => {
+                    frames.pop();
+                    if !frames.is_empty() {
+                        self.on_path.remove(&vertex);
+                        self.path.pop();
+                    }
+                    continue;
+                }
+            };
+            if self.on_path.contains(&dst) {
+                continue;
+            }
+            let total = spent.saturating_add((self.cost)((vertex, dst)));
+            if total > self.limit {
+                self.exceeded = Some(self.exceeded.map_or(total, |e| e.min(total)));
+                continue;
+            }
+            self.path.push(dst);
+            if self.reached(dst, total) {
+                self.path.pop();
+                if !exhaustive {
+                    return true;
+                }
+                continue;
+            }
+            self.on_path.insert(dst);
+            frames.push((dst, total, graph.nodes[dst].posset.iter()));
+        }
+
+        false
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Finds the cheapest simple path from `start` to any vertex satisfying
+    /// `goal` whose cost does not exceed `bound`, without keeping a frontier
+    /// of the whole graph in memory.
+    pub fn search<G, C>(
+        &self,
+        start: VertexIndex,
+        goal: G,
+        cost: C,
+        bound: u64,
+        mode: SearchMode,
+    ) -> Option<(Vec<VertexIndex>, u64)>
+    where
+        G: Fn(VertexIndex) -> bool,
+        C: Fn(EdgeIndex) -> u64,
+    {
+        if !self.nodes.contains(start) {
+            return None;
+        }
+
+        let _span = trace_span!("search", start, bound);
+        let mut search = Search {
+            graph: self,
+            goal,
+            cost,
+            limit: bound,
+            path: vec![start],
+            on_path: vec![start].into_iter().collect(),
+            best: None,
+            exceeded: None,
+        };
+
+        match mode {
+            SearchMode::BranchAndBound => {
+                search.visit(start, true);
+            }
+            SearchMode::IterativeDeepening => {
+                let mut threshold = 0;
+                loop {
+                    search.limit = threshold;
+                    search.exceeded = None;
+                    if search.visit(start, false) {
+                        break;
+                    }
+                    match search.exceeded {
+                        Some(next) if next <= bound => threshold = next,
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        search.best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn bounded_search() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, d);
+        graph.connect(a, c);
+        graph.connect(c, d);
+        graph.connect(d, a);
+
+        let cost = |(src, dst)| if (src, dst) == (a, b) { 5 } else { 2 };
+        for &mode in [SearchMode::IterativeDeepening, SearchMode::BranchAndBound].iter() {
+            assert_eq!(
+                graph.search(a, |v| v == d, cost, 10, mode),
+                Some((vec![a, c, d], 4))
+            );
+            assert_eq!(graph.search(a, |v| v == d, cost, 3, mode), None);
+            assert_eq!(
+                graph.search(b, |v| v == c, cost, 10, mode),
+                Some((vec![b, d, a, c], 6))
+            );
+            assert_eq!(
+                graph.search(a, |v| v == a, cost, 0, mode),
+                Some((vec![a], 0))
+            );
+        }
+    }
+
+    #[test]
+    fn deep_search() {
+        let mut graph = Graph::new();
+        let chain: Vec<VertexIndex> = (0..600).map(|_| graph.insert(0)).collect();
+        for pair in chain.windows(2) {
+            graph.connect(pair[0], pair[1]);
+        }
+        let (first, last) = (chain[0], chain[599]);
+        let found = std::thread::Builder::new()
+            .stack_size(32 * 1024)
+            .spawn(move || {
+                [SearchMode::IterativeDeepening, SearchMode::BranchAndBound]
+                    .iter()
+                    .map(|&mode| graph.search(first, |v| v == last, |_| 1, 1000, mode))
+                    .map(|found| found.map(|(path, cost)| (path.len(), cost)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(found, vec![Some((600, 599)); 2]);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{BTreeSet, HashSet};
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Connected components of the undirected projection once `blocked` is
+    /// removed.
+    fn components_avoiding(&self, blocked: &HashSet<VertexIndex>) -> Vec<Vec<VertexIndex>> {
+        let mut seen: HashSet<VertexIndex> = HashSet::new();
+        let mut components = Vec::new();
+        for (root, _) in self.nodes.iter() {
+            if blocked.contains(&root) || !seen.insert(root) {
+                continue;
+            }
+            let mut component = vec![root];
+            let mut head = 0;
+            while head < component.len() {
+                let vertex = component[head];
+                head += 1;
+                for next in self.undirected_neighbors(vertex) {
+                    if !blocked.contains(&next) && seen.insert(next) {
+                        component.push(next);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    fn component_boundary(&self, component: &[VertexIndex]) -> BTreeSet<VertexIndex> {
+        let members: HashSet<VertexIndex> = component.iter().cloned().collect();
+        component
+            .iter()
+            .flat_map(|&vertex| self.undirected_neighbors(vertex))
+            .filter(|other| !members.contains(other))
+            .collect()
+    }
+
+    /// Enumerates every minimal vertex separator of the undirected
+    /// projection (Berry, Bordat and Cogis). The number of separators can be
+    /// exponential, so this is meant for small graphs.
+    pub fn minimal_separators(&self) -> Vec<Vec<VertexIndex>> {
+        let mut found: BTreeSet<BTreeSet<VertexIndex>> = BTreeSet::new();
+        let mut pending = Vec::new();
+        let record = |blocked: HashSet<VertexIndex>,
+                      found: &mut BTreeSet<BTreeSet<VertexIndex>>,
+                      pending: &mut Vec<BTreeSet<VertexIndex>>| {
+            for component in self.components_avoiding(&blocked) {
+                let separator = self.component_boundary(&component);
+                if !separator.is_empty() && found.insert(separator.clone()) {
+                    pending.push(separator);
+                }
+            }
+        };
+
+        for (vertex, _) in self.nodes.iter() {
+            let closed = self
+                .undirected_neighbors(vertex)
+                .chain(std::iter::once(vertex))
+                .collect();
+            record(closed, &mut found, &mut pending);
+        }
+        while let Some(separator) = pending.pop() {
+            for &vertex in separator.iter() {
+                let blocked = separator
+                    .iter()
+                    .cloned()
+                    .chain(self.undirected_neighbors(vertex))
+                    .collect();
+                record(blocked, &mut found, &mut pending);
+            }
+        }
+
+        found
+            .into_iter()
+            .map(|separator| separator.into_iter().collect())
+            .collect()
+    }
+
+    /// Minimal separators of the undirected projection that disconnect the
+    /// non-adjacent vertices `a` and `b`, i.e. those for which both lie in
+    /// full components. Returns `None` if either vertex is missing.
+    pub fn minimal_separators_between(
+        &self,
+        a: VertexIndex,
+        b: VertexIndex,
+    ) -> Option<Vec<Vec<VertexIndex>>> {
+        if !self.nodes.contains(a) || !self.nodes.contains(b) {
+            return None;
+        }
+
+        let separators = self
+            .minimal_separators()
+            .into_iter()
+            .filter(|separator| {
+                let blocked: HashSet<VertexIndex> = separator.iter().cloned().collect();
+                if blocked.contains(&a) || blocked.contains(&b) {
+                    return false;
+                }
+                let components = self.components_avoiding(&blocked);
+                let full = |vertex: VertexIndex| {
+                    components
+                        .iter()
+                        .find(|component| component.contains(&vertex))
+                        .filter(|component| {
+                            self.component_boundary(component).len() == blocked.len()
+                        })
+                };
+                match (full(a), full(b)) {
+                    (Some(first), Some(second)) => first != second,
+                    _ => false,
+                }
+            })
+            .collect();
+        Some(separators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn minimal_separators() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(d, a);
+        graph.connect(c, e);
+
+        let mut cycle = [vec![a, c], vec![b, d]];
+        for separator in cycle.iter_mut() {
+            separator.sort();
+        }
+        let all = graph.minimal_separators();
+        assert_eq!(all.len(), 3);
+        assert!(all.contains(&vec![c]));
+        assert!(cycle.iter().all(|separator| all.contains(separator)));
+
+        assert_eq!(
+            graph.minimal_separators_between(a, e),
+            Some(vec![cycle[1].clone(), vec![c]])
+        );
+        assert_eq!(
+            graph.minimal_separators_between(b, d),
+            Some(vec![cycle[0].clone()])
+        );
+        assert_eq!(graph.minimal_separators_between(a, 42), None);
+    }
+}
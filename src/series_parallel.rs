@@ -0,0 +1,207 @@
+use crate::{EdgeIndex, Graph, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Construction tree of a two-terminal series-parallel graph. Leaves are the
+/// edges of the graph.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SpTree {
+    Edge(EdgeIndex),
+    Series(Box<SpTree>, Box<SpTree>),
+    Parallel(Box<SpTree>, Box<SpTree>),
+}
+
+impl SpTree {
+    /// Evaluates the tree bottom-up: `edge` values the leaves and `series` /
+    /// `parallel` combine the values of the two halves of each composition.
+    pub fn fold<T, E, S, P>(&self, mut edge: E, mut series: S, mut parallel: P) -> T
+    where
+        E: FnMut(EdgeIndex) -> T,
+        S: FnMut(T, T) -> T,
+        P: FnMut(T, T) -> T,
+    {
+        let mut pending = vec![(self, false)];
+        let mut values = Vec::new();
+        while let Some((tree, expanded)) = pending.pop() {
+            match tree {
+                SpTree::Edge(e) => values.push(edge(*e)),
+                SpTree::Series(a, b) | SpTree::Parallel(a, b) if !expanded => {
+                    pending.push((tree, true));
+                    pending.push((b, false));
+                    pending.push((a, false));
+                }
+                SpTree::Series(_, _) | SpTree::Parallel(_, _) => {
+                    let second = values.pop().unwrap();
+                    let first = values.pop().unwrap();
+                    values.push(match tree {
+                        SpTree::Series(_, _) => series(first, second),
+                        _ => parallel(first, second),
+                    });
+                }
+            }
+        }
+        values.pop().unwrap()
+    }
+}
+
+#[derive(Default)]
+struct Reducer {
+    edges: HashMap<usize, (VertexIndex, VertexIndex, SpTree)>,
+    pairs: HashMap<(VertexIndex, VertexIndex), usize>,
+    preset: HashMap<VertexIndex, HashSet<usize>>,
+    posset: HashMap<VertexIndex, HashSet<usize>>,
+    next: usize,
+}
+
+impl Reducer {
+    fn add(&mut self, src: VertexIndex, dst: VertexIndex, tree: SpTree) {
+        if let Some(&id) = self.pairs.get(&(src, dst)) {
+            let (_, _, old) = self.edges.remove(&id).unwrap();
+            let tree = SpTree::Parallel(Box::new(old), Box::new(tree));
+            self.edges.insert(id, (src, dst, tree));
+            return;
+        }
+        let id = self.next;
+        self.next += 1;
+        self.edges.insert(id, (src, dst, tree));
+        self.pairs.insert((src, dst), id);
+        self.posset.entry(src).or_default().insert(id);
+        self.preset.entry(dst).or_default().insert(id);
+    }
+
+    fn take(&mut self, id: usize) -> (VertexIndex, VertexIndex, SpTree) {
+        let (src, dst, tree) = self.edges.remove(&id).unwrap();
+        self.pairs.remove(&(src, dst));
+        self.posset.get_mut(&src).unwrap().remove(&id);
+        self.preset.get_mut(&dst).unwrap().remove(&id);
+        (src, dst, tree)
+    }
+
+    fn degree(set: &HashMap<VertexIndex, HashSet<usize>>, vertex: VertexIndex) -> usize {
+        set.get(&vertex).map_or(0, |ids| ids.len())
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Decomposes the graph into series and parallel compositions of its
+    /// edges. The graph must have a single trunk (source) and a single leaf
+    /// (sink); `None` is returned if it is not two-terminal series-parallel.
+    pub fn series_parallel_decomposition(&self) -> Option<SpTree> {
+        if self.trunks.len() != 1 || self.leaves.len() != 1 {
+            return None;
+        }
+        let source = *self.trunks.iter().next().unwrap();
+        let sink = *self.leaves.iter().next().unwrap();
+
+        let mut reducer = Reducer::default();
+        for (src, node) in self.nodes.iter() {
+            for &dst in node.posset.iter() {
+                reducer.add(src, dst, SpTree::Edge((src, dst)));
+            }
+        }
+
+        let mut worklist: Vec<VertexIndex> = self
+            .nodes
+            .iter()
+            .map(|(vertex, _)| vertex)
+            .filter(|&vertex| vertex != source && vertex != sink)
+            .collect();
+        while let Some(vertex) = worklist.pop() {
+            if Reducer::degree(&reducer.preset, vertex) != 1
+                || Reducer::degree(&reducer.posset, vertex) != 1
+            {
+                continue;
+            }
+            let incoming = *reducer.preset[&vertex].iter().next().unwrap();
+            let outgoing = *reducer.posset[&vertex].iter().next().unwrap();
+            let (src, _, head) = reducer.take(incoming);
+            let (_, dst, tail) = reducer.take(outgoing);
+            if src == dst {
+                return None;
+            }
+            reducer.add(src, dst, SpTree::Series(Box::new(head), Box::new(tail)));
+            worklist.extend(
+                vec![src, dst]
+                    .into_iter()
+                    .filter(|&v| v != source && v != sink),
+            );
+        }
+
+        if reducer.edges.len() != 1 {
+            return None;
+        }
+        let (_, (src, dst, tree)) = reducer.edges.drain().next().unwrap();
+        if (src, dst) == (source, sink) {
+            Some(tree)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn is_series_parallel(&self) -> bool {
+        self.series_parallel_decomposition().is_some()
+    }
+
+    /// Exact probability that the sink is reachable from the source when
+    /// each edge works independently with the given probability. `None` if
+    /// the graph is not two-terminal series-parallel.
+    #[inline]
+    pub fn two_terminal_reliability<F>(&self, probability: F) -> Option<f64>
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        Some(self.series_parallel_decomposition()?.fold(
+            probability,
+            |a, b| a * b,
+            |a, b| 1.0 - (1.0 - a) * (1.0 - b),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn edges(tree: &SpTree) -> usize {
+        match tree {
+            SpTree::Edge(_) => 1,
+            SpTree::Series(a, b) | SpTree::Parallel(a, b) => edges(a) + edges(b),
+        }
+    }
+
+    #[test]
+    fn series_parallel() {
+        let mut graph = Graph::new();
+        let s = graph.insert("s");
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let t = graph.insert("t");
+        graph.connect(s, a);
+        graph.connect(a, t);
+        graph.connect(s, b);
+        graph.connect(b, t);
+        graph.connect(s, t);
+
+        let tree = graph.series_parallel_decomposition().unwrap();
+        assert_eq!(edges(&tree), 5);
+        assert!(matches!(tree, SpTree::Parallel(_, _)));
+
+        let reliability = graph.two_terminal_reliability(|_| 0.5).unwrap();
+        assert!((reliability - (1.0 - 0.75 * 0.75 * 0.5)).abs() < 1e-12);
+        let delay = tree.fold(
+            |(src, _)| if src == s { 2 } else { 1 },
+            |a, b| a + b,
+            u32::max,
+        );
+        assert_eq!(delay, 3);
+
+        graph.connect(a, b);
+        assert!(!graph.is_series_parallel());
+        assert_eq!(graph.two_terminal_reliability(|_| 0.5), None);
+        graph.disconnect((a, b));
+        graph.connect(b, a);
+        graph.connect(a, b);
+        assert!(!graph.is_series_parallel());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::Graph;
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Whether every vertex is reached from a single root along exactly one
+    /// path, following edges forward when `forward` and backwards otherwise.
+    fn is_arborescence(&self, forward: bool) -> bool {
+        let roots = if forward { &self.trunks } else { &self.leaves };
+        let root = match roots.iter().next() {
+            Some(&root) if roots.len() == 1 => root,
+            _ => return false,
+        };
+        let mut reached = 1;
+        let mut stack = vec![root];
+        while let Some(vertex) = stack.pop() {
+            let node = &self.nodes[vertex];
+            let (next, back) = if forward {
+                (&node.posset, &node.preset)
+            } else {
+                (&node.preset, &node.posset)
+            };
+            if vertex != root && back.len() != 1 {
+                return false;
+            }
+            reached += next.len();
+            stack.extend(next.iter().cloned());
+        }
+        reached == self.nodes.len()
+    }
+
+    /// Whether the graph is a rooted tree with every edge pointing away from
+    /// the root. The empty graph is not.
+    #[inline]
+    pub fn is_out_tree(&self) -> bool {
+        self.is_arborescence(true)
+    }
+
+    /// Whether the graph is a rooted tree with every edge pointing towards
+    /// the root. The empty graph is not.
+    #[inline]
+    pub fn is_in_tree(&self) -> bool {
+        self.is_arborescence(false)
+    }
+
+    /// Whether the graph is a single directed path through every vertex.
+    pub fn is_linear_chain(&self) -> bool {
+        self.nodes.iter().all(|(_, node)| node.posset.len() <= 1) && self.is_out_tree()
+    }
+
+    /// Size of the largest set of pairwise unreachable vertices, the number
+    /// of chains needed to cover the graph. `None` if the graph is cyclic.
+    #[inline]
+    pub fn dag_width(&self) -> Option<usize> {
+        self.maximal_antichain().map(|antichain| antichain.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn shapes() {
+        let mut graph = Graph::new();
+        assert!(!graph.is_out_tree() && !graph.is_linear_chain());
+        let v: Vec<VertexIndex> = (0..4).map(|i| graph.insert(i)).collect();
+        graph.connect(v[0], v[1]);
+        graph.connect(v[1], v[2]);
+        graph.connect(v[2], v[3]);
+        // A path is a tree rooted at either end.
+        assert!(graph.is_out_tree() && graph.is_in_tree() && graph.is_linear_chain());
+        assert_eq!(graph.dag_width(), Some(1));
+
+        graph.disconnect((v[2], v[3]));
+        graph.connect(v[1], v[3]);
+        assert!(graph.is_out_tree() && !graph.is_in_tree() && !graph.is_linear_chain());
+        assert_eq!(graph.dag_width(), Some(2));
+
+        graph.connect(v[2], v[3]);
+        assert!(!graph.is_out_tree());
+        graph.disconnect((v[1], v[3]));
+        graph.disconnect((v[0], v[1]));
+        graph.connect(v[3], v[1]);
+        // A cycle detached from the root covers the vertex count but is not
+        // reached from it.
+        assert!(!graph.is_out_tree());
+        assert_eq!(graph.dag_width(), None);
+
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..4).map(|i| graph.insert(i)).collect();
+        graph.connect(v[1], v[0]);
+        graph.connect(v[2], v[0]);
+        graph.connect(v[3], v[2]);
+        assert!(graph.is_in_tree() && !graph.is_out_tree());
+        graph.connect(v[0], v[0]);
+        assert!(!graph.is_in_tree());
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{BitSet, EdgeIndex, Graph, VertexIndex};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Edges whose endpoints are also joined by a longer path.
+    pub(crate) fn redundant_edges(&self) -> Vec<EdgeIndex> {
+        let mut redundant = Vec::new();
+        for (src, node) in self.nodes.iter() {
+            let mut reached: HashSet<VertexIndex> = HashSet::new();
+            let mut stack: Vec<VertexIndex> = node
+                .posset
+                .iter()
+                .flat_map(|&succ| self.nodes[succ].posset.iter().cloned())
+                .collect();
+            while let Some(vertex) = stack.pop() {
+                if reached.insert(vertex) {
+                    stack.extend(self.nodes[vertex].posset.iter().cloned());
+                }
+            }
+            redundant.extend(
+                node.posset
+                    .iter()
+                    .filter(|dst| reached.contains(dst))
+                    .map(|&dst| (src, dst)),
+            );
+        }
+        redundant
+    }
+
+    /// Removes every edge implied by a longer path, except protected ones.
+    /// Returns the number of edges removed, or `None` if the graph is cyclic.
+    pub fn transitive_reduction(&mut self) -> Option<usize> {
+        let _span = trace_span!("transitive_reduction", vertices = self.nodes.len());
+        self.topological_order()?;
+
+        let redundant = self.redundant_edges();
+        Some(
+            redundant
+                .into_iter()
+                .filter(|&edge| self.disconnect(edge))
+                .count(),
+        )
+    }
+
+    /// Whether `src` and `dst` stay weakly connected without edge
+    /// `(src, dst)`.
+    fn has_detour(&self, src: VertexIndex, dst: VertexIndex) -> bool {
+        let mut seen = BitSet::default();
+        seen.grow(self.nodes.capacity());
+        seen.insert(src);
+        let mut stack = vec![src];
+        while let Some(vertex) = stack.pop() {
+            for next in self.undirected_neighbors(vertex) {
+                if vertex == src && next == dst && !self.nodes[dst].posset.contains(&src) {
+                    continue;
+                }
+                if next == dst {
+                    return true;
+                }
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Extracts a backbone by removing the lowest scoring edges until only
+    /// `keep_fraction` of them remain. `score` can be any closure, such as a
+    /// lookup into `edge_betweenness`. With `keep_connected`, edges whose
+    /// removal would split a weakly connected component are kept, as are
+    /// protected edges; either may leave more than the requested fraction.
+    /// Returns the number of edges removed.
+    pub fn prune_edges_by<F>(&mut self, score: F, keep_fraction: f64, keep_connected: bool) -> usize
+    where
+        F: Fn(EdgeIndex) -> f64,
+    {
+        let _span = trace_span!("prune_edges_by", keep_fraction);
+        let mut edges: Vec<(f64, EdgeIndex)> = self
+            .nodes
+            .iter()
+            .flat_map(|(src, node)| node.posset.iter().map(move |&dst| (src, dst)))
+            .map(|edge| (score(edge), edge))
+            .collect();
+        edges.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        let keep = (edges.len() as f64 * keep_fraction.clamp(0.0, 1.0)).ceil() as usize;
+        let target = edges.len() - keep;
+
+        let mut removed = 0;
+        for (_, (src, dst)) in edges {
+            if removed == target {
+                break;
+            }
+            if self.protected.contains(&(src, dst)) {
+                continue;
+            }
+            if keep_connected && src != dst && !self.has_detour(src, dst) {
+                continue;
+            }
+            if self.disconnect((src, dst)) {
+                removed += 1;
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn transitive_reduction() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, d);
+        graph.connect(a, c);
+        graph.connect(a, d);
+        graph.connect(b, d);
+        graph.protect_edge((a, d));
+
+        assert_eq!(graph.transitive_reduction(), Some(2));
+        let a_pos: HashSet<VertexIndex> = graph.posset(a).unwrap().collect();
+        let b_pos: HashSet<VertexIndex> = graph.posset(b).unwrap().collect();
+        assert_eq!(a_pos, vec![b, d].into_iter().collect());
+        assert_eq!(b_pos, vec![c].into_iter().collect());
+
+        graph.connect(d, a);
+        assert_eq!(graph.transitive_reduction(), None);
+    }
+
+    #[test]
+    fn prune_edges_by() {
+        // Two triangles joined by a single bridge from c to d.
+        let mut graph = Graph::new();
+        let v: Vec<VertexIndex> = (0..6).map(|i| graph.insert(i)).collect();
+        for &(src, dst) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)].iter() {
+            graph.connect(v[src], v[dst]);
+        }
+
+        let betweenness = graph.edge_betweenness();
+        assert_eq!(betweenness[&(v[2], v[3])], 9.0);
+        assert_eq!(betweenness[&(v[0], v[1])], 6.0);
+        assert_eq!(betweenness[&(v[5], v[3])], 3.0);
+
+        let mut pruned = graph.clone();
+        assert_eq!(
+            pruned.prune_edges_by(|edge| betweenness[&edge], 0.5, false),
+            3
+        );
+        assert_eq!(pruned.outdegree(v[2]), Some(1));
+        assert_eq!(pruned.outdegree(v[5]), Some(0));
+
+        let removed = graph.prune_edges_by(|edge| -betweenness[&edge], 0.0, true);
+        assert_eq!(removed, 2);
+        assert_eq!(graph.posset(v[2]).unwrap().sorted(), vec![v[0], v[3]]);
+        assert!(v.iter().all(|&x| graph.same_component(v[0], x) == Some(true)));
+    }
+}
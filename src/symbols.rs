@@ -0,0 +1,132 @@
+use crate::{Graph, LabelIter, VertexIndex, VertexIter};
+use std::hash::Hash;
+
+/// Adapter over an external interner so that graphs can be labeled with
+/// compact symbols while still being queried by name.
+pub trait SymbolTable<V> {
+    type Name: ?Sized;
+
+    /// Symbol already assigned to `name`, without interning it.
+    fn lookup(&self, name: &Self::Name) -> Option<V>;
+
+    fn intern(&mut self, name: &Self::Name) -> V;
+
+    fn resolve(&self, symbol: &V) -> Option<&Self::Name>;
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    #[inline]
+    pub fn insert_named<T>(&mut self, table: &mut T, name: &T::Name) -> VertexIndex
+    where
+        T: SymbolTable<V>,
+    {
+        self.insert(table.intern(name))
+    }
+
+    #[inline]
+    pub fn append_named_label<T>(
+        &mut self,
+        table: &mut T,
+        vertex: VertexIndex,
+        name: &T::Name,
+    ) -> bool
+    where
+        T: SymbolTable<V>,
+    {
+        self.nodes.contains(vertex) && self.append_label(vertex, table.intern(name))
+    }
+
+    #[inline]
+    pub fn get_named<'a, T>(&'a self, table: &T, name: &T::Name) -> Option<VertexIter<'a>>
+    where
+        T: SymbolTable<V>,
+    {
+        self.get(&table.lookup(name)?)
+    }
+
+    #[inline]
+    pub fn label_names<'a, T>(
+        &'a self,
+        table: &'a T,
+        vertex: VertexIndex,
+    ) -> Option<NameIter<'a, V, T>>
+    where
+        T: SymbolTable<V>,
+    {
+        Some(NameIter {
+            table,
+            labels: self.labels(vertex)?,
+        })
+    }
+}
+
+pub struct NameIter<'a, V: 'a, T: SymbolTable<V>> {
+    table: &'a T,
+    labels: LabelIter<'a, V>,
+}
+
+impl<'a, V, T: SymbolTable<V>> Iterator for NameIter<'a, V, T> {
+    type Item = &'a T::Name;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let table = self.table;
+        self.labels.by_ref().find_map(|label| table.resolve(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct Interner {
+        names: Vec<String>,
+        ids: HashMap<String, u32>,
+    }
+
+    impl SymbolTable<u32> for Interner {
+        type Name = str;
+
+        fn lookup(&self, name: &str) -> Option<u32> {
+            self.ids.get(name).cloned()
+        }
+
+        fn intern(&mut self, name: &str) -> u32 {
+            if let Some(&id) = self.ids.get(name) {
+                return id;
+            }
+            let id = self.names.len() as u32;
+            self.names.push(name.to_string());
+            self.ids.insert(name.to_string(), id);
+            id
+        }
+
+        fn resolve(&self, symbol: &u32) -> Option<&str> {
+            self.names.get(*symbol as usize).map(|name| name.as_str())
+        }
+    }
+
+    #[test]
+    fn external_symbols() {
+        let mut table = Interner::default();
+        let mut graph = Graph::new();
+        let alu = graph.insert_named(&mut table, "alu");
+        let fpu = graph.insert_named(&mut table, "fpu");
+        assert!(graph.append_named_label(&mut table, alu, "adder"));
+        assert!(!graph.append_named_label(&mut table, 42, "ghost"));
+
+        let found: Vec<VertexIndex> = graph.get_named(&table, "adder").unwrap().collect();
+        assert_eq!(found, vec![alu]);
+        assert!(graph.get_named(&table, "missing").is_none());
+
+        let mut names: Vec<&str> = graph.label_names(&table, alu).unwrap().collect();
+        names.sort();
+        assert_eq!(names, vec!["adder", "alu"]);
+        assert_eq!(
+            graph.label_names(&table, fpu).unwrap().collect::<Vec<_>>(),
+            vec!["fpu"]
+        );
+    }
+}
@@ -0,0 +1,27 @@
+// Instrumentation hooks compiled away unless the `tracing` feature is on.
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        tracing::trace!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        tracing::debug_span!($($arg)*).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
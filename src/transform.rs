@@ -0,0 +1,216 @@
+use crate::Graph;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// What a pass reports back after running.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PassReport {
+    /// Number of edits the pass made; zero means it left the graph as is.
+    pub changes: usize,
+}
+
+/// Statistics gathered for one pass of a pipeline run.
+#[derive(Clone, Debug)]
+pub struct PassStats {
+    pub name: String,
+    pub report: PassReport,
+    pub vertices: usize,
+    pub edges: usize,
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransformError {
+    /// A pass returned an error.
+    Failed { pass: String, message: String },
+    /// Validation rejected the graph after `pass`, or before the first pass
+    /// if `pass` is `None`.
+    Invalid {
+        pass: Option<String>,
+        message: String,
+    },
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Failed { pass, message } => {
+                write!(f, "pass {} failed: {}", pass, message)
+            }
+            TransformError::Invalid {
+                pass: Some(pass),
+                message,
+            } => write!(f, "invalid graph after pass {}: {}", pass, message),
+            TransformError::Invalid {
+                pass: None,
+                message,
+            } => write!(f, "invalid input graph: {}", message),
+        }
+    }
+}
+
+impl Error for TransformError {}
+
+type Pass<'a, V> = Box<dyn Fn(&mut Graph<V>) -> Result<PassReport, String> + 'a>;
+type Validator<'a, V> = Box<dyn Fn(&Graph<V>) -> Result<(), String> + 'a>;
+type Dump<'a, V> = Box<dyn FnMut(&str, &Graph<V>) + 'a>;
+
+/// Ordered list of named graph passes, run in place with optional
+/// validation and dumping between passes. A failing pass or validation
+/// stops the run and leaves the graph as the last pass left it.
+pub struct Pipeline<'a, V: Hash + Eq + Clone> {
+    passes: Vec<(String, Pass<'a, V>)>,
+    validator: Option<Validator<'a, V>>,
+    dump: Option<Dump<'a, V>>,
+}
+
+impl<'a, V: Hash + Eq + Clone> Default for Pipeline<'a, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, V: Hash + Eq + Clone> Pipeline<'a, V> {
+    #[inline]
+    pub fn new() -> Self {
+        Pipeline {
+            passes: Vec::new(),
+            validator: None,
+            dump: None,
+        }
+    }
+
+    #[inline]
+    pub fn add_pass<F>(&mut self, name: &str, pass: F)
+    where
+        F: Fn(&mut Graph<V>) -> Result<PassReport, String> + 'a,
+    {
+        self.passes.push((name.to_string(), Box::new(pass)));
+    }
+
+    /// Checks the graph before the first pass and after every pass.
+    #[inline]
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&Graph<V>) -> Result<(), String> + 'a,
+    {
+        self.validator = Some(Box::new(validator));
+    }
+
+    /// Called with the pass name and the resulting graph after every pass.
+    #[inline]
+    pub fn set_dump<F>(&mut self, dump: F)
+    where
+        F: FnMut(&str, &Graph<V>) + 'a,
+    {
+        self.dump = Some(Box::new(dump));
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    pub fn run(&mut self, graph: &mut Graph<V>) -> Result<Vec<PassStats>, TransformError> {
+        let _span = trace_span!("pipeline", passes = self.passes.len());
+        if let Some(validator) = self.validator.as_ref() {
+            validator(graph).map_err(|message| TransformError::Invalid {
+                pass: None,
+                message,
+            })?;
+        }
+
+        let mut stats = Vec::with_capacity(self.passes.len());
+        for (name, pass) in self.passes.iter() {
+            let start = Instant::now();
+            let report = pass(graph).map_err(|message| TransformError::Failed {
+                pass: name.clone(),
+                message,
+            })?;
+            let elapsed = start.elapsed();
+            trace_event!(pass = name.as_str(), changes = report.changes, "pass");
+
+            if let Some(validator) = self.validator.as_ref() {
+                validator(graph).map_err(|message| TransformError::Invalid {
+                    pass: Some(name.clone()),
+                    message,
+                })?;
+            }
+            if let Some(dump) = self.dump.as_mut() {
+                dump(name, graph);
+            }
+            stats.push(PassStats {
+                name: name.clone(),
+                report,
+                vertices: graph.nodes.len(),
+                edges: graph.nodes.iter().map(|(_, node)| node.posset.len()).sum(),
+                elapsed,
+            });
+        }
+
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn pipeline() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+
+        let mut dumped = Vec::new();
+        {
+            let mut pipeline = Pipeline::new();
+            pipeline.add_pass("reduce", |graph: &mut Graph<&str>| {
+                let changes = graph.transitive_reduction().ok_or("cyclic")?;
+                Ok(PassReport { changes })
+            });
+            pipeline.add_pass("noop", |_: &mut Graph<&str>| Ok(PassReport::default()));
+            pipeline.set_validator(|graph| {
+                graph
+                    .topological_order()
+                    .map(|_| ())
+                    .ok_or_else(|| "cyclic".to_string())
+            });
+            pipeline.set_dump(|name, graph| dumped.push((name.to_string(), graph.outdegree(a))));
+            assert_eq!(pipeline.len(), 2);
+
+            let stats = pipeline.run(&mut graph).unwrap();
+            assert_eq!(stats[0].report.changes, 1);
+            assert_eq!(stats[1].edges, 2);
+            assert_eq!(stats[1].vertices, 3);
+
+            graph.connect(c, a);
+            assert_eq!(
+                pipeline.run(&mut graph).unwrap_err(),
+                TransformError::Invalid {
+                    pass: None,
+                    message: "cyclic".to_string()
+                }
+            );
+        }
+        assert_eq!(
+            dumped,
+            vec![
+                ("reduce".to_string(), Some(1)),
+                ("noop".to_string(), Some(1))
+            ]
+        );
+    }
+}
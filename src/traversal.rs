@@ -0,0 +1,607 @@
+use crate::{BitSet, EdgeIndex, Graph, VertexIndex};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+/// Scratch space shared by repeated traversals so that hot loops do not
+/// reallocate visited sets and queues on every call.
+#[derive(Default, Clone, Debug)]
+pub struct TraversalContext {
+    visited: BitSet,
+    queue: VecDeque<VertexIndex>,
+    stack: Vec<VertexIndex>,
+}
+
+impl TraversalContext {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn reset(&mut self, capacity: usize) {
+        self.visited.clear();
+        self.visited.grow(capacity);
+        self.queue.clear();
+        self.stack.clear();
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    pub fn bfs_with<F>(&self, ctx: &mut TraversalContext, start: VertexIndex, mut visit: F) -> bool
+    where
+        F: FnMut(VertexIndex),
+    {
+        if !self.nodes.contains(start) {
+            return false;
+        }
+
+        ctx.reset(self.nodes.capacity());
+        ctx.visited.insert(start);
+        ctx.queue.push_back(start);
+        while let Some(vertex) = ctx.queue.pop_front() {
+            visit(vertex);
+            for &dst in self.nodes[vertex].posset.iter() {
+                if ctx.visited.insert(dst) {
+                    ctx.queue.push_back(dst);
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn dfs_with<F>(&self, ctx: &mut TraversalContext, start: VertexIndex, mut visit: F) -> bool
+    where
+        F: FnMut(VertexIndex),
+    {
+        if !self.nodes.contains(start) {
+            return false;
+        }
+
+        ctx.reset(self.nodes.capacity());
+        ctx.stack.push(start);
+        while let Some(vertex) = ctx.stack.pop() {
+            if !ctx.visited.insert(vertex) {
+                continue;
+            }
+            visit(vertex);
+            for &dst in self.nodes[vertex].posset.iter() {
+                if !ctx.visited.contains(dst) {
+                    ctx.stack.push(dst);
+                }
+            }
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn bfs(&self, start: VertexIndex) -> Option<Vec<VertexIndex>> {
+        let mut order = Vec::new();
+        if self.bfs_with(&mut TraversalContext::new(), start, |v| order.push(v)) {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn dfs(&self, start: VertexIndex) -> Option<Vec<VertexIndex>> {
+        let mut order = Vec::new();
+        if self.dfs_with(&mut TraversalContext::new(), start, |v| order.push(v)) {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    pub fn postorder_with(
+        &self,
+        ctx: &mut TraversalContext,
+        entry: VertexIndex,
+    ) -> Option<Vec<VertexIndex>> {
+        let node = self.nodes.get(entry)?;
+        let mut order = Vec::new();
+        let mut stack = vec![(entry, node.posset.iter())];
+        ctx.reset(self.nodes.capacity());
+        ctx.visited.insert(entry);
+
+        while let Some((vertex, succs)) = stack.last_mut() {
+            match succs.find(|&&dst| !ctx.visited.contains(dst)) {
+                Some(&dst) => {
+                    ctx.visited.insert(dst);
+                    stack.push((dst, self.nodes[dst].posset.iter()));
+                }
+                None => {
+                    order.push(*vertex);
+                    stack.pop();
+                }
+            }
+        }
+
+        Some(order)
+    }
+
+    #[inline]
+    pub fn postorder(&self, entry: VertexIndex) -> Option<Vec<VertexIndex>> {
+        self.postorder_with(&mut TraversalContext::new(), entry)
+    }
+
+    /// Whether `dst` can be reached from `src` by following edges, stopping
+    /// as soon as it is found. A vertex always reaches itself.
+    #[inline]
+    pub fn is_reachable_with(
+        &self,
+        ctx: &mut TraversalContext,
+        src: VertexIndex,
+        dst: VertexIndex,
+    ) -> Option<bool> {
+        self.is_reachable_filtered_with(ctx, src, dst, |_| true)
+    }
+
+    /// Like `is_reachable_with`, only following edges for which `filter`
+    /// holds, such as edges of a given kind.
+    pub fn is_reachable_filtered_with<F>(
+        &self,
+        ctx: &mut TraversalContext,
+        src: VertexIndex,
+        dst: VertexIndex,
+        filter: F,
+    ) -> Option<bool>
+    where
+        F: Fn(EdgeIndex) -> bool,
+    {
+        if !(self.nodes.contains(src) && self.nodes.contains(dst)) {
+            return None;
+        }
+
+        ctx.reset(self.nodes.capacity());
+        ctx.visited.insert(src);
+        ctx.stack.push(src);
+        while let Some(vertex) = ctx.stack.pop() {
+            if vertex == dst {
+                return Some(true);
+            }
+            for &next in self.nodes[vertex].posset.iter() {
+                if filter((vertex, next)) && ctx.visited.insert(next) {
+                    ctx.stack.push(next);
+                }
+            }
+        }
+
+        Some(false)
+    }
+
+    #[inline]
+    pub fn is_reachable(&self, src: VertexIndex, dst: VertexIndex) -> Option<bool> {
+        self.is_reachable_with(&mut TraversalContext::new(), src, dst)
+    }
+
+    #[inline]
+    pub fn is_reachable_filtered<F>(
+        &self,
+        src: VertexIndex,
+        dst: VertexIndex,
+        filter: F,
+    ) -> Option<bool>
+    where
+        F: Fn(EdgeIndex) -> bool,
+    {
+        self.is_reachable_filtered_with(&mut TraversalContext::new(), src, dst, filter)
+    }
+
+    pub fn topological_order(&self) -> Option<Vec<VertexIndex>> {
+        let _span = trace_span!("topological_order", vertices = self.nodes.len());
+        let mut indegree: HashMap<VertexIndex, usize> = self
+            .nodes
+            .iter()
+            .map(|(index, node)| (index, node.preset.len()))
+            .collect();
+        let mut order: Vec<VertexIndex> = self.trunks.iter().cloned().collect();
+        let mut head = 0;
+
+        while head < order.len() {
+            let vertex = order[head];
+            head += 1;
+            for &dst in self.nodes[vertex].posset.iter() {
+                let count = indegree.get_mut(&dst).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    order.push(dst);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the vertices of some directed cycle in edge order, the last
+    /// vertex leading back to the first, or `None` if the graph is acyclic.
+    pub fn find_cycle(&self) -> Option<Vec<VertexIndex>> {
+        let mut done = BitSet::default();
+        let mut on_path = BitSet::default();
+        done.grow(self.nodes.capacity());
+        on_path.grow(self.nodes.capacity());
+
+        for (root, node) in self.nodes.iter() {
+            if done.contains(root) {
+                continue;
+            }
+            let mut stack = vec![(root, node.posset.iter())];
+            on_path.insert(root);
+            while let Some((vertex, succs)) = stack.last_mut() {
+                let vertex = *vertex;
+                match succs.find(|&&dst| !done.contains(dst)) {
+                    Some(&dst) if on_path.contains(dst) => {
+                        let start = stack.iter().position(|&(v, _)| v == dst).unwrap();
+                        return Some(stack[start..].iter().map(|&(v, _)| v).collect());
+                    }
+                    Some(&dst) => {
+                        on_path.insert(dst);
+                        stack.push((dst, self.nodes[dst].posset.iter()));
+                    }
+                    None => {
+                        on_path.remove(vertex);
+                        done.insert(vertex);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Whether the graph has no directed cycle. Stops at the first back edge
+    /// found by `find_cycle`, and answers without a search when no vertex lacks
+    /// predecessors or none lacks successors, which guarantees a cycle.
+    pub fn is_dag(&self) -> bool {
+        if !self.nodes.is_empty() && (self.trunks.is_empty() || self.leaves.is_empty()) {
+            return false;
+        }
+        self.find_cycle().is_none()
+    }
+
+    /// Number of vertices reached from `start` following successors,
+    /// predecessors or both.
+    fn reach_count(&self, start: VertexIndex, forward: bool, backward: bool) -> usize {
+        let mut seen = BitSet::default();
+        seen.grow(self.nodes.capacity());
+        seen.insert(start);
+        let mut stack = vec![start];
+        let mut count = 1;
+        while let Some(vertex) = stack.pop() {
+            let node = &self.nodes[vertex];
+            let succs = node.posset.iter().filter(|_| forward);
+            let preds = node.preset.iter().filter(|_| backward);
+            for &next in succs.chain(preds) {
+                if seen.insert(next) {
+                    count += 1;
+                    stack.push(next);
+                }
+            }
+        }
+        count
+    }
+
+    /// Whether every vertex can reach every other ignoring edge directions.
+    /// The empty graph counts as connected.
+    pub fn is_weakly_connected(&self) -> bool {
+        match self.nodes.iter().next() {
+            None => true,
+            Some((start, _)) => self.reach_count(start, true, true) == self.nodes.len(),
+        }
+    }
+
+    /// Whether every vertex can reach every other along edge directions.
+    /// The empty graph counts as connected.
+    pub fn is_strongly_connected(&self) -> bool {
+        let start = match self.nodes.iter().next() {
+            None => return true,
+            Some((start, _)) => start,
+        };
+        if self.nodes.len() > 1 && !(self.trunks.is_empty() && self.leaves.is_empty()) {
+            return false;
+        }
+        self.reach_count(start, true, false) == self.nodes.len()
+            && self.reach_count(start, false, true) == self.nodes.len()
+    }
+
+    #[inline]
+    pub fn reverse_postorder(&self, entry: VertexIndex) -> Option<Vec<VertexIndex>> {
+        self.postorder(entry).map(|mut order| {
+            order.reverse();
+            order
+        })
+    }
+
+    /// Vertices at most `max_depth` edges away from `start`, following
+    /// successors if `forward` and predecessors otherwise, in breadth-first
+    /// order. `start` is left out.
+    fn within_depth(
+        &self,
+        start: VertexIndex,
+        max_depth: Option<usize>,
+        forward: bool,
+    ) -> Vec<VertexIndex> {
+        let mut seen = BitSet::default();
+        seen.grow(self.nodes.capacity());
+        seen.insert(start);
+        let mut found = Vec::new();
+        let mut level = vec![start];
+        let mut depth = 0;
+        while !level.is_empty() && max_depth.is_none_or(|max| depth < max) {
+            let mut next_level = Vec::new();
+            for vertex in level {
+                let node = &self.nodes[vertex];
+                let next = if forward { &node.posset } else { &node.preset };
+                for &next in next.iter() {
+                    if seen.insert(next) {
+                        next_level.push(next);
+                    }
+                }
+            }
+            found.extend_from_slice(&next_level);
+            level = next_level;
+            depth += 1;
+        }
+        found
+    }
+
+    /// Transitive predecessors of `vertex` up to `max_depth` levels back, or
+    /// all of them with `None`, in breadth-first order. `vertex` itself is
+    /// not included even if it lies on a cycle. `None` if the vertex is
+    /// missing.
+    #[inline]
+    pub fn ancestors<B>(&self, vertex: VertexIndex, max_depth: Option<usize>) -> Option<B>
+    where
+        B: FromIterator<VertexIndex>,
+    {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+        Some(
+            self.within_depth(vertex, max_depth, false)
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// Transitive successors of `vertex` up to `max_depth` levels ahead, the
+    /// counterpart of `ancestors`.
+    #[inline]
+    pub fn descendants<B>(&self, vertex: VertexIndex, max_depth: Option<usize>) -> Option<B>
+    where
+        B: FromIterator<VertexIndex>,
+    {
+        if !self.nodes.contains(vertex) {
+            return None;
+        }
+        Some(
+            self.within_depth(vertex, max_depth, true)
+                .into_iter()
+                .collect(),
+        )
+    }
+}
+
+/// Deduplicating work queue that always yields the pending vertex with the
+/// smallest priority first.
+pub struct Worklist<P: Ord, F: Fn(VertexIndex) -> P> {
+    priority: F,
+    heap: BinaryHeap<Reverse<(P, VertexIndex)>>,
+    queued: HashSet<VertexIndex>,
+}
+
+impl<P: Ord, F: Fn(VertexIndex) -> P> Worklist<P, F> {
+    #[inline]
+    pub fn new(priority: F) -> Self {
+        Worklist {
+            priority,
+            heap: BinaryHeap::new(),
+            queued: HashSet::new(),
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, vertex: VertexIndex) -> bool {
+        if self.queued.insert(vertex) {
+            self.heap.push(Reverse(((self.priority)(vertex), vertex)));
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<VertexIndex> {
+        let Reverse((_, vertex)) = self.heap.pop()?;
+        self.queued.remove(&vertex);
+        Some(vertex)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl<P: Ord, F: Fn(VertexIndex) -> P> Extend<VertexIndex> for Worklist<P, F> {
+    fn extend<I: IntoIterator<Item = VertexIndex>>(&mut self, iter: I) {
+        for vertex in iter {
+            self.push(vertex);
+        }
+    }
+}
+
+/// Builds a worklist ordered by position in `order`, typically the result of
+/// `Graph::reverse_postorder`. Vertices missing from `order` come last.
+pub fn ordered_worklist(order: &[VertexIndex]) -> Worklist<usize, impl Fn(VertexIndex) -> usize> {
+    let rank: HashMap<VertexIndex, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(pos, &vertex)| (vertex, pos))
+        .collect();
+    Worklist::new(move |vertex| rank.get(&vertex).cloned().unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn reverse_postorder() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(a, b);
+        graph.connect(a, c);
+        graph.connect(b, d);
+        graph.connect(c, d);
+        graph.connect(d, b);
+
+        let rpo = graph.reverse_postorder(a).unwrap();
+        let pos = |v| rpo.iter().position(|&x| x == v).unwrap();
+        assert_eq!(rpo.len(), 4);
+        assert_eq!(rpo[0], a);
+        assert!(pos(c) < pos(d));
+        assert!(!rpo.contains(&e));
+        assert_eq!(graph.reverse_postorder(42), None);
+    }
+
+    #[test]
+    fn reused_context() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(c, a);
+        graph.connect(d, c);
+
+        let mut ctx = TraversalContext::new();
+        let mut seen = Vec::new();
+        assert!(graph.bfs_with(&mut ctx, a, |v| seen.push(v)));
+        assert_eq!(seen, vec![a, b, c]);
+
+        seen.clear();
+        assert!(graph.dfs_with(&mut ctx, d, |v| seen.push(v)));
+        assert_eq!(seen, vec![d, c, a, b]);
+
+        assert!(!graph.bfs_with(&mut ctx, 42, |_| ()));
+        assert_eq!(graph.is_reachable_with(&mut ctx, a, c), Some(true));
+        assert_eq!(graph.is_reachable(a, d), Some(false));
+        assert_eq!(graph.is_reachable(d, d), Some(true));
+        assert_eq!(graph.is_reachable(a, 42), None);
+        assert_eq!(graph.dfs(b), Some(vec![b, c, a]));
+    }
+
+    #[test]
+    fn find_cycle() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        graph.connect(a, b);
+        graph.connect(b, c);
+        graph.connect(a, c);
+        graph.connect(c, d);
+        assert_eq!(graph.find_cycle(), None);
+
+        assert!(graph.is_dag());
+
+        graph.connect(d, b);
+        assert!(!graph.is_dag());
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle.len(), 3);
+        for (i, &vertex) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            assert!(graph.posset(vertex).unwrap().any(|v| v == next));
+        }
+
+        graph.connect(a, a);
+        assert_eq!(graph.find_cycle().map(|cycle| cycle.len() < 4), Some(true));
+        graph.connect(d, a);
+        assert!(!graph.is_dag());
+        assert!(Graph::<&str>::new().is_dag());
+    }
+
+    #[test]
+    fn connectivity() {
+        let mut graph = Graph::new();
+        assert!(graph.is_weakly_connected() && graph.is_strongly_connected());
+        let a = graph.insert("a");
+        assert!(graph.is_strongly_connected());
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        graph.connect(a, b);
+        assert!(!graph.is_weakly_connected());
+        graph.connect(c, b);
+        assert!(graph.is_weakly_connected());
+        assert!(!graph.is_strongly_connected());
+
+        graph.connect(b, a);
+        graph.connect(a, c);
+        assert!(graph.is_strongly_connected());
+        graph.disconnect((c, b));
+        assert!(!graph.is_strongly_connected());
+    }
+
+    #[test]
+    fn ancestors_and_descendants() {
+        let mut graph = Graph::new();
+        let tasks: Vec<VertexIndex> = (0..5).map(|i| graph.insert(i)).collect();
+        for pair in tasks.windows(2) {
+            graph.connect(pair[0], pair[1]);
+        }
+        let side = graph.insert(9);
+        graph.connect(side, tasks[3]);
+
+        let deps: Vec<VertexIndex> = graph.ancestors(tasks[4], Some(2)).unwrap();
+        assert_eq!(deps[0], tasks[3]);
+        let deps: HashSet<VertexIndex> = graph.ancestors(tasks[4], Some(2)).unwrap();
+        assert_eq!(deps, vec![tasks[3], tasks[2], side].into_iter().collect());
+        let all: HashSet<VertexIndex> = graph.ancestors(tasks[4], None).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(!all.contains(&tasks[4]));
+
+        graph.connect(tasks[4], tasks[0]);
+        let all: Vec<VertexIndex> = graph.ancestors(tasks[4], None).unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(graph.ancestors::<Vec<_>>(tasks[0], Some(0)), Some(vec![]));
+        assert_eq!(graph.ancestors::<Vec<_>>(42, None), None);
+
+        let fanout: HashSet<VertexIndex> = graph.descendants(tasks[2], Some(2)).unwrap();
+        assert_eq!(fanout, vec![tasks[3], tasks[4]].into_iter().collect());
+        let fanout: Vec<VertexIndex> = graph.descendants(side, None).unwrap();
+        assert_eq!(fanout[..2], [tasks[3], tasks[4]]);
+        assert_eq!(fanout.len(), 5);
+        assert_eq!(graph.descendants::<Vec<_>>(42, Some(1)), None);
+    }
+
+    #[test]
+    fn worklist_priority() {
+        let mut list = ordered_worklist(&[3, 1, 2]);
+        list.extend(vec![2, 1, 7, 3, 2]);
+        assert_eq!(list.len(), 4);
+        let order: Vec<VertexIndex> = std::iter::from_fn(|| list.pop()).collect();
+        assert_eq!(order, vec![3, 1, 2, 7]);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{Graph, VertexIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Rule used to pick the next vertex to eliminate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EliminationHeuristic {
+    /// The vertex with the fewest remaining neighbors.
+    MinDegree,
+    /// The vertex whose elimination adds the fewest fill edges.
+    MinFill,
+}
+
+/// Tree decomposition of the undirected projection. Bags are stored in
+/// elimination order, so every bag comes before its parent and iterating
+/// over `0..len()` visits children first.
+#[derive(Clone, Debug)]
+pub struct TreeDecomposition {
+    bags: Vec<Vec<VertexIndex>>,
+    parent: Vec<Option<usize>>,
+}
+
+impl TreeDecomposition {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bags.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bags.is_empty()
+    }
+
+    #[inline]
+    pub fn bag(&self, bag: usize) -> Option<&[VertexIndex]> {
+        self.bags.get(bag).map(|bag| bag.as_slice())
+    }
+
+    #[inline]
+    pub fn parent(&self, bag: usize) -> Option<usize> {
+        self.parent.get(bag).cloned().flatten()
+    }
+
+    #[inline]
+    pub fn root(&self) -> Option<usize> {
+        self.bags.len().checked_sub(1)
+    }
+
+    /// Size of the largest bag minus one.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.bags
+            .iter()
+            .map(|bag| bag.len().saturating_sub(1))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Builds a tree decomposition by greedily eliminating vertices of the
+    /// undirected projection. Its width is an upper bound on the treewidth.
+    pub fn tree_decomposition(&self, heuristic: EliminationHeuristic) -> TreeDecomposition {
+        let mut adjacent: HashMap<VertexIndex, HashSet<VertexIndex>> = self
+            .nodes
+            .iter()
+            .map(|(vertex, _)| (vertex, self.undirected_neighbors(vertex).collect()))
+            .collect();
+        let fill = |adjacent: &HashMap<VertexIndex, HashSet<VertexIndex>>, vertex| {
+            let neighbors = &adjacent[&vertex];
+            neighbors
+                .iter()
+                .map(|u| {
+                    neighbors
+                        .iter()
+                        .filter(|w| !adjacent[u].contains(w))
+                        .count()
+                })
+                .sum::<usize>()
+        };
+
+        let mut bags = Vec::with_capacity(adjacent.len());
+        let mut eliminated: HashMap<VertexIndex, usize> = HashMap::new();
+        let mut order = Vec::with_capacity(adjacent.len());
+        while !adjacent.is_empty() {
+            let vertex = *adjacent
+                .keys()
+                .min_by_key(|&&vertex| {
+                    let cost = match heuristic {
+                        EliminationHeuristic::MinDegree => adjacent[&vertex].len(),
+                        EliminationHeuristic::MinFill => fill(&adjacent, vertex),
+                    };
+                    (cost, vertex)
+                })
+                .unwrap();
+            let neighbors = adjacent.remove(&vertex).unwrap();
+            for &u in neighbors.iter() {
+                let entry = adjacent.get_mut(&u).unwrap();
+                entry.remove(&vertex);
+                entry.extend(neighbors.iter().filter(|&&w| w != u));
+            }
+            let mut bag: Vec<VertexIndex> = neighbors.into_iter().collect();
+            bag.push(vertex);
+            bag.sort_unstable();
+            eliminated.insert(vertex, bags.len());
+            order.push(vertex);
+            bags.push(bag);
+        }
+
+        // Each bag hangs below the bag of its earliest eliminated neighbor;
+        // bags without one join the root so the result stays a single tree.
+        let root = bags.len().checked_sub(1);
+        let parent = order
+            .iter()
+            .enumerate()
+            .map(|(index, vertex)| {
+                bags[index]
+                    .iter()
+                    .filter(|&other| other != vertex)
+                    .map(|other| eliminated[other])
+                    .min()
+                    .or(root.filter(|&root| root != index))
+            })
+            .collect();
+
+        TreeDecomposition { bags, parent }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn tree_decomposition() {
+        let mut graph = Graph::new();
+        let vertices: Vec<VertexIndex> = (0..6).map(|i| graph.insert(i)).collect();
+        for i in 0..6 {
+            graph.connect(vertices[i], vertices[(i + 1) % 6]);
+        }
+        let lonely = graph.insert(6);
+
+        for heuristic in [
+            EliminationHeuristic::MinDegree,
+            EliminationHeuristic::MinFill,
+        ] {
+            let decomposition = graph.tree_decomposition(heuristic);
+            assert_eq!(decomposition.len(), 7);
+            assert_eq!(decomposition.width(), 2);
+            let root = decomposition.root().unwrap();
+            assert_eq!(decomposition.parent(root), None);
+            for bag in 0..root {
+                assert!(decomposition.parent(bag).unwrap() > bag);
+            }
+            for i in 0..6 {
+                let (src, dst) = (vertices[i], vertices[(i + 1) % 6]);
+                assert!((0..7).any(|bag| {
+                    let bag = decomposition.bag(bag).unwrap();
+                    bag.contains(&src) && bag.contains(&dst)
+                }));
+            }
+            assert!((0..7).any(|bag| decomposition.bag(bag) == Some(&[lonely][..])));
+        }
+    }
+}
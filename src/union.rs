@@ -0,0 +1,122 @@
+use crate::{Graph, VertexIndex};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Copies every vertex and edge of `other` into `self` with its labels
+    /// passed through `label`, returning where each vertex of `other` went.
+    /// Label weights, pins, protected edges and edge kinds come along.
+    fn absorb<F>(&mut self, other: &Graph<V>, label: F) -> HashMap<VertexIndex, VertexIndex>
+    where
+        F: Fn(&V) -> V,
+    {
+        let mut map = HashMap::with_capacity(other.nodes.len());
+        for (vertex, node) in other.nodes.iter() {
+            let local = self.insert_unlabeled();
+            for alias in node.aliases.iter() {
+                self.append_label(local, label(alias));
+            }
+            if let Some(weights) = other.label_weights.get(&vertex) {
+                self.label_weights.insert(
+                    local,
+                    weights
+                        .iter()
+                        .map(|(alias, &weight)| (label(alias), weight))
+                        .collect(),
+                );
+            }
+            if other.pinned.contains(&vertex) {
+                self.pin_vertex(local);
+            }
+            map.insert(vertex, local);
+        }
+        for (src, node) in other.nodes.iter() {
+            for &dst in node.posset.iter() {
+                let edge = (map[&src], map[&dst]);
+                self.connect(edge.0, edge.1);
+                if other.protected.contains(&(src, dst)) {
+                    self.protect_edge(edge);
+                }
+                if let Some(&kind) = other.edge_kinds.get(&(src, dst)) {
+                    self.edge_kinds.insert(edge, kind);
+                }
+            }
+        }
+        map
+    }
+
+    /// Disjoint union of `a` and `b`: a new graph holding a copy of each,
+    /// `a` first, with no edges between them. Also returns, for each
+    /// operand, where its vertices went. Labels are kept as they are, so a
+    /// label used in both graphs ends up carried by vertices of both; see
+    /// `disjoint_union_with` to keep them apart.
+    #[inline]
+    pub fn disjoint_union(
+        a: &Graph<V>,
+        b: &Graph<V>,
+    ) -> (Graph<V>, [HashMap<VertexIndex, VertexIndex>; 2]) {
+        Self::disjoint_union_with(a, b, |_, label| label.clone())
+    }
+
+    /// Like `disjoint_union`, with every label of operand `i` (0 for `a`, 1
+    /// for `b`) replaced by `namespace(i, label)`, e.g. prefixed with the
+    /// file it came from so that aliases from different graphs never
+    /// collide.
+    pub fn disjoint_union_with<F>(
+        a: &Graph<V>,
+        b: &Graph<V>,
+        namespace: F,
+    ) -> (Graph<V>, [HashMap<VertexIndex, VertexIndex>; 2])
+    where
+        F: Fn(usize, &V) -> V,
+    {
+        let _span = trace_span!("disjoint_union", a = a.nodes.len(), b = b.nodes.len());
+        let mut graph = Graph::with_index_allocation(a.allocation);
+        let from_a = graph.absorb(a, |label| namespace(0, label));
+        let from_b = graph.absorb(b, |label| namespace(1, label));
+        (graph, [from_a, from_b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn disjoint_union() {
+        let mut a = Graph::new();
+        let top = a.insert("top".to_string());
+        let alu = a.insert("alu".to_string());
+        a.connect(top, alu);
+        a.protect_edge((top, alu));
+        let mut b = Graph::new();
+        let gone = b.insert("gone".to_string());
+        let top_b = b.insert("top".to_string());
+        let fpu = b.insert("fpu".to_string());
+        b.remove(gone);
+        b.connect(top_b, fpu);
+        b.connect(fpu, fpu);
+        b.append_vertex_label_weighted(fpu, "float".to_string(), 0.5);
+
+        let (union, [from_a, from_b]) = Graph::disjoint_union(&a, &b);
+        assert_eq!(union.count_labeled(&"top".to_string()), Some(2));
+        assert_eq!(from_a[&top], 0);
+        assert_eq!(union.is_protected((from_a[&top], from_a[&alu])), Some(true));
+        assert!(union.posset(from_b[&fpu]).unwrap().eq(vec![from_b[&fpu]]));
+        assert!(!from_b.contains_key(&gone));
+
+        let (union, [from_a, from_b]) =
+            Graph::disjoint_union_with(&a, &b, |i, label| format!("f{}.{}", i, label));
+        assert!(union.get("top").is_none());
+        assert_eq!(
+            union.get("f1.top").unwrap().collect::<Vec<_>>(),
+            vec![from_b[&top_b]]
+        );
+        assert_eq!(
+            union.label_weight(from_b[&fpu], &"f1.float".to_string()),
+            Some(0.5)
+        );
+        assert!(union.is_reachable(from_a[&top], from_a[&alu]).unwrap());
+        assert!(!union.is_reachable(from_a[&top], from_b[&fpu]).unwrap());
+    }
+}
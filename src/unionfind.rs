@@ -0,0 +1,243 @@
+use crate::{Graph, VertexIndex};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A weakly connected component copied out as a graph of its own.
+#[derive(Clone)]
+pub struct ComponentGraph<V: Eq + Hash + Clone> {
+    pub graph: Graph<V>,
+    /// Original index of every vertex of `graph`, indexed by its new one.
+    pub original: Vec<VertexIndex>,
+}
+
+impl<V: Eq + Hash + Clone> ComponentGraph<V> {
+    /// New index of the original vertex `vertex`, if it is in the component.
+    pub fn local(&self, vertex: VertexIndex) -> Option<VertexIndex> {
+        self.original.binary_search(&vertex).ok()
+    }
+}
+
+/// Disjoint sets with union by size and path halving.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UnionFind {
+    parent: Vec<VertexIndex>,
+    size: Vec<usize>,
+    pub dirty: bool,
+}
+
+impl UnionFind {
+    pub fn make_set(&mut self, vertex: VertexIndex) {
+        if vertex >= self.parent.len() {
+            self.parent.extend(self.parent.len()..=vertex);
+            self.size.resize(vertex + 1, 1);
+        }
+        self.parent[vertex] = vertex;
+        self.size[vertex] = 1;
+    }
+
+    pub fn find(&mut self, mut vertex: VertexIndex) -> VertexIndex {
+        while self.parent[vertex] != vertex {
+            self.parent[vertex] = self.parent[self.parent[vertex]];
+            vertex = self.parent[vertex];
+        }
+        vertex
+    }
+
+    #[inline]
+    pub fn set_size(&mut self, vertex: VertexIndex) -> usize {
+        let root = self.find(vertex);
+        self.size[root]
+    }
+
+    pub fn union(&mut self, one: VertexIndex, other: VertexIndex) {
+        let (mut one, mut other) = (self.find(one), self.find(other));
+        if one == other {
+            return;
+        }
+        if self.size[one] < self.size[other] {
+            std::mem::swap(&mut one, &mut other);
+        }
+        self.parent[other] = one;
+        self.size[one] += self.size[other];
+    }
+}
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    fn rebuild_components(&mut self) {
+        let mut sets = UnionFind::default();
+        for (vertex, _) in self.nodes.iter() {
+            sets.make_set(vertex);
+        }
+        for (src, node) in self.nodes.iter() {
+            for &dst in node.posset.iter() {
+                sets.union(src, dst);
+            }
+        }
+        self.components = Some(sets);
+    }
+
+    /// Starts maintaining weakly connected components incrementally. Edge and
+    /// vertex insertions update them in near-constant time; removals mark
+    /// them stale and the next query rebuilds them.
+    #[inline]
+    pub fn track_components(&mut self) {
+        if self.components.is_none() {
+            self.rebuild_components();
+        }
+    }
+
+    #[inline]
+    pub fn untrack_components(&mut self) {
+        self.components = None;
+    }
+
+    #[inline]
+    pub fn is_tracking_components(&self) -> bool {
+        self.components.is_some()
+    }
+
+    /// Whether `one` and `other` belong to the same weakly connected
+    /// component. Takes `&mut self` to compress paths and rebuild stale
+    /// state; starts tracking components if it was not enabled.
+    pub fn same_component(&mut self, one: VertexIndex, other: VertexIndex) -> Option<bool> {
+        if !(self.nodes.contains(one) && self.nodes.contains(other)) {
+            return None;
+        }
+        if self.components.as_ref().is_none_or(|sets| sets.dirty) {
+            self.rebuild_components();
+        }
+        let sets = self.components.as_mut().unwrap();
+        Some(sets.find(one) == sets.find(other))
+    }
+
+    /// Every weakly connected component as an independent graph, ordered by
+    /// smallest vertex, for processing components separately. Labels with
+    /// their weights, protected edges, edge kinds and pins are copied;
+    /// vertices are renumbered densely in their original order.
+    pub fn components_subgraphs(&self) -> impl Iterator<Item = ComponentGraph<V>> + '_ {
+        let mut sets = UnionFind::default();
+        for (vertex, _) in self.nodes.iter() {
+            sets.make_set(vertex);
+        }
+        for (src, node) in self.nodes.iter() {
+            for &dst in node.posset.iter() {
+                sets.union(src, dst);
+            }
+        }
+        let mut members: HashMap<VertexIndex, Vec<VertexIndex>> = HashMap::new();
+        let mut roots = Vec::new();
+        for (vertex, _) in self.nodes.iter() {
+            let root = sets.find(vertex);
+            if !members.contains_key(&root) {
+                roots.push(root);
+            }
+            members.entry(root).or_default().push(vertex);
+        }
+
+        roots.into_iter().map(move |root| {
+            let original = members.remove(&root).unwrap();
+            let mut graph = Graph::new();
+            for &vertex in original.iter() {
+                let local = graph.insert_unlabeled();
+                for label in self.nodes[vertex].aliases.iter() {
+                    graph.append_label(local, label.clone());
+                }
+                if let Some(weights) = self.label_weights.get(&vertex) {
+                    graph.label_weights.insert(local, weights.clone());
+                }
+                if self.pinned.contains(&vertex) {
+                    graph.pin_vertex(local);
+                }
+            }
+            let local = |vertex: VertexIndex| original.binary_search(&vertex).unwrap();
+            for (pos, &src) in original.iter().enumerate() {
+                for &dst in self.nodes[src].posset.iter() {
+                    graph.connect(pos, local(dst));
+                    if self.protected.contains(&(src, dst)) {
+                        graph.protect_edge((pos, local(dst)));
+                    }
+                    if let Some(&kind) = self.edge_kinds.get(&(src, dst)) {
+                        graph.edge_kinds.insert((pos, local(dst)), kind);
+                    }
+                }
+            }
+            ComponentGraph { graph, original }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn incremental_components() {
+        let mut graph = Graph::new();
+        graph.track_components();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        assert_eq!(graph.same_component(a, b), Some(false));
+
+        graph.connect(a, b);
+        graph.connect(c, b);
+        assert_eq!(graph.same_component(a, c), Some(true));
+
+        graph.disconnect((c, b));
+        assert_eq!(graph.same_component(a, c), Some(false));
+        assert_eq!(graph.same_component(a, b), Some(true));
+
+        let ac = graph.merge_vertices(vec![a, c]);
+        assert_eq!(graph.same_component(ac, b), Some(true));
+        assert_eq!(graph.same_component(a, b), None);
+
+        graph.untrack_components();
+        assert!(!graph.is_tracking_components());
+    }
+
+    #[test]
+    fn merge_with_fresh_index() {
+        let mut graph = Graph::with_index_allocation(IndexAllocation::NeverReuse);
+        graph.track_components();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let x = graph.insert("x");
+
+        let merged = graph.merge_vertices(vec![a, b]);
+        assert_eq!(merged, 3);
+        graph.connect(merged, x);
+        assert_eq!(graph.same_component(merged, x), Some(true));
+    }
+
+    #[test]
+    fn components_subgraphs() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        let d = graph.insert("d");
+        let e = graph.insert("e");
+        graph.connect(c, a);
+        graph.connect(b, d);
+        graph.connect(d, d);
+        graph.protect_edge((b, d));
+        graph.append_vertex_label_weighted(d, "x", 2.0);
+        graph.pin_vertex(e);
+        graph.remove(a);
+
+        let parts: Vec<ComponentGraph<&str>> = graph.components_subgraphs().collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].original, vec![b, d]);
+        assert_eq!(parts[1].original, vec![c]);
+        assert_eq!(parts[2].local(e), Some(0));
+        assert_eq!(parts[2].graph.is_pinned(0), Some(true));
+
+        let first = &parts[0].graph;
+        assert_eq!(parts[0].local(d), Some(1));
+        assert_eq!(first.posset(1).unwrap().sorted(), vec![1]);
+        assert_eq!(first.is_protected((0, 1)), Some(true));
+        assert_eq!(first.label_weight(1, &"x"), Some(2.0));
+        assert_eq!(first.label_weight(1, &"d"), Some(1.0));
+        assert_eq!(parts[1].graph.nodes.len(), 1);
+    }
+}
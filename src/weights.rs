@@ -0,0 +1,126 @@
+use crate::{Graph, VertexIndex};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Weight of a label attached without an explicit one.
+const DEFAULT_WEIGHT: f64 = 1.0;
+
+impl<V: Eq + Hash + Clone> Graph<V> {
+    /// Attaches `label` to `vertex` with a confidence `weight`, replacing the
+    /// weight if the label is already attached. Labels attached through
+    /// `append_label` weigh 1.
+    pub fn append_vertex_label_weighted(
+        &mut self,
+        vertex: VertexIndex,
+        label: V,
+        weight: f64,
+    ) -> bool {
+        if !self.append_label(vertex, label.clone()) {
+            return false;
+        }
+        self.label_weights
+            .entry(vertex)
+            .or_default()
+            .insert(label, weight);
+        true
+    }
+
+    /// Weight of `label` on `vertex`, or `None` if the vertex does not carry
+    /// it.
+    pub fn label_weight(&self, vertex: VertexIndex, label: &V) -> Option<f64> {
+        if !self.nodes.get(vertex)?.aliases.contains(label) {
+            return None;
+        }
+        Some(
+            self.label_weights
+                .get(&vertex)
+                .and_then(|weights| weights.get(label))
+                .cloned()
+                .unwrap_or(DEFAULT_WEIGHT),
+        )
+    }
+
+    /// The vertex carrying `label` with the highest weight, the lowest index
+    /// winning ties.
+    pub fn best_labeled(&self, label: &V) -> Option<VertexIndex> {
+        let mut best: Option<(f64, VertexIndex)> = None;
+        for &vertex in self.aliases.get(label)?.iter() {
+            let weight = self.label_weight(vertex, label).unwrap();
+            if best.is_none_or(|(top, index)| weight > top || weight == top && vertex < index) {
+                best = Some((weight, vertex));
+            }
+        }
+        best.map(|(_, vertex)| vertex)
+    }
+
+    pub(crate) fn forget_label_weight(&mut self, vertex: VertexIndex, label: &V) {
+        if let Some(weights) = self.label_weights.get_mut(&vertex) {
+            weights.remove(label);
+            if weights.is_empty() {
+                self.label_weights.remove(&vertex);
+            }
+        }
+    }
+
+    /// Drops the weights of `members` and returns those of the vertex that
+    /// replaces them, keeping the highest weight of each shared label.
+    pub(crate) fn merged_label_weights(&mut self, members: &[VertexIndex]) -> HashMap<V, f64> {
+        let mut merged = HashMap::new();
+        if !members
+            .iter()
+            .any(|vertex| self.label_weights.contains_key(vertex))
+        {
+            return merged;
+        }
+        for &vertex in members.iter() {
+            for label in self.nodes[vertex].aliases.iter() {
+                let weight = self.label_weight(vertex, label).unwrap();
+                let best = merged.entry(label.clone()).or_insert(weight);
+                if weight > *best {
+                    *best = weight;
+                }
+            }
+        }
+        for vertex in members.iter() {
+            self.label_weights.remove(vertex);
+        }
+        merged.retain(|_, weight| *weight != DEFAULT_WEIGHT);
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn weighted_labels() {
+        let mut graph = Graph::new();
+        let a = graph.insert("a");
+        let b = graph.insert("b");
+        let c = graph.insert("c");
+        assert!(graph.append_vertex_label_weighted(b, "acme", 0.4));
+        assert!(graph.append_vertex_label_weighted(c, "acme", 0.9));
+        assert!(!graph.append_vertex_label_weighted(42, "acme", 1.0));
+        assert_eq!(graph.label_weight(a, &"a"), Some(1.0));
+        assert_eq!(graph.label_weight(a, &"acme"), None);
+        assert_eq!(graph.best_labeled(&"acme"), Some(c));
+
+        graph.append_vertex_label_weighted(b, "acme", 2.5);
+        assert_eq!(graph.best_labeled(&"acme"), Some(b));
+        graph.remove_vertex_label(&"acme", b);
+        assert_eq!(graph.best_labeled(&"acme"), Some(c));
+        graph.append_label(b, "acme");
+        assert_eq!(graph.label_weight(b, &"acme"), Some(1.0));
+        graph.append_vertex_label_weighted(a, "acme", 0.5);
+        graph.append_vertex_label_weighted(a, "alias", 3.0);
+
+        let ab = graph.merge_vertices(vec![a, b]);
+        assert_eq!(graph.label_weight(ab, &"acme"), Some(1.0));
+        assert_eq!(graph.label_weight(ab, &"alias"), Some(3.0));
+        assert_eq!(graph.best_labeled(&"acme"), Some(ab));
+        graph.remove(ab);
+        assert_eq!(graph.best_labeled(&"acme"), Some(c));
+        assert_eq!(graph.best_labeled(&"nobody"), None);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{Graph, VertexIndex};
+use slab::Slab;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+pub type GraphId = usize;
+
+/// A vertex of one of the graphs owned by a `Workspace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexRef {
+    pub graph: GraphId,
+    pub vertex: VertexIndex,
+}
+
+impl VertexRef {
+    #[inline]
+    pub fn new(graph: GraphId, vertex: VertexIndex) -> Self {
+        VertexRef { graph, vertex }
+    }
+}
+
+/// Several named graphs plus symmetric correspondence links between their
+/// vertices. Links are dropped automatically when either end is removed
+/// through the workspace.
+pub struct Workspace<V: Hash + Eq + Clone> {
+    graphs: Slab<Graph<V>>,
+    names: HashMap<String, GraphId>,
+    links: HashMap<VertexRef, HashSet<VertexRef>>,
+}
+
+impl<V: Hash + Eq + Clone> Default for Workspace<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Hash + Eq + Clone> Workspace<V> {
+    #[inline]
+    pub fn new() -> Self {
+        Workspace {
+            graphs: Slab::new(),
+            names: HashMap::new(),
+            links: HashMap::new(),
+        }
+    }
+
+    /// Adds `graph` under `name`. Returns `None` if the name is taken.
+    pub fn insert(&mut self, name: &str, graph: Graph<V>) -> Option<GraphId> {
+        if self.names.contains_key(name) {
+            return None;
+        }
+        let id = self.graphs.insert(graph);
+        self.names.insert(name.to_string(), id);
+        Some(id)
+    }
+
+    /// Removes a graph together with every link touching it.
+    pub fn remove(&mut self, id: GraphId) -> Option<Graph<V>> {
+        if !self.graphs.contains(id) {
+            return None;
+        }
+        let graph = self.graphs.remove(id);
+        self.names.retain(|_, &mut other| other != id);
+        let doomed: Vec<VertexRef> = self
+            .links
+            .keys()
+            .filter(|r| r.graph == id)
+            .cloned()
+            .collect();
+        for vertex in doomed {
+            self.unlink_all(vertex);
+        }
+        Some(graph)
+    }
+
+    #[inline]
+    pub fn id(&self, name: &str) -> Option<GraphId> {
+        self.names.get(name).cloned()
+    }
+
+    #[inline]
+    pub fn graph(&self, id: GraphId) -> Option<&Graph<V>> {
+        self.graphs.get(id)
+    }
+
+    /// Runs `edit` on a graph and then drops links to vertices it removed.
+    /// Removed indices may be reused by the graph, so graphs edited this way
+    /// should use `IndexAllocation::NeverReuse` to keep links unambiguous.
+    pub fn edit<R, F>(&mut self, id: GraphId, edit: F) -> Option<R>
+    where
+        F: FnOnce(&mut Graph<V>) -> R,
+    {
+        let graph = self.graphs.get_mut(id)?;
+        let result = edit(graph);
+        let graph = &self.graphs[id];
+        let stale: Vec<VertexRef> = self
+            .links
+            .keys()
+            .filter(|r| r.graph == id && !graph.nodes.contains(r.vertex))
+            .cloned()
+            .collect();
+        for vertex in stale {
+            self.unlink_all(vertex);
+        }
+        Some(result)
+    }
+
+    #[inline]
+    pub fn contains(&self, vertex: VertexRef) -> bool {
+        self.graphs
+            .get(vertex.graph)
+            .is_some_and(|graph| graph.nodes.contains(vertex.vertex))
+    }
+
+    /// Removes a vertex from its graph and drops all of its links.
+    pub fn remove_vertex(&mut self, vertex: VertexRef) -> bool {
+        let removed = self
+            .graphs
+            .get_mut(vertex.graph)
+            .is_some_and(|graph| graph.remove(vertex.vertex));
+        if removed {
+            self.unlink_all(vertex);
+        }
+        removed
+    }
+
+    /// Records that `one` and `other` correspond to each other.
+    pub fn link(&mut self, one: VertexRef, other: VertexRef) -> bool {
+        if !(self.contains(one) && self.contains(other)) || one == other {
+            return false;
+        }
+        self.links.entry(one).or_default().insert(other);
+        self.links.entry(other).or_default().insert(one)
+    }
+
+    pub fn unlink(&mut self, one: VertexRef, other: VertexRef) -> bool {
+        let removed = self.detach(one, other);
+        self.detach(other, one) && removed
+    }
+
+    /// Vertices linked to `vertex`, or `None` if it does not exist.
+    pub fn links(&self, vertex: VertexRef) -> Option<Vec<VertexRef>> {
+        if !self.contains(vertex) {
+            return None;
+        }
+        let mut links: Vec<VertexRef> = self
+            .links
+            .get(&vertex)
+            .map(|set| set.iter().cloned().collect())
+            .unwrap_or_default();
+        links.sort();
+        Some(links)
+    }
+
+    /// Vertices of graph `target` linked to `vertex`.
+    #[inline]
+    pub fn links_into(&self, vertex: VertexRef, target: GraphId) -> Option<Vec<VertexIndex>> {
+        self.links(vertex).map(|links| {
+            links
+                .into_iter()
+                .filter(|r| r.graph == target)
+                .map(|r| r.vertex)
+                .collect()
+        })
+    }
+
+    fn detach(&mut self, one: VertexRef, other: VertexRef) -> bool {
+        match self.links.get_mut(&one) {
+            None => false,
+            Some(set) => {
+                let removed = set.remove(&other);
+                if set.is_empty() {
+                    self.links.remove(&one);
+                }
+                removed
+            }
+        }
+    }
+
+    fn unlink_all(&mut self, vertex: VertexRef) {
+        for other in self.links.remove(&vertex).unwrap_or_default() {
+            self.detach(other, vertex);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn workspace_links() {
+        let mut workspace = Workspace::new();
+        let mut source = Graph::new();
+        let a = source.insert("a");
+        let b = source.insert("b");
+        let mut lowered = Graph::with_index_allocation(IndexAllocation::NeverReuse);
+        let a1 = lowered.insert("a.1");
+        let a2 = lowered.insert("a.2");
+
+        let src = workspace.insert("source", source).unwrap();
+        let low = workspace.insert("lowered", lowered).unwrap();
+        assert_eq!(workspace.insert("source", Graph::new()), None);
+        assert_eq!(workspace.id("lowered"), Some(low));
+
+        let (ra, rb) = (VertexRef::new(src, a), VertexRef::new(src, b));
+        assert!(workspace.link(ra, VertexRef::new(low, a1)));
+        assert!(workspace.link(ra, VertexRef::new(low, a2)));
+        assert!(workspace.link(rb, VertexRef::new(low, a2)));
+        assert!(!workspace.link(ra, VertexRef::new(low, 42)));
+        assert_eq!(workspace.links_into(ra, low), Some(vec![a1, a2]));
+
+        workspace.edit(low, |graph| graph.remove(a2));
+        assert_eq!(workspace.links_into(ra, low), Some(vec![a1]));
+        assert_eq!(workspace.links(rb), Some(vec![]));
+
+        assert!(workspace.remove_vertex(VertexRef::new(low, a1)));
+        assert_eq!(workspace.links(ra), Some(vec![]));
+
+        assert!(workspace.link(ra, rb));
+        assert!(workspace.unlink(rb, ra));
+        assert!(workspace.link(ra, rb));
+        assert!(workspace.remove(src).is_some());
+        assert_eq!(workspace.links(ra), None);
+        assert_eq!(workspace.id("source"), None);
+    }
+}